@@ -0,0 +1 @@
+DROP INDEX IF EXISTS room_time_all;
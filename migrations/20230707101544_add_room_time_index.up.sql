@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS room_time_all ON room USING gist ("time");
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS room_ban (
+    room_id uuid NOT NULL,
+    agent_id agent_id NOT NULL,
+    created_by agent_id NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    FOREIGN KEY (room_id) REFERENCES room (id) ON DELETE CASCADE,
+    PRIMARY KEY (room_id, agent_id)
+);
@@ -0,0 +1,11 @@
+ALTER TABLE room DROP COLUMN IF EXISTS lobby;
+
+DELETE FROM agent WHERE status = 'pending';
+
+ALTER TYPE agent_status RENAME TO agent_status_old;
+CREATE TYPE agent_status AS ENUM ('in_progress', 'ready');
+ALTER TABLE agent
+    ALTER COLUMN status DROP DEFAULT,
+    ALTER COLUMN status TYPE agent_status USING status::text::agent_status,
+    ALTER COLUMN status SET DEFAULT 'in_progress';
+DROP TYPE agent_status_old;
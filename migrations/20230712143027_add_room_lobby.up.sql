@@ -0,0 +1,3 @@
+ALTER TYPE agent_status ADD VALUE IF NOT EXISTS 'pending';
+
+ALTER TABLE room ADD COLUMN IF NOT EXISTS lobby boolean NOT NULL DEFAULT false;
@@ -0,0 +1 @@
+ALTER TABLE room ADD COLUMN IF NOT EXISTS co_hosts agent_id[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS room_closing_warning (
+    room_id uuid NOT NULL,
+    threshold integer NOT NULL,
+    closes_at timestamp with time zone NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    FOREIGN KEY (room_id) REFERENCES room (id) ON DELETE CASCADE,
+    PRIMARY KEY (room_id, threshold, closes_at)
+);
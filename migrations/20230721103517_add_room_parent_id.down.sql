@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS room_parent_id_idx;
+ALTER TABLE room DROP COLUMN IF EXISTS parent_id;
@@ -0,0 +1,2 @@
+ALTER TABLE room ADD COLUMN IF NOT EXISTS parent_id uuid NULL REFERENCES room (id) ON DELETE CASCADE;
+CREATE INDEX IF NOT EXISTS room_parent_id_idx ON room (parent_id) WHERE parent_id IS NOT NULL;
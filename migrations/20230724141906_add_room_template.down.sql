@@ -0,0 +1,2 @@
+ALTER TABLE room DROP COLUMN IF EXISTS template_id;
+DROP TABLE IF EXISTS room_template;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS room_template (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    audience text NOT NULL,
+    rtc_sharing_policy rtc_sharing_policy NOT NULL,
+    reserve integer,
+    tags jsonb DEFAULT '{}'::jsonb NOT NULL,
+    groups jsonb,
+    writer_config jsonb,
+    created_by agent_id NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    PRIMARY KEY (id)
+);
+
+ALTER TABLE room ADD COLUMN IF NOT EXISTS template_id uuid NULL REFERENCES room_template (id) ON DELETE SET NULL;
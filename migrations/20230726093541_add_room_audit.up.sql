@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS room_audit (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    room_id uuid NOT NULL,
+    action text NOT NULL,
+    created_by agent_id NOT NULL,
+    before jsonb,
+    after jsonb,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    PRIMARY KEY (id),
+    FOREIGN KEY (room_id) REFERENCES room (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS room_audit_room_id_created_at_idx ON room_audit (room_id, created_at, id);
@@ -0,0 +1,22 @@
+CREATE TYPE attendance_leave_reason AS ENUM ('leave', 'disconnect', 'kick', 'room_close');
+
+CREATE TABLE IF NOT EXISTS attendance (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    room_id uuid NOT NULL,
+    agent_id agent_id NOT NULL,
+    entered_at timestamp with time zone DEFAULT now() NOT NULL,
+    left_at timestamp with time zone,
+    leave_reason attendance_leave_reason,
+
+    PRIMARY KEY (id),
+    FOREIGN KEY (room_id) REFERENCES room (id) ON DELETE CASCADE
+);
+
+-- An agent may have only one open attendance entry per room.
+CREATE UNIQUE INDEX IF NOT EXISTS attendance_room_id_agent_id_open_idx
+    ON attendance (room_id, agent_id)
+    WHERE left_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS attendance_agent_id_open_idx
+    ON attendance (agent_id)
+    WHERE left_at IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS agent_role;
+DROP TYPE IF EXISTS agent_role_kind;
@@ -0,0 +1,12 @@
+CREATE TYPE agent_role_kind AS ENUM ('host', 'co_host', 'speaker', 'viewer');
+
+CREATE TABLE IF NOT EXISTS agent_role (
+    room_id uuid NOT NULL,
+    agent_id agent_id NOT NULL,
+    role agent_role_kind NOT NULL,
+    updated_by agent_id,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    PRIMARY KEY (room_id, agent_id),
+    FOREIGN KEY (room_id) REFERENCES room (id) ON DELETE CASCADE
+);
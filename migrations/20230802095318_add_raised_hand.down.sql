@@ -0,0 +1 @@
+DROP TABLE IF EXISTS raised_hand;
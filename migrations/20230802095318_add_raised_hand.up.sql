@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS raised_hand (
+    room_id uuid NOT NULL,
+    agent_id agent_id NOT NULL,
+    created_at timestamp with time zone DEFAULT clock_timestamp() NOT NULL,
+
+    PRIMARY KEY (room_id, agent_id),
+    FOREIGN KEY (room_id) REFERENCES room (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS raised_hand_room_id_created_at_idx ON raised_hand (room_id, created_at);
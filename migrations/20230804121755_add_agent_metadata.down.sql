@@ -0,0 +1 @@
+ALTER TABLE agent DROP COLUMN IF EXISTS metadata;
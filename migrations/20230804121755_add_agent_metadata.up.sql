@@ -0,0 +1 @@
+ALTER TABLE agent ADD COLUMN IF NOT EXISTS metadata jsonb;
@@ -0,0 +1,33 @@
+DELETE FROM rtc WHERE deleted_at IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION on_rtc_insert() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    CASE (SELECT r.rtc_sharing_policy FROM room AS r WHERE id = NEW.room_id)
+    WHEN 'none' THEN
+        -- RTC creation not allowed.
+        RAISE EXCEPTION 'creating RTC within a room with `none` RTC sharing policy is not allowed';
+    WHEN 'shared' THEN
+        -- Only single RTC allowed in the room.
+        IF (SELECT COUNT(id) FROM rtc WHERE room_id = NEW.room_id) = 0 THEN
+            RETURN NEW;
+        ELSE
+            RAISE EXCEPTION 'creating multiple RTCs within a room with `shared` RTC sharing policy is not allowed';
+        END IF;
+    WHEN 'owned' THEN
+        -- Only single RTC of each kind per agent allowed in the room.
+        IF (
+            SELECT COUNT(id)
+            FROM rtc
+            WHERE room_id = NEW.room_id AND created_by = NEW.created_by AND kind = NEW.kind
+        ) = 0 THEN
+            RETURN NEW;
+        ELSE
+            RAISE EXCEPTION 'creating multiple RTCs of the same kind per agent within a room with `owned` RTC sharing policy is not allowed';
+        END IF;
+    END CASE;
+END;
+$$;
+
+ALTER TABLE rtc DROP COLUMN IF EXISTS deleted_at;
@@ -0,0 +1 @@
+ALTER TABLE agent_connection DROP COLUMN slow_link_at;
@@ -0,0 +1 @@
+ALTER TABLE agent_connection ADD COLUMN slow_link_at timestamptz;
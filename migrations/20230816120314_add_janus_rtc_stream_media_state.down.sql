@@ -0,0 +1,3 @@
+ALTER TABLE janus_rtc_stream
+    DROP COLUMN audio_receiving,
+    DROP COLUMN video_receiving;
@@ -0,0 +1,3 @@
+ALTER TABLE janus_rtc_stream
+    ADD COLUMN audio_receiving boolean,
+    ADD COLUMN video_receiving boolean;
@@ -0,0 +1,3 @@
+ALTER TABLE janus_backend DROP COLUMN transport;
+
+DROP TYPE IF EXISTS janus_transport;
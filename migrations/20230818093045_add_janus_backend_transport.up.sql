@@ -0,0 +1,3 @@
+CREATE TYPE janus_transport AS ENUM ('http', 'websocket');
+
+ALTER TABLE janus_backend ADD COLUMN transport janus_transport NOT NULL DEFAULT 'http';
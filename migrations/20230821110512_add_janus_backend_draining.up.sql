@@ -0,0 +1 @@
+ALTER TABLE janus_backend ADD COLUMN draining boolean NOT NULL DEFAULT false;
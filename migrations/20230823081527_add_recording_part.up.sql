@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS recording_part (
+    rtc_id uuid NOT NULL,
+    backend_id agent_id NOT NULL,
+    status recording_status DEFAULT 'in_progress'::recording_status NOT NULL,
+    mjr_dumps_uris text[],
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    FOREIGN KEY (rtc_id) REFERENCES recording (rtc_id) ON DELETE CASCADE,
+    PRIMARY KEY (rtc_id, backend_id)
+);
+
+INSERT INTO recording_part (rtc_id, backend_id, status, mjr_dumps_uris)
+SELECT recording.rtc_id, room.backend_id, recording.status, recording.mjr_dumps_uris
+FROM recording
+INNER JOIN rtc
+ON rtc.id = recording.rtc_id
+INNER JOIN room
+ON room.id = rtc.room_id
+WHERE room.backend_id IS NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE room DROP COLUMN preferred_region;
+ALTER TABLE janus_backend DROP COLUMN region;
@@ -0,0 +1,2 @@
+ALTER TABLE janus_backend ADD COLUMN region text;
+ALTER TABLE room ADD COLUMN preferred_region text;
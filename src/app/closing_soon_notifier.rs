@@ -0,0 +1,168 @@
+use crate::{
+    app::{context::GlobalContext, error::Error as AppError},
+    config::ClosingSoonConfig,
+    db,
+};
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use svc_agent::mqtt::{
+    Agent, IntoPublishableMessage, OutgoingEvent, OutgoingEventProperties,
+    ShortTermTimingProperties,
+};
+use tokio::{sync::watch, task::JoinHandle, time::MissedTickBehavior};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Serialize)]
+struct RoomClosingSoonEvent {
+    id: db::room::Id,
+    #[serde(with = "ts_seconds")]
+    closes_at: DateTime<Utc>,
+    threshold: i32,
+}
+
+pub fn run(
+    ctx: Arc<dyn GlobalContext + Send + Sync>,
+    mut agent: Agent,
+    config: ClosingSoonConfig,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> JoinHandle<()> {
+    info!("Closing soon notifier started");
+
+    tokio::spawn(async move {
+        let mut check_interval = tokio::time::interval(config.check_interval);
+        check_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = check_interval.tick() => {
+                    let notifications = match collect_notifications(ctx.as_ref(), &config.thresholds).await {
+                        Ok(notifications) => notifications,
+                        Err(err) => {
+                            error!(?err, "Failed to collect closing soon notifications");
+                            AppError::from(err).notify_sentry();
+                            continue;
+                        }
+                    };
+
+                    for notification in notifications {
+                        if let Err(err) = agent.publish_publishable(notification) {
+                            error!(?err, "Failed to publish closing soon notification");
+                        }
+                    }
+                }
+                // Graceful shutdown
+                _ = shutdown_rx.changed() => {
+                    warn!("Closing soon notifier completes its work");
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Builds `room.closing_soon` notifications for rooms which have reached any of the thresholds.
+///
+/// When a room reaches several thresholds at once only the closest one is notified about.
+async fn collect_notifications(
+    ctx: &(dyn GlobalContext + Send + Sync),
+    thresholds: &[i32],
+) -> sqlx::Result<Vec<Box<dyn IntoPublishableMessage + Send + Sync + 'static>>> {
+    let start_timestamp = Utc::now();
+    let mut conn = ctx.db().acquire().await?;
+
+    let mut warnings = db::room_closing_warning::MarkDueQuery::new(thresholds)
+        .execute(&mut conn)
+        .await?;
+
+    warnings.sort_by_key(|w| w.threshold());
+
+    let mut closest_warnings = HashMap::new();
+
+    for warning in warnings {
+        closest_warnings.entry(warning.room_id()).or_insert(warning);
+    }
+
+    let notifications = closest_warnings
+        .into_values()
+        .map(|warning| {
+            let payload = RoomClosingSoonEvent {
+                id: warning.room_id(),
+                closes_at: warning.closes_at(),
+                threshold: warning.threshold(),
+            };
+
+            let timing = ShortTermTimingProperties::until_now(start_timestamp);
+            let props = OutgoingEventProperties::new("room.closing_soon", timing);
+            let path = format!("rooms/{}/events", warning.room_id());
+
+            Box::new(OutgoingEvent::broadcast(payload, props, &path))
+                as Box<dyn IntoPublishableMessage + Send + Sync + 'static>
+        })
+        .collect();
+
+    Ok(notifications)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::{Duration, SubsecRound};
+    use serde_json::Value as JsonValue;
+
+    use crate::test_helpers::{db::TestDb, find_event_by_predicate, parse_messages, prelude::*};
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn notify_closing_rooms_once(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let mut conn = db.get_conn().await;
+        let now = Utc::now().trunc_subsecs(0);
+
+        let insert_room = |closes_in: Duration| {
+            factory::Room::new()
+                .audience(USR_AUDIENCE)
+                .time((
+                    Bound::Included(now - Duration::hours(1)),
+                    Bound::Excluded(now + closes_in),
+                ))
+                .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+        };
+
+        let room_closing_in_minutes = insert_room(Duration::minutes(5)).insert(&mut conn).await;
+        let room_closing_in_seconds = insert_room(Duration::seconds(30)).insert(&mut conn).await;
+        insert_room(Duration::hours(1)).insert(&mut conn).await;
+
+        let context = TestContext::new(db, TestAuthz::new()).await;
+
+        let notifications = collect_notifications(&context, &[600, 60])
+            .await
+            .expect("Failed to collect notifications");
+
+        let messages = parse_messages(Box::new(futures::stream::iter(notifications))).await;
+        assert_eq!(messages.len(), 2);
+
+        for (room, threshold) in [
+            (room_closing_in_minutes, 600),
+            (room_closing_in_seconds, 60),
+        ] {
+            let (payload, evp, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |_, _, topic| {
+                    topic.ends_with(&format!("/rooms/{}/events", room.id()))
+                })
+                .expect("Failed to find room.closing_soon event");
+
+            assert_eq!(evp.label(), "room.closing_soon");
+            assert_eq!(payload["threshold"], threshold);
+        }
+
+        // Each warning is sent only once.
+        let notifications = collect_notifications(&context, &[600, 60])
+            .await
+            .expect("Failed to collect notifications");
+
+        assert!(notifications.is_empty());
+    }
+}
@@ -1,10 +1,16 @@
 use std::sync::Arc;
 
+use anyhow::{anyhow, Context as AnyhowContext};
 use async_trait::async_trait;
-use axum::extract::{Extension, Path, Query};
+use axum::{
+    extract::{Extension, Path, Query},
+    Json,
+};
 
-use serde::Deserialize;
-use svc_agent::mqtt::ResponseStatus;
+use chrono::{serde::ts_seconds, DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Connection;
+use svc_agent::{mqtt::ResponseStatus, Addressable, AgentId};
 use svc_utils::extractors::AgentIdExtractor;
 
 use crate::{
@@ -15,7 +21,7 @@ use crate::{
         service_utils::{RequestParams, Response},
     },
     authz::AuthzObject,
-    db,
+    db::{self, agent_role::Role, room::FindQueryable},
 };
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -27,6 +33,8 @@ pub struct ListRequest {
     room_id: db::room::Id,
     offset: Option<i64>,
     limit: Option<i64>,
+    #[serde(default)]
+    status: Option<db::agent::Status>,
 }
 
 #[derive(Deserialize, Clone, Copy)]
@@ -35,11 +43,33 @@ pub struct Pagination {
     limit: i64,
 }
 
+#[derive(Deserialize, Clone, Copy)]
+pub struct StatusFilter {
+    status: Option<db::agent::Status>,
+}
+
+#[derive(Debug, Serialize)]
+struct RaisedHand {
+    /// 1-based position in the room's raise hand queue.
+    position: usize,
+    #[serde(with = "ts_seconds")]
+    raised_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct AgentListItem {
+    #[serde(flatten)]
+    agent: db::agent::Object,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raised_hand: Option<RaisedHand>,
+}
+
 pub async fn list(
     Extension(ctx): Extension<Arc<AppContext>>,
     AgentIdExtractor(agent_id): AgentIdExtractor,
     Path(room_id): Path<db::room::Id>,
     query: Option<Query<Pagination>>,
+    Query(filter): Query<StatusFilter>,
 ) -> RequestResult {
     tracing::Span::current().record("room_id", &tracing::field::display(room_id));
 
@@ -47,6 +77,7 @@ pub async fn list(
         room_id,
         offset: query.map(|x| x.offset),
         limit: query.map(|x| x.limit),
+        status: filter.status,
     };
     ListHandler::handle(
         &mut ctx.start_message(),
@@ -94,15 +125,38 @@ impl RequestHandler for ListHandler {
             .await?;
         context.metrics().observe_auth(authz_time);
 
-        // Get agents list in the room.
+        // Get agents list in the room. Only ready agents are listed by default,
+        // `pending` status lists the lobby.
         let mut conn = context.get_conn().await?;
-        let agents = db::agent::ListQuery::new()
+        let mut q = db::agent::ListQuery::new()
             .room_id(payload.room_id)
             .offset(payload.offset.unwrap_or(0))
-            .limit(std::cmp::min(payload.limit.unwrap_or(MAX_LIMIT), MAX_LIMIT))
+            .limit(std::cmp::min(payload.limit.unwrap_or(MAX_LIMIT), MAX_LIMIT));
+
+        if let Some(status) = payload.status {
+            q = q.status(status);
+        }
+
+        let agents = q.execute(&mut conn).await?;
+        let queue = db::raised_hand::ListQuery::new(payload.room_id)
             .execute(&mut conn)
             .await?;
 
+        let agents = agents
+            .into_iter()
+            .map(|agent| {
+                let raised_hand = queue
+                    .iter()
+                    .position(|h| h.agent_id() == agent.agent_id())
+                    .map(|idx| RaisedHand {
+                        position: idx + 1,
+                        raised_at: queue[idx].created_at(),
+                    });
+
+                AgentListItem { agent, raised_hand }
+            })
+            .collect::<Vec<_>>();
+
         context
             .metrics()
             .request_duration
@@ -121,144 +175,1342 @@ impl RequestHandler for ListHandler {
 
 ///////////////////////////////////////////////////////////////////////////////
 
-#[cfg(test)]
-mod tests {
-    mod list {
-        use serde::Deserialize;
-        use svc_agent::AgentId;
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    room_id: db::room::Id,
+    agent_id: AgentId,
+    role: Role,
+}
 
-        use crate::test_helpers::{db::TestDb, prelude::*};
+#[derive(Debug, Deserialize)]
+pub struct UpdateRolePayload {
+    role: Role,
+}
 
-        use super::super::*;
+pub async fn update_role(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path((room_id, target_agent_id)): Path<(db::room::Id, AgentId)>,
+    Json(payload): Json<UpdateRolePayload>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
 
-        ///////////////////////////////////////////////////////////////////////////
+    let request = UpdateRoleRequest {
+        room_id,
+        agent_id: target_agent_id,
+        role: payload.role,
+    };
 
-        #[derive(Deserialize)]
-        struct Agent {
-            agent_id: AgentId,
-            room_id: db::room::Id,
-        }
+    UpdateRoleHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
 
-        #[sqlx::test]
-        async fn list_agents(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+pub struct UpdateRoleHandler;
 
-            let mut conn = db.get_conn().await;
+#[async_trait]
+impl RequestHandler for UpdateRoleHandler {
+    type Payload = UpdateRoleRequest;
+    const ERROR_TITLE: &'static str = "Failed to update agent role";
 
-            // Create room and put the agent online.
-            let room = shared_helpers::insert_room(&mut conn).await;
-            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        if payload.role == Role::Host {
+            return Err(anyhow!(
+                "Host role can be assigned only by updating the room"
+            ))
+            .error(AppErrorKind::InvalidPayload);
+        }
 
-            // Allow agent to list agents in the room.
-            let mut authz = TestAuthz::new();
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &room.classroom_id().to_string()],
-                "read",
-            );
+        let (room, current_role, updater_role) = {
+            let mut conn = context.get_conn().await?;
+            let room = helpers::find_room_by_id(
+                payload.room_id,
+                helpers::RoomTimeRequirement::NotClosed,
+                &mut conn,
+            )
+            .await?;
 
-            // Make agent.list request.
-            let mut context = TestContext::new(db, authz).await;
+            let current_role =
+                helpers::find_agent_role(&room, &payload.agent_id, &mut conn).await?;
+            let updater_role =
+                helpers::find_agent_role(&room, reqp.as_agent_id(), &mut conn).await?;
 
-            let payload = ListRequest {
-                room_id: room.id(),
-                offset: None,
-                limit: None,
-            };
+            (room, current_role, updater_role)
+        };
 
-            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
-                .await
-                .expect("Agents listing failed");
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
 
-            // Assert response.
-            let (agents, respp, _) = find_response::<Vec<Agent>>(messages.as_slice());
-            assert_eq!(respp.status(), ResponseStatus::OK);
-            assert_eq!(agents.len(), 1);
-            assert_eq!(&agents[0].agent_id, agent.agent_id());
-            assert_eq!(agents[0].room_id, room.id());
+        if current_role == Role::Host {
+            return Err(anyhow!(
+                "Host role can be changed only by updating the room"
+            ))
+            .error(AppErrorKind::InvalidPayload);
         }
 
-        #[sqlx::test]
-        async fn list_agents_not_authorized(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        // The host manages all the roles, co-hosts manage speakers and viewers.
+        // Anyone else has to be authorized to update the room on the tenant.
+        let is_allowed_by_role = match updater_role {
+            Role::Host => true,
+            Role::CoHost => payload.role != Role::CoHost && current_role != Role::CoHost,
+            _ => false,
+        };
 
-            let room = {
-                let mut conn = db.get_conn().await;
-                shared_helpers::insert_room(&mut conn).await
-            };
+        let maybe_authz_time = if is_allowed_by_role {
+            None
+        } else {
+            let classroom_id = room.classroom_id().to_string();
+            let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
 
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let authz_time = context
+                .authz()
+                .authorize(room.audience().into(), reqp, object, "update".into())
+                .await?;
+            context.metrics().observe_auth(authz_time);
+            Some(authz_time)
+        };
 
-            let payload = ListRequest {
-                room_id: room.id(),
-                offset: None,
-                limit: None,
-            };
+        let (agent_role, maybe_updated_room) = {
+            let mut conn = context.get_conn().await?;
+            let room_id = room.id();
+            let agent_id = payload.agent_id.clone();
+            let updated_by = reqp.as_agent_id().clone();
+            let role = payload.role;
 
-            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
-                .await
-                .expect_err("Unexpected success on agents listing");
+            conn.transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    let agent_role = db::agent_role::UpsertQuery::new(room_id, &agent_id, role)
+                        .updated_by(&updated_by)
+                        .execute(conn)
+                        .await?;
 
-            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
-            assert_eq!(err.kind(), "access_denied");
-        }
+                    // Co-hosts are defined by the room so granting or taking away the role
+                    // updates it. The room is locked not to lose concurrent role updates.
+                    let room = db::room::FindForUpdateQuery::new(room_id)
+                        .execute(conn)
+                        .await?
+                        .context("Room not found")
+                        .error(AppErrorKind::RoomNotFound)?;
 
-        #[sqlx::test]
-        async fn list_agents_closed_room(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+                    let is_co_host = room.co_hosts().contains(&agent_id);
 
-            let room = {
-                let mut conn = db.get_conn().await;
-                // Create closed room.
-                shared_helpers::insert_closed_room(&mut conn).await
-            };
+                    let maybe_co_hosts = match (is_co_host, role) {
+                        (false, Role::CoHost) => {
+                            let mut co_hosts = room.co_hosts().to_vec();
+                            co_hosts.push(agent_id.clone());
+                            Some(co_hosts)
+                        }
+                        (true, role) if role != Role::CoHost => {
+                            let co_hosts = room
+                                .co_hosts()
+                                .iter()
+                                .filter(|id| **id != agent_id)
+                                .cloned()
+                                .collect::<Vec<_>>();
 
-            // Allow agent to list agents in the room.
-            let mut authz = TestAuthz::new();
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &room.classroom_id().to_string()],
-                "read",
-            );
+                            Some(co_hosts)
+                        }
+                        _ => None,
+                    };
 
-            // Make agent.list request.
-            let mut context = TestContext::new(db, authz).await;
+                    let maybe_updated_room = match maybe_co_hosts {
+                        Some(co_hosts) => {
+                            let updated_room = db::room::UpdateQuery::new(room_id)
+                                .co_hosts(Some(&co_hosts))
+                                .execute(conn)
+                                .await?;
 
-            let payload = ListRequest {
-                room_id: room.id(),
-                offset: None,
-                limit: None,
-            };
+                            helpers::audit_room_change(
+                                room_id,
+                                "agent.update_role",
+                                &updated_by,
+                                &room,
+                                &updated_room,
+                                conn,
+                            )
+                            .await?;
 
-            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
-                .await
-                .expect_err("Unexpected success on agents listing");
+                            Some(updated_room)
+                        }
+                        None => None,
+                    };
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_closed");
+                    Ok((agent_role, maybe_updated_room))
+                })
+            })
+            .await?
+        };
+
+        let mut response = Response::new(
+            ResponseStatus::OK,
+            agent_role.clone(),
+            context.start_timestamp(),
+            maybe_authz_time,
+        );
+
+        response.add_notification(
+            "agent.update_role",
+            &format!("rooms/{}/events", payload.room_id),
+            agent_role,
+            context.start_timestamp(),
+        );
+
+        if let Some(room) = maybe_updated_room {
+            response.add_notification(
+                "room.update",
+                &format!("audiences/{}/events", room.audience()),
+                room.clone(),
+                context.start_timestamp(),
+            );
+
+            response.add_notification(
+                "room.update",
+                &format!("rooms/{}/events", room.id()),
+                room,
+                context.start_timestamp(),
+            );
         }
 
-        #[sqlx::test]
-        async fn list_agents_missing_room(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
+        context
+            .metrics()
+            .request_duration
+            .agent_update_role
+            .observe_timestamp(context.start_timestamp());
 
-            let payload = ListRequest {
-                room_id: db::room::Id::random(),
-                offset: None,
-                limit: None,
-            };
+        Ok(response)
+    }
+}
 
-            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
-                .await
-                .expect_err("Unexpected success on agents listing");
+///////////////////////////////////////////////////////////////////////////////
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_not_found");
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HandStatus {
+    Raised,
+    Lowered,
+    Accepted,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HandUpdateEvent {
+    id: db::room::Id,
+    agent_id: AgentId,
+    status: HandStatus,
+    queue: Vec<db::raised_hand::Object>,
+}
+
+/// Lets hosts and co-hosts manage the queue on their own, anyone else
+/// has to be authorized to update the room on the tenant.
+async fn authorize_hand_moderation<C: Context + Send + Sync>(
+    context: &mut C,
+    room: &db::room::Object,
+    reqp: RequestParams<'_>,
+) -> Result<Option<Duration>, AppError> {
+    if helpers::is_moderator(room, reqp.as_agent_id()) {
+        return Ok(None);
+    }
+
+    let classroom_id = room.classroom_id().to_string();
+    let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+
+    let authz_time = context
+        .authz()
+        .authorize(room.audience().into(), reqp, object, "update".into())
+        .await?;
+    context.metrics().observe_auth(authz_time);
+    Ok(Some(authz_time))
+}
+
+fn hand_update_response<C: Context>(
+    context: &C,
+    room_id: db::room::Id,
+    agent_id: AgentId,
+    status: HandStatus,
+    queue: Vec<db::raised_hand::Object>,
+    maybe_authz_time: Option<Duration>,
+) -> Response {
+    let mut response = Response::new(
+        ResponseStatus::OK,
+        queue.clone(),
+        context.start_timestamp(),
+        maybe_authz_time,
+    );
+
+    response.add_notification(
+        "agent.hand_update",
+        &format!("rooms/{room_id}/events"),
+        HandUpdateEvent {
+            id: room_id,
+            agent_id,
+            status,
+            queue,
+        },
+        context.start_timestamp(),
+    );
+
+    response
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct RaiseHandRequest {
+    room_id: db::room::Id,
+}
+
+pub async fn raise_hand(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    RaiseHandHandler::handle(
+        &mut ctx.start_message(),
+        RaiseHandRequest { room_id },
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct RaiseHandHandler;
+
+#[async_trait]
+impl RequestHandler for RaiseHandHandler {
+    type Payload = RaiseHandRequest;
+    const ERROR_TITLE: &'static str = "Failed to raise hand";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let queue = {
+            let mut conn = context.get_conn().await?;
+            let room = helpers::find_room_by_id(
+                payload.room_id,
+                helpers::RoomTimeRequirement::Open,
+                &mut conn,
+            )
+            .await?;
+
+            helpers::check_room_presence(&room, reqp.as_agent_id(), &mut conn).await?;
+
+            db::raised_hand::InsertQuery::new(room.id(), reqp.as_agent_id())
+                .execute(&mut conn)
+                .await?;
+
+            db::raised_hand::ListQuery::new(room.id())
+                .execute(&mut conn)
+                .await?
+        };
+
+        let response = hand_update_response(
+            context,
+            payload.room_id,
+            reqp.as_agent_id().to_owned(),
+            HandStatus::Raised,
+            queue,
+            None,
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .agent_raise_hand
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct LowerHandRequest {
+    room_id: db::room::Id,
+    /// Lowering someone else's hand is available for moderators only.
+    agent_id: Option<AgentId>,
+}
+
+pub async fn lower_hand(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    LowerHandHandler::handle(
+        &mut ctx.start_message(),
+        LowerHandRequest {
+            room_id,
+            agent_id: None,
+        },
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub async fn lower_agent_hand(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path((room_id, target_agent_id)): Path<(db::room::Id, AgentId)>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    LowerHandHandler::handle(
+        &mut ctx.start_message(),
+        LowerHandRequest {
+            room_id,
+            agent_id: Some(target_agent_id),
+        },
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct LowerHandHandler;
+
+#[async_trait]
+impl RequestHandler for LowerHandHandler {
+    type Payload = LowerHandRequest;
+    const ERROR_TITLE: &'static str = "Failed to lower hand";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.room_id,
+                helpers::RoomTimeRequirement::NotClosed,
+                &mut conn,
+            )
+            .await?
+        };
+
+        let agent_id = payload
+            .agent_id
+            .unwrap_or_else(|| reqp.as_agent_id().to_owned());
+
+        let maybe_authz_time = if &agent_id == reqp.as_agent_id() {
+            None
+        } else {
+            authorize_hand_moderation(context, &room, reqp).await?
+        };
+
+        let queue = {
+            let mut conn = context.get_conn().await?;
+
+            let row_count = db::raised_hand::DeleteQuery::new(room.id(), &agent_id)
+                .execute(&mut conn)
+                .await?;
+
+            if row_count < 1 {
+                return Err(anyhow!("The agent's hand is not raised"))
+                    .error(AppErrorKind::HandNotRaised);
+            }
+
+            db::raised_hand::ListQuery::new(room.id())
+                .execute(&mut conn)
+                .await?
+        };
+
+        let response = hand_update_response(
+            context,
+            room.id(),
+            agent_id,
+            HandStatus::Lowered,
+            queue,
+            maybe_authz_time,
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .agent_lower_hand
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptHandRequest {
+    room_id: db::room::Id,
+    agent_id: AgentId,
+}
+
+pub async fn accept_hand(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path((room_id, target_agent_id)): Path<(db::room::Id, AgentId)>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    AcceptHandHandler::handle(
+        &mut ctx.start_message(),
+        AcceptHandRequest {
+            room_id,
+            agent_id: target_agent_id,
+        },
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct AcceptHandHandler;
+
+#[async_trait]
+impl RequestHandler for AcceptHandHandler {
+    type Payload = AcceptHandRequest;
+    const ERROR_TITLE: &'static str = "Failed to accept hand";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.room_id,
+                helpers::RoomTimeRequirement::NotClosed,
+                &mut conn,
+            )
+            .await?
+        };
+
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        let maybe_authz_time = authorize_hand_moderation(context, &room, reqp).await?;
+
+        // In webinars speakers granted by a moderator may publish without the tenant
+        // authorization so the accepted agent becomes one unless it's a moderator already.
+        let grants_speaker = room.rtc_sharing_policy() == db::rtc::SharingPolicy::Shared
+            && !helpers::is_moderator(&room, &payload.agent_id);
+
+        let (queue, maybe_agent_role) = {
+            let mut conn = context.get_conn().await?;
+            let room_id = room.id();
+            let agent_id = payload.agent_id.clone();
+            let moderator_id = reqp.as_agent_id().clone();
+
+            // The hand must not be dropped without granting the role.
+            conn.transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    let row_count = db::raised_hand::DeleteQuery::new(room_id, &agent_id)
+                        .execute(conn)
+                        .await?;
+
+                    if row_count < 1 {
+                        return Err(anyhow!("The agent's hand is not raised"))
+                            .error(AppErrorKind::HandNotRaised);
+                    }
+
+                    let maybe_agent_role = if grants_speaker {
+                        let current_role = db::agent_role::FindQuery::new(room_id, &agent_id)
+                            .execute(conn)
+                            .await?;
+
+                        match current_role {
+                            Some(r) if r.role() == Role::Speaker && r.updated_by().is_some() => {
+                                None
+                            }
+                            _ => {
+                                let agent_role = db::agent_role::UpsertQuery::new(
+                                    room_id,
+                                    &agent_id,
+                                    Role::Speaker,
+                                )
+                                .updated_by(&moderator_id)
+                                .execute(conn)
+                                .await?;
+
+                                Some(agent_role)
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                    let queue = db::raised_hand::ListQuery::new(room_id)
+                        .execute(conn)
+                        .await?;
+
+                    Ok((queue, maybe_agent_role))
+                })
+            })
+            .await?
+        };
+
+        let mut response = hand_update_response(
+            context,
+            room.id(),
+            payload.agent_id,
+            HandStatus::Accepted,
+            queue,
+            maybe_authz_time,
+        );
+
+        if let Some(agent_role) = maybe_agent_role {
+            response.add_notification(
+                "agent.update_role",
+                &format!("rooms/{}/events", room.id()),
+                agent_role,
+                context.start_timestamp(),
+            );
+        }
+
+        context
+            .metrics()
+            .request_duration
+            .agent_accept_hand
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    mod list {
+        use serde::Deserialize;
+        use svc_agent::AgentId;
+
+        use crate::test_helpers::{db::TestDb, prelude::*};
+
+        use super::super::*;
+
+        ///////////////////////////////////////////////////////////////////////////
+
+        #[derive(Deserialize)]
+        struct Agent {
+            agent_id: AgentId,
+            room_id: db::room::Id,
+        }
+
+        #[sqlx::test]
+        async fn list_agents(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let mut conn = db.get_conn().await;
+
+            // Create room and put the agent online.
+            let room = shared_helpers::insert_room(&mut conn).await;
+            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+
+            // Allow agent to list agents in the room.
+            let mut authz = TestAuthz::new();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &room.classroom_id().to_string()],
+                "read",
+            );
+
+            // Make agent.list request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = ListRequest {
+                room_id: room.id(),
+                offset: None,
+                limit: None,
+                status: None,
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Agents listing failed");
+
+            // Assert response.
+            let (agents, respp, _) = find_response::<Vec<Agent>>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(agents.len(), 1);
+            assert_eq!(&agents[0].agent_id, agent.agent_id());
+            assert_eq!(agents[0].room_id, room.id());
+        }
+
+        #[sqlx::test]
+        async fn list_pending_agents(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let pending_agent = TestAgent::new("web", "user456", USR_AUDIENCE);
+
+            let mut conn = db.get_conn().await;
+
+            // Create room with one agent online and another one waiting in the lobby.
+            let room = shared_helpers::insert_room(&mut conn).await;
+            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+
+            factory::Agent::new()
+                .room_id(room.id())
+                .agent_id(pending_agent.agent_id())
+                .status(db::agent::Status::Pending)
+                .insert(&mut conn)
+                .await;
+
+            // Allow agent to list agents in the room.
+            let mut authz = TestAuthz::new();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &room.classroom_id().to_string()],
+                "read",
+            );
+
+            // Make agent.list request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = ListRequest {
+                room_id: room.id(),
+                offset: None,
+                limit: None,
+                status: Some(db::agent::Status::Pending),
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Agents listing failed");
+
+            // Assert response.
+            let (agents, respp, _) = find_response::<Vec<Agent>>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(agents.len(), 1);
+            assert_eq!(&agents[0].agent_id, pending_agent.agent_id());
+        }
+
+        #[sqlx::test]
+        async fn list_agents_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = ListRequest {
+                room_id: room.id(),
+                offset: None,
+                limit: None,
+                status: None,
+            };
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on agents listing");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn list_agents_closed_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                // Create closed room.
+                shared_helpers::insert_closed_room(&mut conn).await
+            };
+
+            // Allow agent to list agents in the room.
+            let mut authz = TestAuthz::new();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &room.classroom_id().to_string()],
+                "read",
+            );
+
+            // Make agent.list request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = ListRequest {
+                room_id: room.id(),
+                offset: None,
+                limit: None,
+                status: None,
+            };
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on agents listing");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_closed");
+        }
+
+        #[sqlx::test]
+        async fn list_agents_missing_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = ListRequest {
+                room_id: db::room::Id::random(),
+                offset: None,
+                limit: None,
+                status: None,
+            };
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on agents listing");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_not_found");
+        }
+    }
+
+    mod update_role {
+        use serde_json::{json, Value as JsonValue};
+
+        use crate::{
+            app::endpoint::room,
+            db::{agent_role::FindQuery as AgentRoleFindQuery, room::FindQueryable},
+            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn update_role_by_host(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                let room = db::room::UpdateQuery::new(room.id())
+                    .host(Some(host.agent_id()))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to update room");
+
+                room
+            };
+
+            // The host doesn't need to be authorized.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::Viewer,
+            };
+
+            let messages = handle_request::<UpdateRoleHandler>(&mut context, &host, payload)
+                .await
+                .expect("Agent role update failed");
+
+            let (_, respp, _) = find_response::<JsonValue>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+
+            let (payload, evp, topic) = find_event::<JsonValue>(messages.as_slice());
+            assert!(topic.ends_with(&format!("/rooms/{}/events", room.id())));
+            assert_eq!(evp.label(), "agent.update_role");
+            assert_eq!(payload["role"], "viewer");
+
+            let mut conn = context.get_conn().await.expect("Failed to get conn");
+
+            let agent_role = AgentRoleFindQuery::new(room.id(), agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find agent role")
+                .expect("Agent role not found");
+
+            assert_eq!(agent_role.role(), Role::Viewer);
+        }
+
+        #[sqlx::test]
+        async fn assign_and_take_away_co_host_role(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                db::room::UpdateQuery::new(room.id())
+                    .host(Some(host.agent_id()))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to update room")
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::CoHost,
+            };
+
+            let messages = handle_request::<UpdateRoleHandler>(&mut context, &host, payload)
+                .await
+                .expect("Agent role update failed");
+
+            let (event, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, topic| {
+                    evp.label() == "room.update" && topic.ends_with("/events")
+                })
+                .expect("Failed to find room.update event");
+
+            assert_eq!(event["co_hosts"], json!([agent.agent_id()]));
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::Speaker,
+            };
+
+            handle_request::<UpdateRoleHandler>(&mut context, &host, payload)
+                .await
+                .expect("Agent role update failed");
+
+            let mut conn = context.get_conn().await.expect("Failed to get conn");
+
+            let room = db::room::FindQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find room")
+                .expect("Room not found");
+
+            assert!(room.co_hosts().is_empty());
+        }
+
+        #[sqlx::test]
+        async fn update_role_by_former_host(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let admin = TestAgent::new("web", "admin", USR_AUDIENCE);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let new_host = TestAgent::new("web", "new-host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                db::room::UpdateQuery::new(room.id())
+                    .host(Some(host.agent_id()))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to update room")
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                admin.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            let mut context = TestContext::new(db, authz).await;
+
+            // Hand the host role over to another agent.
+            let payload = serde_json::from_value::<room::UpdateRequest>(json!({
+                "id": room.id(),
+                "host": new_host.agent_id(),
+            }))
+            .expect("Failed to build room update request");
+
+            handle_request::<room::UpdateHandler>(&mut context, &admin, payload)
+                .await
+                .expect("Room update failed");
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::Viewer,
+            };
+
+            let err = handle_request::<UpdateRoleHandler>(&mut context, &host, payload)
+                .await
+                .expect_err("Unexpected success on agent role update");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::Viewer,
+            };
+
+            handle_request::<UpdateRoleHandler>(&mut context, &new_host, payload)
+                .await
+                .expect("Agent role update failed");
+        }
+
+        #[sqlx::test]
+        async fn co_host_promotes_to_co_host_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let co_host = TestAgent::new("web", "co-host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                let co_hosts = vec![co_host.agent_id().to_owned()];
+
+                let room = db::room::UpdateQuery::new(room.id())
+                    .co_hosts(Some(&co_hosts))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to update room");
+
+                room
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::CoHost,
+            };
+
+            let err = handle_request::<UpdateRoleHandler>(&mut context, &co_host, payload)
+                .await
+                .expect_err("Unexpected success on agent role update");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn assign_host_role(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = UpdateRoleRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                role: Role::Host,
+            };
+
+            let err = handle_request::<UpdateRoleHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on agent role update");
+
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+        }
+    }
+
+    mod hand {
+        use serde_json::Value as JsonValue;
+
+        use crate::{
+            db::{
+                agent_role::{
+                    FindQuery as AgentRoleFindQuery, UpsertQuery as AgentRoleUpsertQuery,
+                },
+                raised_hand::{InsertQuery as RaisedHandInsertQuery, Object as RaisedHandObject},
+            },
+            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn raise_hand(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let other_agent = TestAgent::new("web", "user456", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+                shared_helpers::insert_agent(&mut conn, other_agent.agent_id(), room.id()).await;
+
+                RaisedHandInsertQuery::new(room.id(), other_agent.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to raise hand");
+
+                room
+            };
+
+            let mut authz = TestAuthz::new();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &room.classroom_id().to_string()],
+                "read",
+            );
+
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = RaiseHandRequest { room_id: room.id() };
+
+            let messages = handle_request::<RaiseHandHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Hand raising failed");
+
+            // The agent is queued after the one who has raised the hand earlier.
+            let (queue, respp, _) = find_response::<Vec<RaisedHandObject>>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(queue.len(), 2);
+            assert_eq!(queue[0].agent_id(), other_agent.agent_id());
+            assert_eq!(queue[1].agent_id(), agent.agent_id());
+
+            let (event, evp, topic) = find_event::<HandUpdateEvent>(messages.as_slice());
+            assert!(topic.ends_with(&format!("/rooms/{}/events", room.id())));
+            assert_eq!(evp.label(), "agent.hand_update");
+            assert_eq!(&event.agent_id, agent.agent_id());
+            assert_eq!(event.status, HandStatus::Raised);
+
+            // The queue is returned by agent.list.
+            let payload = ListRequest {
+                room_id: room.id(),
+                offset: None,
+                limit: None,
+                status: None,
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Agents listing failed");
+
+            let (agents, _, _) = find_response::<Vec<JsonValue>>(messages.as_slice());
+            assert_eq!(agents.len(), 2);
+
+            let listed_agent = agents
+                .iter()
+                .find(|a| a["agent_id"] == agent.agent_id().to_string())
+                .expect("Agent not listed");
+
+            assert_eq!(listed_agent["raised_hand"]["position"], 2);
+        }
+
+        #[sqlx::test]
+        async fn accept_hand_in_webinar(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                let room = db::room::UpdateQuery::new(room.id())
+                    .host(Some(host.agent_id()))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to update room");
+
+                AgentRoleUpsertQuery::new(room.id(), agent.agent_id(), Role::Viewer)
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to assign agent role");
+
+                RaisedHandInsertQuery::new(room.id(), agent.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to raise hand");
+
+                room
+            };
+
+            // The host doesn't need to be authorized.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = AcceptHandRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+            };
+
+            let messages = handle_request::<AcceptHandHandler>(&mut context, &host, payload)
+                .await
+                .expect("Hand accepting failed");
+
+            let (queue, _, _) = find_response::<Vec<RaisedHandObject>>(messages.as_slice());
+            assert!(queue.is_empty());
+
+            let (event, _, _) =
+                find_event_by_predicate::<HandUpdateEvent, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "agent.hand_update"
+                })
+                .expect("Failed to find agent.hand_update event");
+
+            assert_eq!(event.status, HandStatus::Accepted);
+
+            let (event, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "agent.update_role"
+                })
+                .expect("Failed to find agent.update_role event");
+
+            assert_eq!(event["role"], "speaker");
+
+            // The agent is allowed to speak now.
+            let mut conn = context.get_conn().await.expect("Failed to get conn");
+
+            let agent_role = AgentRoleFindQuery::new(room.id(), agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find agent role")
+                .expect("Agent role not found");
+
+            assert_eq!(agent_role.role(), Role::Speaker);
+            assert_eq!(agent_role.updated_by(), Some(host.agent_id()));
+        }
+
+        #[sqlx::test]
+        async fn accept_hand_of_default_speaker(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                let room = db::room::UpdateQuery::new(room.id())
+                    .host(Some(host.agent_id()))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to update room");
+
+                // The role assigned on enter doesn't allow to publish without the authorization.
+                AgentRoleUpsertQuery::new(room.id(), agent.agent_id(), Role::Speaker)
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to assign agent role");
+
+                RaisedHandInsertQuery::new(room.id(), agent.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to raise hand");
+
+                room
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = AcceptHandRequest {
+                room_id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+            };
+
+            let messages = handle_request::<AcceptHandHandler>(&mut context, &host, payload)
+                .await
+                .expect("Hand accepting failed");
+
+            let (event, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "agent.update_role"
+                })
+                .expect("Failed to find agent.update_role event");
+
+            assert_eq!(event["role"], "speaker");
+            assert_eq!(event["updated_by"], host.agent_id().to_string());
+        }
+
+        #[sqlx::test]
+        async fn lower_someone_else_hand_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let other_agent = TestAgent::new("web", "user456", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                RaisedHandInsertQuery::new(room.id(), other_agent.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to raise hand");
+
+                room
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = LowerHandRequest {
+                room_id: room.id(),
+                agent_id: Some(other_agent.agent_id().to_owned()),
+            };
+
+            let err = handle_request::<LowerHandHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on hand lowering");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn lower_hand_not_raised(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = LowerHandRequest {
+                room_id: room.id(),
+                agent_id: None,
+            };
+
+            let err = handle_request::<LowerHandHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on hand lowering");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "hand_not_raised");
         }
     }
 }
@@ -41,7 +41,7 @@ impl State {
         let configs = rtc_reader_configs
             .iter()
             .map(|(rtc_reader_config, rtc)| {
-                StateConfigItem::new(rtc.created_by().to_owned())
+                StateConfigItem::new(rtc.created_by().to_owned(), rtc.kind())
                     .receive_video(rtc_reader_config.receive_video())
                     .receive_audio(rtc_reader_config.receive_audio())
             })
@@ -49,19 +49,29 @@ impl State {
 
         Self { room_id, configs }
     }
+
+    fn only_agents(self, agent_ids: &[AgentId]) -> Vec<StateConfigItem> {
+        self.configs
+            .into_iter()
+            .filter(|c| agent_ids.contains(&c.agent_id))
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StateConfigItem {
     agent_id: AgentId,
+    #[serde(default)]
+    kind: db::rtc::Kind,
     receive_video: Option<bool>,
     receive_audio: Option<bool>,
 }
 
 impl StateConfigItem {
-    fn new(agent_id: AgentId) -> Self {
+    fn new(agent_id: AgentId, kind: db::rtc::Kind) -> Self {
         Self {
             agent_id,
+            kind,
             receive_video: None,
             receive_audio: None,
         }
@@ -186,6 +196,16 @@ impl RequestHandler for UpdateHandler {
                         .filter_by_agent(&agent_id);
                     let group_agents = groups.iter().flat_map(|i| i.agents()).collect::<Vec<_>>();
 
+                    let audited_agent_ids = configs
+                        .iter()
+                        .map(|c| c.agent_id.clone())
+                        .collect::<Vec<_>>();
+
+                    let configs_before =
+                        db::rtc_reader_config::ListWithRtcQuery::new(room_id, &[&agent_id])
+                            .execute(conn)
+                            .await?;
+
                     // Find RTCs owned by agents.
                     let agent_ids = configs.iter().map(|c| &c.agent_id).collect::<Vec<_>>();
 
@@ -197,15 +217,19 @@ impl RequestHandler for UpdateHandler {
 
                     let agents_to_rtcs = rtcs
                         .iter()
-                        .map(|rtc| (rtc.created_by(), rtc.id()))
+                        .map(|rtc| ((rtc.created_by(), rtc.kind()), rtc.id()))
                         .collect::<HashMap<_, _>>();
 
                     // Create or update the config.
                     for state_config_item in configs {
                         let rtc_id = agents_to_rtcs
-                            .get(&state_config_item.agent_id)
+                            .get(&(&state_config_item.agent_id, state_config_item.kind))
                             .ok_or_else(|| {
-                                anyhow!("{} has no owned RTC", state_config_item.agent_id)
+                                anyhow!(
+                                    "{} has no owned {:?} RTC",
+                                    state_config_item.agent_id,
+                                    state_config_item.kind
+                                )
                             })
                             .error(AppErrorKind::InvalidPayload)?;
 
@@ -236,6 +260,17 @@ impl RequestHandler for UpdateHandler {
                             .execute(conn)
                             .await?;
 
+                    helpers::audit_room_change(
+                        room_id,
+                        "agent_reader_config.update",
+                        &agent_id,
+                        &State::new(room_id, &configs_before).only_agents(&audited_agent_ids),
+                        &State::new(room_id, &rtc_reader_configs_with_rtcs)
+                            .only_agents(&audited_agent_ids),
+                        conn,
+                    )
+                    .await?;
+
                     Ok(rtc_reader_configs_with_rtcs)
                 })
             })
@@ -466,11 +501,13 @@ mod tests {
                 configs: vec![
                     StateConfigItem {
                         agent_id: agent2.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         receive_video: Some(true),
                         receive_audio: Some(false),
                     },
                     StateConfigItem {
                         agent_id: agent3.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         receive_video: Some(false),
                         receive_audio: Some(false),
                     },
@@ -511,11 +548,13 @@ mod tests {
                 configs: vec![
                     StateConfigItem {
                         agent_id: agent4.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         receive_video: Some(true),
                         receive_audio: Some(true),
                     },
                     StateConfigItem {
                         agent_id: agent3.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         receive_video: None,
                         receive_audio: Some(true),
                     },
@@ -576,6 +615,7 @@ mod tests {
 
                     StateConfigItem {
                         agent_id: agent.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         receive_video: Some(false),
                         receive_audio: Some(true),
                     }
@@ -636,6 +676,7 @@ mod tests {
                 room_id: room.id(),
                 configs: vec![StateConfigItem {
                     agent_id: agent2.agent_id().to_owned(),
+                    kind: db::rtc::Kind::Camera,
                     receive_video: Some(false),
                     receive_audio: Some(true),
                 }],
@@ -851,6 +892,7 @@ mod tests {
                 room_id: room.id(),
                 configs: vec![StateConfigItem {
                     agent_id: agent2.agent_id().to_owned(),
+                    kind: db::rtc::Kind::Camera,
                     receive_video: Some(true),
                     receive_audio: Some(false),
                 }],
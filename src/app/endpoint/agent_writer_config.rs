@@ -53,7 +53,7 @@ impl State {
         let configs = rtc_writer_configs_with_rtcs
             .iter()
             .map(|(rtc_writer_config, rtc)| {
-                let mut config_item = StateConfigItem::new(rtc.created_by().to_owned())
+                let mut config_item = StateConfigItem::new(rtc.created_by().to_owned(), rtc.kind())
                     .send_video(rtc_writer_config.send_video())
                     .send_audio(rtc_writer_config.send_audio());
 
@@ -76,11 +76,20 @@ impl State {
             updated_at_ns: updated_at,
         }
     }
+
+    fn only_agents(self, agent_ids: &[AgentId]) -> Vec<StateConfigItem> {
+        self.configs
+            .into_iter()
+            .filter(|c| agent_ids.contains(&c.agent_id))
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StateConfigItem {
     agent_id: AgentId,
+    #[serde(default)]
+    kind: db::rtc::Kind,
     send_video: Option<bool>,
     send_audio: Option<bool>,
     video_remb: Option<u32>,
@@ -89,9 +98,10 @@ pub struct StateConfigItem {
 }
 
 impl StateConfigItem {
-    fn new(agent_id: AgentId) -> Self {
+    fn new(agent_id: AgentId, kind: db::rtc::Kind) -> Self {
         Self {
             agent_id,
+            kind,
             send_video: None,
             send_audio: None,
             video_remb: None,
@@ -178,7 +188,7 @@ impl RequestHandler for UpdateHandler {
                 .error(AppErrorKind::InvalidPayload)?;
         }
 
-        let room = {
+        let (room, is_moderator) = {
             let mut conn = context.get_conn().await?;
             let room = helpers::find_room_by_id(
                 payload.room_id,
@@ -195,8 +205,9 @@ impl RequestHandler for UpdateHandler {
             }
 
             helpers::check_room_presence(&room, reqp.as_agent_id(), &mut conn).await?;
+            let is_moderator = helpers::is_moderator(&room, reqp.as_agent_id());
 
-            room
+            (room, is_moderator)
         };
 
         tracing::Span::current().record(
@@ -205,10 +216,14 @@ impl RequestHandler for UpdateHandler {
         );
 
         // Authorize agent writer config updating on the tenant.
-        let is_only_owned_config =
-            payload.configs.len() == 1 && &payload.configs[0].agent_id == reqp.as_agent_id();
+        let is_only_owned_config = !payload.configs.is_empty()
+            && payload
+                .configs
+                .iter()
+                .all(|c| &c.agent_id == reqp.as_agent_id());
 
-        let maybe_authz_time = if is_only_owned_config {
+        // Hosts and co-hosts manage the room by themselves.
+        let maybe_authz_time = if is_only_owned_config || is_moderator {
             None
         } else {
             let object = AuthzObject::new(&["classrooms", &room.classroom_id().to_string()]);
@@ -237,6 +252,16 @@ impl RequestHandler for UpdateHandler {
 
         conn.transaction::<_, _, AppError>(|conn| {
             Box::pin(async move {
+                let audited_agent_ids = payload
+                    .configs
+                    .iter()
+                    .map(|c| c.agent_id.clone())
+                    .collect::<Vec<_>>();
+
+                let configs_before = db::rtc_writer_config::ListWithRtcQuery::new(room_id)
+                    .execute(conn)
+                    .await?;
+
                 // Find RTCs owned by agents.
                 let agent_ids = payload
                     .configs
@@ -252,14 +277,20 @@ impl RequestHandler for UpdateHandler {
 
                 let agents_to_rtcs = rtcs
                     .iter()
-                    .map(|rtc| (rtc.created_by(), rtc.id()))
+                    .map(|rtc| ((rtc.created_by(), rtc.kind()), rtc.id()))
                     .collect::<HashMap<_, _>>();
 
                 // Create or update the config.
                 for state_config_item in payload.configs {
                     let rtc_id = agents_to_rtcs
-                        .get(&state_config_item.agent_id)
-                        .ok_or_else(|| anyhow!("{} has no owned RTC", state_config_item.agent_id))
+                        .get(&(&state_config_item.agent_id, state_config_item.kind))
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "{} has no owned {:?} RTC",
+                                state_config_item.agent_id,
+                                state_config_item.kind
+                            )
+                        })
                         .error(AppErrorKind::InvalidPayload)?;
 
                     let mut q = db::rtc_writer_config::UpsertQuery::new(*rtc_id);
@@ -289,6 +320,21 @@ impl RequestHandler for UpdateHandler {
                         snapshot_q.execute(conn).await?;
                     }
                 }
+
+                let configs_after = db::rtc_writer_config::ListWithRtcQuery::new(room_id)
+                    .execute(conn)
+                    .await?;
+
+                helpers::audit_room_change(
+                    room_id,
+                    "agent_writer_config.update",
+                    &agent_id,
+                    &State::new(room_id, &configs_before).only_agents(&audited_agent_ids),
+                    &State::new(room_id, &configs_after).only_agents(&audited_agent_ids),
+                    conn,
+                )
+                .await?;
+
                 Ok(())
             })
         })
@@ -503,6 +549,7 @@ mod tests {
                 configs: vec![
                     StateConfigItem {
                         agent_id: agent2.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         send_video: Some(true),
                         send_audio: Some(false),
                         video_remb: Some(300_000),
@@ -510,6 +557,7 @@ mod tests {
                     },
                     StateConfigItem {
                         agent_id: agent3.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         send_video: Some(false),
                         send_audio: Some(false),
                         video_remb: None,
@@ -595,6 +643,7 @@ mod tests {
                 configs: vec![
                     StateConfigItem {
                         agent_id: agent4.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         send_video: Some(true),
                         send_audio: Some(true),
                         video_remb: Some(1_000_000),
@@ -602,6 +651,7 @@ mod tests {
                     },
                     StateConfigItem {
                         agent_id: agent3.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         send_video: None,
                         send_audio: Some(true),
                         video_remb: None,
@@ -724,6 +774,49 @@ mod tests {
             Ok(())
         }
 
+        #[sqlx::test]
+        async fn update_by_co_host_without_authz(pool: sqlx::PgPool) -> std::io::Result<()> {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user1", USR_AUDIENCE);
+
+            let mut conn = db.get_conn().await;
+
+            // Insert a room with the agent being a co-host.
+            let room = factory::Room::new()
+                .audience(USR_AUDIENCE)
+                .time((Bound::Included(Utc::now()), Bound::Unbounded))
+                .rtc_sharing_policy(RtcSharingPolicy::Owned)
+                .insert(&mut conn)
+                .await;
+
+            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+
+            let co_hosts = vec![agent.agent_id().to_owned()];
+
+            db::room::UpdateQuery::new(room.id())
+                .co_hosts(Some(&co_hosts))
+                .execute(&mut conn)
+                .await
+                .expect("Failed to update room");
+
+            // Make agent_writer_config.update request.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = State {
+                updated_at_ns: Some(Utc::now()),
+                room_id: room.id(),
+                configs: vec![],
+            };
+
+            let messages = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Agent writer config update failed");
+
+            let (_, respp, _) = find_response::<State>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            Ok(())
+        }
+
         #[sqlx::test]
         async fn too_many_config_items(pool: sqlx::PgPool) -> std::io::Result<()> {
             // Make agent_writer_config.update request.
@@ -737,6 +830,7 @@ mod tests {
 
                     StateConfigItem {
                         agent_id: agent.agent_id().to_owned(),
+                        kind: db::rtc::Kind::Camera,
                         send_video: Some(false),
                         send_audio: Some(true),
                         video_remb: Some(300_000),
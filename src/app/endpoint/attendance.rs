@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::{Extension, Path};
+use serde::Deserialize;
+use svc_agent::mqtt::ResponseStatus;
+use svc_utils::extractors::AgentIdExtractor;
+
+use crate::{
+    app::{
+        context::{AppContext, Context},
+        endpoint::prelude::*,
+        metrics::HistogramExt,
+        service_utils::{RequestParams, Response},
+    },
+    authz::AuthzObject,
+    db,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct ListRequest {
+    room_id: db::room::Id,
+}
+
+pub async fn list(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = ListRequest { room_id };
+
+    ListHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct ListHandler;
+
+#[async_trait]
+impl RequestHandler for ListHandler {
+    type Payload = ListRequest;
+    const ERROR_TITLE: &'static str = "Failed to list attendance";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.room_id,
+                helpers::RoomTimeRequirement::Any,
+                &mut conn,
+            )
+            .await?
+        };
+
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        // Authorize attendance listing on the tenant.
+        let classroom_id = room.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id, "attendance"]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(room.audience().into(), reqp, object, "list".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let summary = {
+            let mut conn = context.get_conn().await?;
+
+            db::attendance::SummaryQuery::new(room.id())
+                .execute(&mut conn)
+                .await?
+        };
+
+        context
+            .metrics()
+            .request_duration
+            .attendance_list
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(Response::new(
+            ResponseStatus::OK,
+            summary,
+            context.start_timestamp(),
+            Some(authz_time),
+        ))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::attendance::{CloseQuery, InsertQuery, LeaveReason, Summary as AttendanceSummary},
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn list_attendance(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let kicked_agent = TestAgent::new("web", "user456", USR_AUDIENCE);
+        let online_agent = TestAgent::new("web", "user789", USR_AUDIENCE);
+
+        let room = {
+            let mut conn = db.get_conn().await;
+            let room = shared_helpers::insert_room(&mut conn).await;
+
+            for attendee in &[&kicked_agent, &online_agent] {
+                InsertQuery::new(room.id(), attendee.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to insert attendance entry");
+            }
+
+            CloseQuery::new(LeaveReason::Kick)
+                .room_id(room.id())
+                .agent_id(kicked_agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to close attendance entry");
+
+            // The kicked agent enters the room once again after the kick.
+            InsertQuery::new(room.id(), kicked_agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert attendance entry");
+
+            // Entering again while being online doesn't open another entry.
+            InsertQuery::new(room.id(), online_agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert attendance entry");
+
+            CloseQuery::new(LeaveReason::Leave)
+                .agent_id(kicked_agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to close attendance entry");
+
+            room
+        };
+
+        let mut authz = TestAuthz::new();
+        let classroom_id = room.classroom_id().to_string();
+        authz.allow(
+            agent.account_id(),
+            vec!["classrooms", &classroom_id, "attendance"],
+            "list",
+        );
+
+        let mut context = TestContext::new(db, authz).await;
+        let payload = ListRequest { room_id: room.id() };
+
+        let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Attendance listing failed");
+
+        let (summary, respp, _) = find_response::<Vec<AttendanceSummary>>(messages.as_slice());
+        assert_eq!(respp.status(), ResponseStatus::OK);
+        assert_eq!(summary.len(), 2);
+
+        let kicked = summary
+            .iter()
+            .find(|s| s.agent_id() == kicked_agent.agent_id())
+            .expect("Missing kicked agent attendance");
+
+        assert_eq!(kicked.entries(), 2);
+        assert!(!kicked.online());
+        assert_eq!(kicked.last_leave_reason(), Some(LeaveReason::Leave));
+        assert!(kicked.presence() >= 0);
+
+        let online = summary
+            .iter()
+            .find(|s| s.agent_id() == online_agent.agent_id())
+            .expect("Missing online agent attendance");
+
+        assert_eq!(online.entries(), 1);
+        assert!(online.online());
+        assert_eq!(online.last_leave_reason(), None);
+    }
+
+    #[sqlx::test]
+    async fn list_attendance_not_authorized(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+        let room = {
+            let mut conn = db.get_conn().await;
+            shared_helpers::insert_room(&mut conn).await
+        };
+
+        let mut context = TestContext::new(db, TestAuthz::new()).await;
+        let payload = ListRequest { room_id: room.id() };
+
+        let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+            .await
+            .expect_err("Unexpected success on attendance listing");
+
+        assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+        assert_eq!(err.kind(), "access_denied");
+    }
+}
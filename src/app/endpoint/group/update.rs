@@ -28,7 +28,7 @@ use serde::Deserialize;
 use serde_json::json;
 use sqlx::Connection;
 use std::sync::Arc;
-use svc_agent::mqtt::ResponseStatus;
+use svc_agent::{mqtt::ResponseStatus, Addressable};
 use svc_events::{EventV1 as Event, VideoGroupEventV1 as VideoGroupEvent};
 use svc_utils::extractors::AgentIdExtractor;
 use tracing::error;
@@ -82,15 +82,17 @@ impl Handler {
             &tracing::field::display(room.classroom_id()),
         );
 
-        // Authorize classrooms.update on the tenant
-        let classroom_id = room.classroom_id().to_string();
-        let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+        // Authorize classrooms.update on the tenant unless the agent is a host or a co-host.
+        if !helpers::is_moderator(&room, reqp.as_agent_id()) {
+            let classroom_id = room.classroom_id().to_string();
+            let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
 
-        let authz_time = context
-            .authz()
-            .authorize(room.audience().into(), reqp, object, "update".into())
-            .await?;
-        context.metrics().observe_auth(authz_time);
+            let authz_time = context
+                .authz()
+                .authorize(room.audience().into(), reqp, object, "update".into())
+                .await?;
+            context.metrics().observe_auth(authz_time);
+        }
 
         if room.rtc_sharing_policy() != db::rtc::SharingPolicy::Owned {
             return Err(anyhow!(
@@ -105,6 +107,7 @@ impl Handler {
             .context("backend not found")
             .error(AppErrorKind::BackendNotFound)?;
 
+        let agent_id = reqp.as_agent_id().to_owned();
         let mut conn = context.get_conn().await?;
         let event_id = conn
             .transaction::<_, _, AppError>(|conn| {
@@ -112,8 +115,19 @@ impl Handler {
                     let existed_groups = db::group_agent::FindQuery::new(room.id())
                         .execute(conn)
                         .await?
-                        .groups()
-                        .len();
+                        .groups();
+
+                    helpers::audit_room_change(
+                        room.id(),
+                        "group.update",
+                        &agent_id,
+                        &existed_groups,
+                        &groups,
+                        conn,
+                    )
+                    .await?;
+
+                    let existed_groups = existed_groups.len();
 
                     let timestamp = Utc::now().timestamp_nanos();
                     let (event, operation) = if existed_groups == 1 {
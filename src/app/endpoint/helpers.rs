@@ -69,6 +69,7 @@ pub async fn find_room_by_id(
     find_room(query, opening_requirement, conn).await
 }
 
+/// Deleted RTCs are considered missing.
 pub async fn find_room_by_rtc_id(
     rtc_id: db::rtc::Id,
     opening_requirement: RoomTimeRequirement,
@@ -168,3 +169,50 @@ pub async fn check_room_presence(
         Ok(())
     }
 }
+
+/// Returns `true` if the agent is the host or a co-host of the room.
+pub fn is_moderator(room: &Room, agent_id: &AgentId) -> bool {
+    db::agent_role::Role::resolve(room, agent_id, None).is_moderator()
+}
+
+/// Returns the role of the agent in the room.
+pub async fn find_agent_role(
+    room: &Room,
+    agent_id: &AgentId,
+    conn: &mut sqlx::PgConnection,
+) -> Result<db::agent_role::Role, AppError> {
+    let stored = db::agent_role::FindQuery::new(room.id(), agent_id)
+        .execute(conn)
+        .await?
+        .map(|r| r.role());
+
+    Ok(db::agent_role::Role::resolve(room, agent_id, stored))
+}
+
+/// Appends an entry to the room audit log keeping only the changed properties.
+pub async fn audit_room_change(
+    room_id: db::room::Id,
+    action: &str,
+    agent_id: &AgentId,
+    before: &impl Serialize,
+    after: &impl Serialize,
+    conn: &mut sqlx::PgConnection,
+) -> Result<(), AppError> {
+    let before = serde_json::to_value(before)
+        .context("Failed to serialize audit entry")
+        .error(AppErrorKind::MessageBuildingFailed)?;
+
+    let after = serde_json::to_value(after)
+        .context("Failed to serialize audit entry")
+        .error(AppErrorKind::MessageBuildingFailed)?;
+
+    let (before, after) = db::room_audit::diff(before, after);
+
+    db::room_audit::InsertQuery::new(room_id, action, agent_id)
+        .before(before)
+        .after(after)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
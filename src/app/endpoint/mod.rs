@@ -50,13 +50,23 @@ macro_rules! request_routes {
 
 // Request routes configuration: method => RequestHandler
 request_routes!(
+    "agent.accept_hand" => agent::AcceptHandHandler,
     "agent.list" => agent::ListHandler,
+    "agent.lower_hand" => agent::LowerHandHandler,
+    "agent.raise_hand" => agent::RaiseHandHandler,
+    "agent.update_role" => agent::UpdateRoleHandler,
     "agent_reader_config.read" => agent_reader_config::ReadHandler,
     "agent_reader_config.update" => agent_reader_config::UpdateHandler,
     "agent_writer_config.read" => agent_writer_config::ReadHandler,
     "agent_writer_config.update" => agent_writer_config::UpdateHandler,
+    "attendance.list" => attendance::ListHandler,
     "message.broadcast" => message::BroadcastHandler,
     "message.unicast" => message::UnicastHandler,
+    // Same as `room.enter`, `room.admit` is available through HTTP only.
+    // "room.admit" => room::AdmitHandler,
+    "room.breakout" => room::BreakoutHandler,
+    "room.breakout_close" => room::BreakoutCloseHandler,
+    "room.clone" => room::CloneHandler,
     "room.close" => room::CloseHandler,
     "room.create" => room::CreateHandler,
     // todo delete later unused routes
@@ -64,11 +74,19 @@ request_routes!(
     // `room::EnterHandler` function and in order to do that, we need to pass
     // the context as `Arc<dyn GlobalContext>`
     // "room.enter" => room::EnterHandler,
+    "room.kick" => room::KickHandler,
     "room.leave" => room::LeaveHandler,
+    "room.list" => room::ListHandler,
     "room.read" => room::ReadHandler,
+    "room.reject" => room::RejectHandler,
+    "room_audit.list" => room_audit::ListHandler,
+    "room_template.create" => room_template::CreateHandler,
+    "room_template.create_room" => room_template::CreateRoomHandler,
+    "room_template.read" => room_template::ReadHandler,
     "room.update" => room::UpdateHandler,
     "rtc.connect" => rtc::ConnectHandler,
     "rtc.create" => rtc::CreateHandler,
+    "rtc.delete" => rtc::DeleteHandler,
     "rtc.list" => rtc::ListHandler,
     "rtc.read" => rtc::ReadHandler,
     "rtc_signal.create" => rtc_signal::CreateHandler,
@@ -76,6 +94,7 @@ request_routes!(
     "system.vacuum" => system::VacuumHandler,
     "system.agent_cleanup" => system::AgentCleanupHandler,
     "system.agent_connection_cleanup" => system::AgentConnectionCleanupHandler,
+    "system.lobby_cleanup" => system::LobbyCleanupHandler,
     "writer_config_snapshot.read" => writer_config_snapshot::ReadHandler
 );
 
@@ -180,10 +199,13 @@ event_routes!(
 pub mod agent;
 pub mod agent_reader_config;
 pub mod agent_writer_config;
+pub mod attendance;
 pub mod group;
 pub mod helpers;
 pub mod message;
 pub mod room;
+pub mod room_audit;
+pub mod room_template;
 pub mod rtc;
 pub mod rtc_signal;
 pub mod rtc_stream;
@@ -1,14 +1,14 @@
 use anyhow::{anyhow, Context as AnyhowContext};
 use async_trait::async_trait;
 use axum::{
-    extract::{Extension, Path},
+    extract::{Extension, Path, Query},
     Json,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
 use sqlx::Connection;
-use std::{ops::Bound, sync::Arc};
+use std::{collections::HashSet, ops::Bound, sync::Arc};
 use svc_agent::{
     mqtt::{OutgoingRequest, ResponseStatus, ShortTermTimingProperties, SubscriptionTopic},
     Addressable, AgentId, Authenticable, Subscription,
@@ -38,12 +38,15 @@ use crate::{
         API_VERSION,
     },
     authz::AuthzObject,
-    backend::janus::client::update_agent_reader_config::UpdateReaderConfigRequestBodyConfigItem,
+    backend::janus::client::{
+        detach_handle::DetachHandleRequest,
+        update_agent_reader_config::UpdateReaderConfigRequestBodyConfigItem,
+    },
     client::mqtt_gateway::MqttGatewayClient,
     db::{
         self,
         group_agent::{GroupItem, Groups},
-        room::{RoomBackend, Time},
+        room::{FindQueryable, RoomBackend, Time},
         rtc::SharingPolicy as RtcSharingPolicy,
     },
     outbox::{
@@ -84,6 +87,13 @@ pub struct CreateRequest {
     reserve: Option<i32>,
     tags: Option<JsonValue>,
     classroom_id: Uuid,
+    #[serde(default)]
+    lobby: bool,
+    #[serde(default)]
+    co_hosts: Vec<AgentId>,
+    parent_id: Option<db::room::Id>,
+    #[serde(default)]
+    preferred_region: Option<String>,
 }
 
 pub async fn create(
@@ -120,7 +130,7 @@ impl RequestHandler for CreateHandler {
             .unwrap_or(RtcSharingPolicy::None);
 
         // Authorize room creation on the tenant.
-        let authz_time = context
+        let mut authz_time = context
             .authz()
             .authorize(
                 payload.audience.clone(),
@@ -131,9 +141,41 @@ impl RequestHandler for CreateHandler {
             .await?;
         context.metrics().observe_auth(authz_time);
 
+        // A child room belongs to its parent's classroom and linking it updates the parent.
+        if let Some(parent_id) = payload.parent_id {
+            let parent = {
+                let mut conn = context.get_conn().await?;
+                helpers::find_room_by_id(
+                    parent_id,
+                    helpers::RoomTimeRequirement::NotClosed,
+                    &mut conn,
+                )
+                .await?
+            };
+
+            if parent.audience() != payload.audience
+                || parent.classroom_id() != payload.classroom_id
+            {
+                return Err(anyhow!("Parent room belongs to another classroom"))
+                    .error(AppErrorKind::InvalidPayload);
+            }
+
+            let classroom_id = parent.classroom_id().to_string();
+            let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+
+            let parent_authz_time = context
+                .authz()
+                .authorize(parent.audience().into(), reqp, object, "update".into())
+                .await?;
+            context.metrics().observe_auth(parent_authz_time);
+
+            authz_time = authz_time + parent_authz_time;
+        }
+
         // Create a room.
         let audience = payload.audience.clone();
         let mut conn = context.get_conn().await?;
+
         let mut q = db::room::InsertQuery::new(
             payload.time,
             &payload.audience,
@@ -149,6 +191,22 @@ impl RequestHandler for CreateHandler {
             q = q.tags(tags);
         }
 
+        if payload.lobby {
+            q = q.lobby(true);
+        }
+
+        if !payload.co_hosts.is_empty() {
+            q = q.co_hosts(&payload.co_hosts);
+        }
+
+        if let Some(parent_id) = payload.parent_id {
+            q = q.parent_id(parent_id);
+        }
+
+        if let Some(ref preferred_region) = payload.preferred_region {
+            q = q.preferred_region(preferred_region);
+        }
+
         let room = q.execute(&mut conn).await?;
 
         // Create a default group for minigroups
@@ -187,11 +245,217 @@ impl RequestHandler for CreateHandler {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// Settings copied to a new room by `room.clone` and `room_template.create_room`.
+pub struct RoomSetup {
+    pub audience: String,
+    pub rtc_sharing_policy: RtcSharingPolicy,
+    pub reserve: Option<i32>,
+    pub tags: JsonValue,
+    pub groups: Option<Groups>,
+    pub template_id: Option<db::room_template::Id>,
+}
+
+impl RoomSetup {
+    pub async fn insert(
+        self,
+        time: Time,
+        classroom_id: Uuid,
+        conn: &mut sqlx::PgConnection,
+    ) -> Result<db::room::Object, AppError> {
+        conn.transaction::<_, _, AppError>(|conn| {
+            Box::pin(async move {
+                let mut q = db::room::InsertQuery::new(
+                    time,
+                    &self.audience,
+                    self.rtc_sharing_policy,
+                    classroom_id,
+                )
+                .tags(&self.tags);
+
+                if let Some(reserve) = self.reserve {
+                    q = q.reserve(reserve);
+                }
+
+                if let Some(template_id) = self.template_id {
+                    q = q.template_id(template_id);
+                }
+
+                let room = q.execute(conn).await?;
+
+                // Minigroups always have at least the default group.
+                let groups = match self.groups {
+                    Some(groups) => Some(groups),
+                    None if room.rtc_sharing_policy() == db::rtc::SharingPolicy::Owned => {
+                        Some(Groups::new(vec![GroupItem::new(0, vec![])]))
+                    }
+                    None => None,
+                };
+
+                if let Some(groups) = groups {
+                    db::group_agent::UpsertQuery::new(room.id(), &groups)
+                        .execute(conn)
+                        .await?;
+                }
+
+                Ok(room)
+            })
+        })
+        .await
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct CloneRequest {
+    id: db::room::Id,
+    #[serde(with = "crate::serde::ts_seconds_bound_tuple")]
+    time: Time,
+    classroom_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClonePayload {
+    #[serde(with = "crate::serde::ts_seconds_bound_tuple")]
+    time: Time,
+    classroom_id: Uuid,
+}
+
+pub async fn clone(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+    Json(payload): Json<ClonePayload>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = CloneRequest {
+        id: room_id,
+        time: payload.time,
+        classroom_id: payload.classroom_id,
+    };
+
+    CloneHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct CloneHandler;
+
+#[async_trait]
+impl RequestHandler for CloneHandler {
+    type Payload = CloneRequest;
+    const ERROR_TITLE: &'static str = "Failed to clone room";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        // Closed rooms may be cloned as well to repeat a past lesson.
+        let source = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(payload.id, helpers::RoomTimeRequirement::Any, &mut conn)
+                .await?
+        };
+
+        // Authorize reading the source room on the tenant as its setup gets copied.
+        let source_classroom_id = source.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &source_classroom_id]).into();
+
+        let read_authz_time = context
+            .authz()
+            .authorize(source.audience().into(), reqp, object, "read".into())
+            .await?;
+        context.metrics().observe_auth(read_authz_time);
+
+        // Authorize room creation on the tenant.
+        let create_authz_time = context
+            .authz()
+            .authorize(
+                source.audience().into(),
+                reqp,
+                AuthzObject::new(&["classrooms"]).into(),
+                "create".into(),
+            )
+            .await?;
+        context.metrics().observe_auth(create_authz_time);
+
+        let authz_time = read_authz_time + create_authz_time;
+        let mut conn = context.get_conn().await?;
+
+        let groups = if source.rtc_sharing_policy() == db::rtc::SharingPolicy::Owned {
+            match db::group_agent::FindQuery::new(source.id())
+                .execute(&mut conn)
+                .await
+            {
+                Ok(group_agent) => Some(group_agent.groups()),
+                Err(sqlx::Error::RowNotFound) => None,
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            None
+        };
+
+        let room = RoomSetup {
+            audience: source.audience().to_owned(),
+            rtc_sharing_policy: source.rtc_sharing_policy(),
+            reserve: source.reserve(),
+            tags: source.tags().to_owned(),
+            groups,
+            template_id: source.template_id(),
+        }
+        .insert(payload.time, payload.classroom_id, &mut conn)
+        .await?;
+
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        // Respond and broadcast to the audience topic.
+        let mut response = Response::new(
+            ResponseStatus::CREATED,
+            room.clone(),
+            context.start_timestamp(),
+            Some(authz_time),
+        );
+
+        response.add_notification(
+            "room.create",
+            &format!("audiences/{}/events", room.audience()),
+            room,
+            context.start_timestamp(),
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .room_clone
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct ReadRequest {
     id: db::room::Id,
 }
 
+#[derive(Debug, Serialize)]
+struct ReadResponse {
+    #[serde(flatten)]
+    room: db::room::Object,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<db::room::Object>,
+}
+
 pub async fn read(
     Extension(ctx): Extension<Arc<AppContext>>,
     AgentIdExtractor(agent_id): AgentIdExtractor,
@@ -222,10 +486,18 @@ impl RequestHandler for ReadHandler {
         payload: Self::Payload,
         reqp: RequestParams<'_>,
     ) -> RequestResult {
-        let room = {
+        let (room, children) = {
             let mut conn = context.get_conn().await?;
-            helpers::find_room_by_id(payload.id, helpers::RoomTimeRequirement::Any, &mut conn)
-                .await?
+            let room =
+                helpers::find_room_by_id(payload.id, helpers::RoomTimeRequirement::Any, &mut conn)
+                    .await?;
+
+            let children = db::room::ListQuery::new()
+                .parent_id(room.id())
+                .execute(&mut conn)
+                .await?;
+
+            (room, children)
         };
 
         tracing::Span::current().record(
@@ -250,7 +522,192 @@ impl RequestHandler for ReadHandler {
 
         Ok(Response::new(
             ResponseStatus::OK,
-            room,
+            ReadResponse { room, children },
+            context.start_timestamp(),
+            Some(authz_time),
+        ))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+const MAX_LIMIT: i64 = 25;
+
+#[derive(Debug, Deserialize)]
+pub struct ListRequest {
+    audience: String,
+    classroom_id: Option<Uuid>,
+    #[serde(default)]
+    #[serde(with = "crate::serde::ts_seconds_option_bound_tuple")]
+    time: Option<Time>,
+    closed: Option<bool>,
+    backend_id: Option<AgentId>,
+    tags: Option<JsonValue>,
+    parent_id: Option<db::room::Id>,
+    cursor: Option<db::room::Id>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    audience: String,
+    classroom_id: Option<Uuid>,
+    time_from: Option<i64>,
+    time_to: Option<i64>,
+    closed: Option<bool>,
+    backend_id: Option<AgentId>,
+    // JSON-encoded object since query strings can't carry nested values.
+    tags: Option<String>,
+    parent_id: Option<db::room::Id>,
+    cursor: Option<db::room::Id>,
+    limit: Option<i64>,
+}
+
+pub async fn list(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Query(params): Query<ListParams>,
+) -> RequestResult {
+    let time = match (params.time_from, params.time_to) {
+        (None, None) => None,
+        (from, to) => {
+            let from = from.map(parse_timestamp).transpose()?;
+            let to = to.map(parse_timestamp).transpose()?;
+
+            Some((
+                from.map_or(Bound::Unbounded, Bound::Included),
+                to.map_or(Bound::Unbounded, Bound::Excluded),
+            ))
+        }
+    };
+
+    let tags = params
+        .tags
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .context("Invalid tags filter")
+        .error(AppErrorKind::InvalidPayload)?;
+
+    let request = ListRequest {
+        audience: params.audience,
+        classroom_id: params.classroom_id,
+        time,
+        closed: params.closed,
+        backend_id: params.backend_id,
+        tags,
+        parent_id: params.parent_id,
+        cursor: params.cursor,
+        limit: params.limit,
+    };
+    ListHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+fn parse_timestamp(seconds: i64) -> Result<DateTime<Utc>, AppError> {
+    NaiveDateTime::from_timestamp_opt(seconds, 0)
+        .map(|dt| DateTime::from_utc(dt, Utc))
+        .context("Invalid time filter")
+        .error(AppErrorKind::InvalidPayload)
+}
+
+pub struct ListHandler;
+
+#[async_trait]
+impl RequestHandler for ListHandler {
+    type Payload = ListRequest;
+    const ERROR_TITLE: &'static str = "Failed to list rooms";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        if let Some(ref tags) = payload.tags {
+            if !tags.is_object() {
+                return Err(anyhow!("Tags filter must be an object"))
+                    .error(AppErrorKind::InvalidPayload);
+            }
+        }
+
+        if matches!(payload.limit, Some(limit) if limit < 1) {
+            return Err(anyhow!("Limit must be positive")).error(AppErrorKind::InvalidPayload);
+        }
+
+        // Authorize rooms listing on the tenant.
+        let authz_time = context
+            .authz()
+            .authorize(
+                payload.audience.clone(),
+                reqp,
+                AuthzObject::new(&["classrooms"]).into(),
+                "list".into(),
+            )
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let mut query = db::room::ListQuery::new()
+            .audience(&payload.audience)
+            .limit(std::cmp::min(payload.limit.unwrap_or(MAX_LIMIT), MAX_LIMIT));
+
+        if let Some(classroom_id) = payload.classroom_id {
+            query = query.classroom_id(classroom_id);
+        }
+
+        if let Some(time) = payload.time {
+            query = query.time(time);
+        }
+
+        if let Some(closed) = payload.closed {
+            query = query.closed(closed);
+        }
+
+        if let Some(ref backend_id) = payload.backend_id {
+            query = query.backend_id(backend_id);
+        }
+
+        if let Some(ref tags) = payload.tags {
+            query = query.tags(tags);
+        }
+
+        if let Some(parent_id) = payload.parent_id {
+            query = query.parent_id(parent_id);
+        }
+
+        if let Some(cursor) = payload.cursor {
+            query = query.after(cursor);
+        }
+
+        let rooms = {
+            let mut conn = context.get_conn().await?;
+
+            // An unknown cursor would silently yield an empty page.
+            if let Some(cursor) = payload.cursor {
+                db::room::FindQuery::new(cursor)
+                    .execute(&mut conn)
+                    .await?
+                    .context("Unknown cursor")
+                    .error(AppErrorKind::InvalidPayload)?;
+            }
+
+            query.execute(&mut conn).await?
+        };
+
+        context
+            .metrics()
+            .request_duration
+            .room_list
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(Response::new(
+            ResponseStatus::OK,
+            rooms,
             context.start_timestamp(),
             Some(authz_time),
         ))
@@ -269,6 +726,9 @@ pub struct UpdateRequest {
     tags: Option<JsonValue>,
     classroom_id: Option<Uuid>,
     host: Option<AgentId>,
+    lobby: Option<bool>,
+    locked: Option<bool>,
+    co_hosts: Option<Vec<AgentId>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -280,6 +740,9 @@ pub struct UpdateFields {
     tags: Option<JsonValue>,
     classroom_id: Option<Uuid>,
     host: Option<AgentId>,
+    lobby: Option<bool>,
+    locked: Option<bool>,
+    co_hosts: Option<Vec<AgentId>>,
 }
 
 pub async fn update(
@@ -297,6 +760,9 @@ pub async fn update(
         tags: request.tags,
         classroom_id: request.classroom_id,
         host: request.host,
+        lobby: request.lobby,
+        locked: request.locked,
+        co_hosts: request.co_hosts,
     };
     UpdateHandler::handle(
         &mut ctx.start_message(),
@@ -348,6 +814,7 @@ impl RequestHandler for UpdateHandler {
         context.metrics().observe_auth(authz_time);
 
         let room_was_open = !room.is_closed();
+        let room_was_locked = room.locked();
 
         // Update room.
         let room = {
@@ -395,14 +862,36 @@ impl RequestHandler for UpdateHandler {
                 }
             };
 
-            db::room::UpdateQuery::new(room.id())
-                .time(time)
-                .reserve(payload.reserve)
-                .tags(payload.tags)
-                .classroom_id(payload.classroom_id)
-                .host(payload.host.as_ref())
-                .execute(&mut conn)
-                .await?
+            let agent_id = reqp.as_agent_id().clone();
+
+            conn.transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    let updated_room = db::room::UpdateQuery::new(room.id())
+                        .time(time)
+                        .reserve(payload.reserve)
+                        .tags(payload.tags)
+                        .classroom_id(payload.classroom_id)
+                        .host(payload.host.as_ref())
+                        .lobby(payload.lobby)
+                        .locked(payload.locked)
+                        .co_hosts(payload.co_hosts.as_deref())
+                        .execute(conn)
+                        .await?;
+
+                    helpers::audit_room_change(
+                        room.id(),
+                        "room.update",
+                        &agent_id,
+                        &room,
+                        &updated_room,
+                        conn,
+                    )
+                    .await?;
+
+                    Ok(updated_room)
+                })
+            })
+            .await?
         };
 
         // Respond and broadcast to the audience topic.
@@ -420,6 +909,16 @@ impl RequestHandler for UpdateHandler {
             context.start_timestamp(),
         );
 
+        // Let the agents in the room know that it has been locked or unlocked.
+        if room.locked() != room_was_locked {
+            response.add_notification(
+                "room.update",
+                &format!("rooms/{}/events", room.id()),
+                room.clone(),
+                context.start_timestamp(),
+            );
+        }
+
         // Publish room closed notification.
         if let (_, Bound::Excluded(closed_at)) = room.time() {
             if room_was_open && closed_at <= Utc::now() {
@@ -523,7 +1022,13 @@ impl RequestHandler for CloseHandler {
         // Update room.
         let room = {
             let mut conn = context.get_conn().await?;
-            db::room::set_closed_by(room.id(), reqp.as_agent_id(), &mut conn).await?
+
+            let agent_id = reqp.as_agent_id().clone();
+
+            conn.transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move { close_room(&room, &agent_id, conn).await })
+            })
+            .await?
         };
 
         // Respond and broadcast to the audience topic.
@@ -565,12 +1070,37 @@ impl RequestHandler for CloseHandler {
     }
 }
 
-///////////////////////////////////////////////////////////////////////////////
+/// Closes the room on behalf of the agent and records it in the room audit log.
+async fn close_room(
+    room: &db::room::Object,
+    agent_id: &AgentId,
+    conn: &mut sqlx::PgConnection,
+) -> Result<db::room::Object, AppError> {
+    let closed_room = db::room::set_closed_by(room.id(), agent_id, conn).await?;
 
-#[derive(Deserialize)]
-pub struct EnterPayload {
-    #[serde(default)]
+    db::attendance::CloseQuery::new(db::attendance::LeaveReason::RoomClose)
+        .room_id(room.id())
+        .execute(conn)
+        .await?;
+
+    helpers::audit_room_change(room.id(), "room.close", agent_id, room, &closed_room, conn).await?;
+
+    Ok(closed_room)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+const MAX_DISPLAY_NAME_LEN: usize = 256;
+const MAX_AVATAR_URL_LEN: usize = 2048;
+const MAX_CLIENT_VERSION_LEN: usize = 64;
+const MAX_DEVICE_TYPE_LEN: usize = 64;
+
+#[derive(Deserialize)]
+pub struct EnterPayload {
+    #[serde(default)]
     agent_label: Option<String>,
+    #[serde(default)]
+    metadata: Option<db::agent::Metadata>,
 }
 
 pub async fn enter(
@@ -581,19 +1111,22 @@ pub async fn enter(
 ) -> RequestResult {
     tracing::Span::current().record("room_id", &tracing::field::display(room_id));
 
-    let request = EnterRequest { id: room_id };
+    let (agent_label, metadata) = payload
+        .map(|Json(p)| (p.agent_label, p.metadata))
+        .unwrap_or_default();
 
-    let agent_id = payload
-        .and_then(|p| {
-            p.agent_label
-                .as_ref()
-                .map(|label| AgentId::new(label, agent_id.as_account_id().to_owned()))
-        })
+    let request = EnterRequest {
+        id: room_id,
+        metadata,
+    };
+
+    let agent_id = agent_label
+        .map(|label| AgentId::new(&label, agent_id.as_account_id().to_owned()))
         .unwrap_or(agent_id);
 
     EnterHandler::handle(
         ctx,
-        request.clone(),
+        request,
         RequestParams::Http {
             agent_id: &agent_id,
         },
@@ -602,7 +1135,52 @@ pub async fn enter(
     .await
 }
 
-pub type EnterRequest = ReadRequest;
+#[derive(Debug)]
+pub struct EnterRequest {
+    id: db::room::Id,
+    metadata: Option<db::agent::Metadata>,
+}
+
+impl EnterRequest {
+    fn validate_metadata(&self) -> Result<(), AppError> {
+        let metadata = match self.metadata {
+            Some(ref metadata) => metadata,
+            None => return Ok(()),
+        };
+
+        let limits = [
+            ("display_name", &metadata.display_name, MAX_DISPLAY_NAME_LEN),
+            ("avatar_url", &metadata.avatar_url, MAX_AVATAR_URL_LEN),
+            (
+                "client_version",
+                &metadata.client_version,
+                MAX_CLIENT_VERSION_LEN,
+            ),
+            ("device_type", &metadata.device_type, MAX_DEVICE_TYPE_LEN),
+        ];
+
+        for (name, value, max_len) in limits {
+            if value.as_ref().map_or(0, |v| v.chars().count()) > max_len {
+                return Err(anyhow!(
+                    "Metadata `{}` exceeds {} characters",
+                    name,
+                    max_len
+                ))
+                .error(AppErrorKind::InvalidPayload);
+            }
+        }
+
+        if let Some(ref avatar_url) = metadata.avatar_url {
+            if !avatar_url.starts_with("https://") && !avatar_url.starts_with("http://") {
+                return Err(anyhow!("Metadata `avatar_url` must be an HTTP(S) URL"))
+                    .error(AppErrorKind::InvalidPayload);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub struct EnterHandler;
 
 impl EnterHandler {
@@ -612,6 +1190,8 @@ impl EnterHandler {
         reqp: RequestParams<'_>,
         start_timestamp: DateTime<Utc>,
     ) -> RequestResult {
+        payload.validate_metadata()?;
+
         let room = {
             let mut conn = context.get_conn().await?;
             helpers::find_room_by_id(
@@ -628,7 +1208,6 @@ impl EnterHandler {
         );
 
         // Authorize subscribing to the room's events.
-        let room_id = room.id().to_string();
         let classroom_id = room.classroom_id().to_string();
         let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
 
@@ -638,212 +1217,317 @@ impl EnterHandler {
             .await?;
         context.metrics().observe_auth(authz_time);
 
-        // Register agent in `in_progress` state.
-        {
+        // Register agent in `in_progress` state unless it's banned. Agents entering a room
+        // with the lobby enabled wait in `pending` state for the host approval unless they
+        // have been already admitted.
+        let pending = {
             let mut conn = context.get_conn().await?;
-            db::agent::InsertQuery::new(reqp.as_agent_id(), room.id())
+
+            let ban = db::room_ban::FindQuery::new(room.id(), reqp.as_agent_id())
                 .execute(&mut conn)
                 .await?;
-        }
 
-        // Send dynamic subscription creation request to the broker.
-        let subject = reqp.as_agent_id().to_owned();
-        let object = ["rooms", &room_id, "events"];
+            if ban.is_some() {
+                return Err(anyhow!("Agent is banned in the room"))
+                    .error(AppErrorKind::AgentBanned);
+            }
 
-        tracing::info!(
-            "send dynsub request to mqtt gateway {} {:?}",
-            subject,
-            object
-        );
+            // Only the host and agents which are already in the room may enter a locked room.
+            // Agents waiting in the lobby are not in the room yet.
+            if room.locked() && room.host() != Some(reqp.as_agent_id()) {
+                let present = db::agent::ListQuery::new()
+                    .room_id(room.id())
+                    .agent_id(reqp.as_agent_id())
+                    .status(db::agent::Status::Ready)
+                    .limit(1)
+                    .execute(&mut conn)
+                    .await?;
+
+                if present.is_empty() {
+                    return Err(anyhow!("Room is locked")).error(AppErrorKind::RoomLocked);
+                }
+            }
 
-        context
-            .mqtt_gateway_client()
-            .create_subscription(subject.clone(), &object)
-            .await
-            .error(AppErrorKind::BrokerRequestFailed)?;
+            let pending = room.lobby()
+                && room.host() != Some(reqp.as_agent_id())
+                && db::agent::ListQuery::new()
+                    .room_id(room.id())
+                    .agent_id(reqp.as_agent_id())
+                    .execute(&mut conn)
+                    .await?
+                    .is_empty();
+
+            let status = if pending {
+                db::agent::Status::Pending
+            } else {
+                db::agent::Status::InProgress
+            };
 
-        {
-            let mut conn = context.get_conn().await?;
+            let mut q = db::agent::InsertQuery::new(reqp.as_agent_id(), room.id()).status(status);
 
-            if room.host() == Some(&subject) {
-                db::orphaned_room::remove_room(room.id(), &mut conn).await?;
+            if let Some(ref metadata) = payload.metadata {
+                q = q.metadata(metadata);
             }
 
-            // Update agent state to `ready`.
-            db::agent::UpdateQuery::new(&subject, room.id())
-                .status(db::agent::Status::Ready)
-                .execute(&mut conn)
-                .await?;
+            q.execute(&mut conn).await?;
+
+            pending
+        };
+
+        let response = if pending {
+            let mut response = Response::new(
+                ResponseStatus::ACCEPTED,
+                json!({}),
+                start_timestamp,
+                Some(authz_time),
+            );
+
+            response.add_notification(
+                "room.lobby_enter",
+                &format!("rooms/{}/events", room.id()),
+                RoomEnterLeaveEvent::new(room.id(), reqp.as_agent_id().to_owned())
+                    .metadata(payload.metadata),
+                start_timestamp,
+            );
+
+            response
+        } else {
+            join_room(context.clone(), room, reqp, start_timestamp).await?
+        };
+
+        context
+            .metrics()
+            .request_duration
+            .room_enter
+            .observe_timestamp(start_timestamp);
+
+        Ok(response)
+    }
+}
+
+/// Subscribes the agent to the room's events and marks it as `ready`.
+/// Creates an owned RTC and puts the agent into the default group for minigroups.
+async fn join_room(
+    context: Arc<dyn GlobalContext + Send + Sync>,
+    room: db::room::Object,
+    reqp: RequestParams<'_>,
+    start_timestamp: DateTime<Utc>,
+) -> RequestResult {
+    // Send dynamic subscription creation request to the broker.
+    let subject = reqp.as_agent_id().to_owned();
+    let room_id = room.id().to_string();
+    let object = ["rooms", &room_id, "events"];
+
+    tracing::info!(
+        "send dynsub request to mqtt gateway {} {:?}",
+        subject,
+        object
+    );
+
+    context
+        .mqtt_gateway_client()
+        .create_subscription(subject.clone(), &object)
+        .await
+        .error(AppErrorKind::BrokerRequestFailed)?;
+
+    let metadata = {
+        let mut conn = context.get_conn().await?;
+
+        if room.host() == Some(&subject) {
+            db::orphaned_room::remove_room(room.id(), &mut conn).await?;
         }
 
-        let mut response = Response::new(ResponseStatus::OK, json!({}), start_timestamp, None);
+        // Update agent state to `ready`.
+        let agent = db::agent::UpdateQuery::new(&subject, room.id())
+            .status(db::agent::Status::Ready)
+            .execute(&mut conn)
+            .await?;
+
+        let metadata = agent.and_then(|a| a.metadata().cloned());
 
-        let ctx = context.clone();
-        let room_id = room.id();
-        let outbox_config = ctx.config().clone().outbox;
-        if room.rtc_sharing_policy() == db::rtc::SharingPolicy::Owned {
-            let mut conn = context.get_conn().await?;
-            let rtcs = db::rtc::ListQuery::new()
-                .room_id(room_id)
-                .created_by(&[reqp.as_agent_id()])
+        db::attendance::InsertQuery::new(room.id(), &subject)
+            .execute(&mut conn)
+            .await?;
+
+        // Assign the agent's role in the room.
+        let current_role = db::agent_role::FindQuery::new(room.id(), &subject)
+            .execute(&mut conn)
+            .await?
+            .map(|r| r.role());
+
+        let role = db::agent_role::Role::resolve(&room, &subject, current_role);
+
+        if current_role != Some(role) {
+            db::agent_role::UpsertQuery::new(room.id(), &subject, role)
                 .execute(&mut conn)
                 .await?;
+        }
 
-            let rtc = rtcs.into_iter().next();
-
-            if rtc.is_none() {
-                let RtcCreateResult {
-                    rtc,
-                    authz_time,
-                    notification_label,
-                    notification_topic,
-                } = RtcCreate {
-                    ctx: context.as_ref(),
-                    room: either::Either::Left(room.clone()),
-                    reqp,
-                }
-                .run()
-                .await?;
+        metadata
+    };
 
-                response.set_authz_time(authz_time);
+    let mut response = Response::new(ResponseStatus::OK, json!({}), start_timestamp, None);
 
-                response.add_notification(
-                    notification_label,
-                    &notification_topic,
-                    rtc,
-                    start_timestamp,
-                );
+    let ctx = context.clone();
+    let room_id = room.id();
+    let outbox_config = ctx.config().clone().outbox;
+    if room.rtc_sharing_policy() == db::rtc::SharingPolicy::Owned {
+        let mut conn = context.get_conn().await?;
+        let rtcs = db::rtc::ListQuery::new()
+            .room_id(room_id)
+            .created_by(&[reqp.as_agent_id()])
+            .kind(db::rtc::Kind::Camera)
+            .execute(&mut conn)
+            .await?;
+
+        let rtc = rtcs.into_iter().next();
+
+        if rtc.is_none() {
+            let RtcCreateResult {
+                rtc,
+                authz_time,
+                notification_label,
+                notification_topic,
+            } = RtcCreate {
+                ctx: context.as_ref(),
+                room: either::Either::Left(room.clone()),
+                kind: db::rtc::Kind::Camera,
+                reqp,
             }
+            .run()
+            .await?;
 
-            // Adds participants to the default group for minigroups
-            let mut conn = context.get_conn().await?;
-            let agent_id = reqp.as_agent_id().clone();
+            response.set_authz_time(authz_time);
 
-            let maybe_event_id = conn
-                .transaction::<_, _, AppError>(|conn| {
-                    Box::pin(async move {
-                        let group_agent = db::group_agent::FindQuery::new(room_id)
-                            .execute(conn)
-                            .await?;
+            response.add_notification(
+                notification_label,
+                &notification_topic,
+                rtc,
+                start_timestamp,
+            );
+        }
 
-                        let groups = group_agent.groups();
-                        let agent_exists = groups.is_agent_exist(&agent_id);
+        // Adds participants to the default group for minigroups
+        let mut conn = context.get_conn().await?;
+        let agent_id = reqp.as_agent_id().clone();
 
-                        if !agent_exists {
-                            let changed_groups = groups.add_to_default_group(&agent_id);
-                            db::group_agent::UpsertQuery::new(room_id, &changed_groups)
-                                .execute(conn)
-                                .await?;
+        let maybe_event_id = conn
+            .transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    let group_agent = db::group_agent::FindQuery::new(room_id)
+                        .execute(conn)
+                        .await?;
 
-                            // Check the number of groups, and if there are more than 1,
-                            // then create RTC reader configs for participants from other groups
-                            if groups.len() > 1 {
-                                let backend_id = room
-                                    .backend_id()
-                                    .cloned()
-                                    .context("backend not found")
-                                    .error(AppErrorKind::BackendNotFound)?;
-
-                                let configs =
-                                    group_reader_config::update(conn, room_id, changed_groups)
-                                        .await?;
-
-                                // Generate configs for janus
-                                let items = configs
-                                    .into_iter()
-                                    .map(|((rtc_id, agent_id), value)| {
-                                        UpdateReaderConfigRequestBodyConfigItem {
-                                            reader_id: agent_id,
-                                            stream_id: rtc_id,
-                                            receive_video: value,
-                                            receive_audio: value,
-                                        }
-                                    })
-                                    .collect();
-
-                                let timestamp = Utc::now().timestamp_nanos();
-                                let event = Event::from(VideoGroupEvent::Updated {
-                                    created_at: timestamp,
-                                });
-                                let init_stage = VideoGroupUpdateJanusConfig::init(
-                                    event,
-                                    room.classroom_id(),
-                                    room.id(),
-                                    backend_id,
-                                    items,
-                                );
-
-                                let serialized_stage = serde_json::to_value(init_stage)
-                                    .context("serialization failed")
-                                    .error(AppErrorKind::OutboxStageSerializationFailed)?;
-
-                                let delivery_deadline_at = outbox::util::delivery_deadline_from_now(
-                                    outbox_config.try_wake_interval,
-                                );
-
-                                let event_id = outbox::db::sqlx::InsertQuery::new(
-                                    stage::video_group::ENTITY_TYPE,
-                                    serialized_stage,
-                                    delivery_deadline_at,
-                                    stage::video_group::UPDATED_OPERATION,
-                                )
-                                .execute(conn)
-                                .await?;
+                    let groups = group_agent.groups();
+                    let agent_exists = groups.is_agent_exist(&agent_id);
 
-                                return Ok(Some(event_id));
-                            }
-                        }
+                    if !agent_exists {
+                        let changed_groups = groups.add_to_default_group(&agent_id);
+                        db::group_agent::UpsertQuery::new(room_id, &changed_groups)
+                            .execute(conn)
+                            .await?;
 
-                        Ok(None)
-                    })
-                })
-                .await?;
+                        // Check the number of groups, and if there are more than 1,
+                        // then create RTC reader configs for participants from other groups
+                        if groups.len() > 1 {
+                            let backend_id = room
+                                .backend_id()
+                                .cloned()
+                                .context("backend not found")
+                                .error(AppErrorKind::BackendNotFound)?;
+
+                            let configs =
+                                group_reader_config::update(conn, room_id, changed_groups).await?;
+
+                            // Generate configs for janus
+                            let items = configs
+                                .into_iter()
+                                .map(|((rtc_id, agent_id), value)| {
+                                    UpdateReaderConfigRequestBodyConfigItem {
+                                        reader_id: agent_id,
+                                        stream_id: rtc_id,
+                                        receive_video: value,
+                                        receive_audio: value,
+                                    }
+                                })
+                                .collect();
+
+                            let timestamp = Utc::now().timestamp_nanos();
+                            let event = Event::from(VideoGroupEvent::Updated {
+                                created_at: timestamp,
+                            });
+                            let init_stage = VideoGroupUpdateJanusConfig::init(
+                                event,
+                                room.classroom_id(),
+                                room.id(),
+                                backend_id,
+                                items,
+                            );
+
+                            let serialized_stage = serde_json::to_value(init_stage)
+                                .context("serialization failed")
+                                .error(AppErrorKind::OutboxStageSerializationFailed)?;
+
+                            let delivery_deadline_at = outbox::util::delivery_deadline_from_now(
+                                outbox_config.try_wake_interval,
+                            );
+
+                            let event_id = outbox::db::sqlx::InsertQuery::new(
+                                stage::video_group::ENTITY_TYPE,
+                                serialized_stage,
+                                delivery_deadline_at,
+                                stage::video_group::UPDATED_OPERATION,
+                            )
+                            .execute(conn)
+                            .await?;
 
-            match maybe_event_id {
-                Some(event_id) => {
-                    let pipeline = DieselPipeline::new(
-                        ctx.db().clone(),
-                        outbox_config.try_wake_interval,
-                        outbox_config.max_delivery_interval,
-                    );
-                    if let Err(err) = pipeline
-                        .run_single_stage::<AppStage, _>(ctx, event_id)
-                        .await
-                    {
-                        if let ErrorKind::StageError(kind) = &err.kind {
-                            context.metrics().observe_outbox_error(kind);
+                            return Ok(Some(event_id));
                         }
+                    }
+
+                    Ok(None)
+                })
+            })
+            .await?;
 
-                        error!(%err, "failed to complete stage");
-                        AppError::from(err).notify_sentry();
+        match maybe_event_id {
+            Some(event_id) => {
+                let pipeline = DieselPipeline::new(
+                    ctx.db().clone(),
+                    outbox_config.try_wake_interval,
+                    outbox_config.max_delivery_interval,
+                );
+                if let Err(err) = pipeline
+                    .run_single_stage::<AppStage, _>(ctx, event_id)
+                    .await
+                {
+                    if let ErrorKind::StageError(kind) = &err.kind {
+                        context.metrics().observe_outbox_error(kind);
                     }
-                }
-                None => {
-                    response.add_notification(
-                        MQTT_NOTIFICATION_LABEL,
-                        &format!("rooms/{room_id}/events"),
-                        json!({}),
-                        start_timestamp,
-                    );
+
+                    error!(%err, "failed to complete stage");
+                    AppError::from(err).notify_sentry();
                 }
             }
-        };
-
-        response.add_notification(
-            "room.enter",
-            &format!("rooms/{room_id}/events"),
-            RoomEnterLeaveEvent::new(room_id, subject),
-            start_timestamp,
-        );
+            None => {
+                response.add_notification(
+                    MQTT_NOTIFICATION_LABEL,
+                    &format!("rooms/{room_id}/events"),
+                    json!({}),
+                    start_timestamp,
+                );
+            }
+        }
+    };
 
-        context
-            .metrics()
-            .request_duration
-            .room_enter
-            .observe_timestamp(start_timestamp);
+    response.add_notification(
+        "room.enter",
+        &format!("rooms/{room_id}/events"),
+        RoomEnterLeaveEvent::new(room_id, subject).metadata(metadata),
+        start_timestamp,
+    );
 
-        Ok(response)
-    }
+    Ok(response)
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -929,172 +1613,2443 @@ impl RequestHandler for LeaveHandler {
 
 ///////////////////////////////////////////////////////////////////////////////
 
-#[cfg(test)]
-mod test {
-    use serde::Deserialize;
+#[derive(Debug, Deserialize)]
+pub struct KickRequest {
+    id: db::room::Id,
+    agent_id: AgentId,
+    #[serde(default)]
+    ban: bool,
+}
 
-    use super::AgentId;
+#[derive(Debug, Default, Deserialize)]
+pub struct KickPayload {
+    #[serde(default)]
+    ban: bool,
+}
 
-    #[derive(Deserialize)]
-    struct DynSubRequest {
-        subject: AgentId,
-        object: Vec<String>,
-    }
+#[derive(Debug, Serialize)]
+struct RoomKickEvent {
+    id: db::room::Id,
+    agent_id: AgentId,
+    banned: bool,
+}
 
-    mod create {
-        use std::ops::Bound;
+pub async fn kick(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path((room_id, kicked_agent_id)): Path<(db::room::Id, AgentId)>,
+    payload: Option<Json<KickPayload>>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
 
-        use chrono::Utc;
-        use serde_json::json;
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let request = KickRequest {
+        id: room_id,
+        agent_id: kicked_agent_id,
+        ban: payload.ban,
+    };
+    KickHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
 
-        use crate::db::group_agent::{GroupItem, Groups};
-        use crate::{
-            db::room::Object as Room,
-            test_helpers::{db::TestDb, prelude::*},
+pub struct KickHandler;
+
+#[async_trait]
+impl RequestHandler for KickHandler {
+    type Payload = KickRequest;
+    const ERROR_TITLE: &'static str = "Failed to kick agent from room";
+
+    #[instrument(skip(context, payload, reqp), fields(room_id = %payload.id))]
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.id,
+                helpers::RoomTimeRequirement::NotClosed,
+                &mut conn,
+            )
+            .await?
         };
 
-        use super::super::*;
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
 
-        #[sqlx::test]
-        async fn create(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
+        // Authorize room updating on the tenant.
+        let classroom_id = room.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
 
-            // Allow user to create rooms.
-            let mut authz = TestAuthz::new();
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+        let authz_time = context
+            .authz()
+            .authorize(room.audience().into(), reqp, object, "update".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
 
-            // Make room.create request.
-            let mut context = TestContext::new(db, authz).await;
-            let time = (Bound::Unbounded, Bound::Unbounded);
-            let classroom_id = Uuid::new_v4();
+        let room_id = room.id();
+        let agent_id = payload.agent_id;
+        let ban = payload.ban;
+        let created_by = reqp.as_agent_id().to_owned();
+
+        // Remove the agent from the room and optionally ban it. Since bans are per account,
+        // every agent of the banned account gets kicked. Handles have to be fetched beforehand
+        // since agent connections are deleted in cascade.
+        let (maybe_backend, handles, kicked_agent_ids) = {
+            let mut conn = context.get_conn().await?;
 
-            let payload = CreateRequest {
-                time,
-                audience: USR_AUDIENCE.to_owned(),
-                backend: None,
-                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Shared),
-                reserve: Some(123),
-                tags: Some(json!({ "foo": "bar" })),
-                classroom_id,
+            let maybe_backend = match room.backend_id() {
+                None => None,
+                Some(backend_id) => {
+                    db::janus_backend::FindQuery::new(backend_id)
+                        .execute(&mut conn)
+                        .await?
+                }
             };
 
-            let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
-                .await
-                .expect("Room creation failed");
+            let agent_id = agent_id.clone();
 
-            // Assert response.
-            let (room, respp, _) = find_response::<Room>(messages.as_slice());
-            assert_eq!(respp.status(), ResponseStatus::OK);
-            assert_eq!(room.audience(), USR_AUDIENCE);
-            assert_eq!(room.time(), time);
-            assert_eq!(room.rtc_sharing_policy(), db::rtc::SharingPolicy::Shared);
-            assert_eq!(room.reserve(), Some(123));
-            assert_eq!(room.tags(), &json!({ "foo": "bar" }));
-            assert_eq!(room.classroom_id(), classroom_id);
+            let (handles, kicked_agent_ids) = conn
+                .transaction::<_, _, AppError>(|conn| {
+                    Box::pin(async move {
+                        let mut agent_ids = vec![agent_id.clone()];
 
-            // Assert notification.
-            let (room, evp, topic) = find_event::<Room>(messages.as_slice());
-            assert!(topic.ends_with(&format!("/audiences/{}/events", USR_AUDIENCE)));
-            assert_eq!(evp.label(), "room.create");
-            assert_eq!(room.audience(), USR_AUDIENCE);
-            assert_eq!(room.time(), time);
-            assert_eq!(room.rtc_sharing_policy(), db::rtc::SharingPolicy::Shared);
-            assert_eq!(room.reserve(), Some(123));
+                        if ban {
+                            let account_agent_ids = db::agent::ListQuery::new()
+                                .room_id(room_id)
+                                .execute(conn)
+                                .await?
+                                .into_iter()
+                                .map(|agent| agent.agent_id().to_owned())
+                                .filter(|id| {
+                                    id.as_account_id() == agent_id.as_account_id()
+                                        && *id != agent_id
+                                });
+
+                            agent_ids.extend(account_agent_ids);
+                        }
+
+                        let mut handles = Vec::new();
+
+                        for id in &agent_ids {
+                            let agent_handles = db::agent_connection::ListQuery::new(id, room_id)
+                                .execute(conn)
+                                .await?
+                                .into_iter()
+                                .map(|c| c.handle_id());
+
+                            handles.extend(agent_handles);
+
+                            let row_count = db::agent::DeleteQuery::new()
+                                .agent_id(id)
+                                .room_id(room_id)
+                                .execute(conn)
+                                .await?;
+
+                            if !ban && row_count < 1 {
+                                return Err(anyhow!("Agent is not online in the room"))
+                                    .error(AppErrorKind::AgentNotEnteredTheRoom);
+                            }
+
+                            db::attendance::CloseQuery::new(db::attendance::LeaveReason::Kick)
+                                .agent_id(id)
+                                .room_id(room_id)
+                                .execute(conn)
+                                .await?;
+
+                            db::raised_hand::DeleteQuery::new(room_id, id)
+                                .execute(conn)
+                                .await?;
+                        }
+
+                        if ban {
+                            db::room_ban::InsertQuery::new(room_id, &agent_id, &created_by)
+                                .execute(conn)
+                                .await?;
+                        }
+
+                        Ok((handles, agent_ids))
+                    })
+                })
+                .await?;
+
+            (maybe_backend, handles, kicked_agent_ids)
+        };
+
+        // Send dynamic subscription deletion requests to the broker. The agents are already
+        // out of the room at this point so failures are logged rather than returned.
+        let room_id_str = room_id.to_string();
+        let object = ["rooms", &room_id_str, "events"];
+
+        for kicked_agent_id in &kicked_agent_ids {
+            if let Err(err) = context
+                .mqtt_gateway_client()
+                .delete_subscription(kicked_agent_id.clone(), &object)
+                .await
+            {
+                error!(?err, agent_id = %kicked_agent_id, "Failed to delete subscription");
+            }
+        }
+
+        // Drop the agent's media. Failures are not fatal since the handles might be gone already.
+        if let Some(backend) = maybe_backend {
+            match context.janus_clients().get_or_insert(&backend) {
+                Ok(client) => {
+                    for handle_id in handles {
+                        let request = DetachHandleRequest {
+                            session_id: backend.session_id(),
+                            handle_id,
+                        };
+
+                        if let Err(err) = client.detach_handle(request).await {
+                            error!(?err, %handle_id, "Failed to detach janus handle");
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!(?err, "Failed to get janus client");
+                }
+            }
+        }
+
+        let mut response = Response::new(
+            ResponseStatus::OK,
+            json!({}),
+            context.start_timestamp(),
+            Some(authz_time),
+        );
+
+        for agent_id in kicked_agent_ids {
+            response.add_notification(
+                "room.kick",
+                &format!("rooms/{room_id}/events"),
+                RoomKickEvent {
+                    id: room_id,
+                    agent_id,
+                    banned: ban,
+                },
+                context.start_timestamp(),
+            );
+        }
+
+        context
+            .metrics()
+            .request_duration
+            .room_kick
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct LobbyRequest {
+    id: db::room::Id,
+    agent_id: AgentId,
+}
+
+pub async fn admit(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path((room_id, pending_agent_id)): Path<(db::room::Id, AgentId)>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = AdmitRequest {
+        id: room_id,
+        agent_id: pending_agent_id,
+    };
+    AdmitHandler::handle(
+        ctx,
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+        Utc::now(),
+    )
+    .await
+}
+
+pub type AdmitRequest = LobbyRequest;
+pub struct AdmitHandler;
+
+impl AdmitHandler {
+    async fn handle(
+        context: Arc<dyn GlobalContext + Send + Sync>,
+        payload: AdmitRequest,
+        reqp: RequestParams<'_>,
+        start_timestamp: DateTime<Utc>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.id,
+                helpers::RoomTimeRequirement::NotClosed,
+                &mut conn,
+            )
+            .await?
+        };
+
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        // Authorize room updating on the tenant.
+        let classroom_id = room.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(room.audience().into(), reqp, object, "update".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        // Check that the agent is waiting in the lobby.
+        {
+            let mut conn = context.get_conn().await?;
+
+            let pending = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .agent_id(&payload.agent_id)
+                .status(db::agent::Status::Pending)
+                .execute(&mut conn)
+                .await?;
+
+            if pending.is_empty() {
+                return Err(anyhow!("Agent is not waiting in the lobby"))
+                    .error(AppErrorKind::AgentNotEnteredTheRoom);
+            }
+        }
+
+        // Complete the entrance on behalf of the admitted agent.
+        let agent_reqp = RequestParams::Http {
+            agent_id: &payload.agent_id,
+        };
+
+        let mut response = join_room(context.clone(), room, agent_reqp, start_timestamp).await?;
+        response.set_authz_time(authz_time);
+
+        context
+            .metrics()
+            .request_duration
+            .room_admit
+            .observe_timestamp(start_timestamp);
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+pub async fn reject(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path((room_id, pending_agent_id)): Path<(db::room::Id, AgentId)>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = RejectRequest {
+        id: room_id,
+        agent_id: pending_agent_id,
+    };
+    RejectHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub type RejectRequest = LobbyRequest;
+pub struct RejectHandler;
+
+#[async_trait]
+impl RequestHandler for RejectHandler {
+    type Payload = RejectRequest;
+    const ERROR_TITLE: &'static str = "Failed to reject agent";
+
+    #[instrument(skip(context, payload, reqp), fields(room_id = %payload.id))]
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(payload.id, helpers::RoomTimeRequirement::Any, &mut conn)
+                .await?
+        };
+
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        // Authorize room updating on the tenant.
+        let classroom_id = room.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(room.audience().into(), reqp, object, "update".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        // Remove the agent from the lobby.
+        {
+            let mut conn = context.get_conn().await?;
+
+            let pending = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .agent_id(&payload.agent_id)
+                .status(db::agent::Status::Pending)
+                .execute(&mut conn)
+                .await?;
+
+            if pending.is_empty() {
+                return Err(anyhow!("Agent is not waiting in the lobby"))
+                    .error(AppErrorKind::AgentNotEnteredTheRoom);
+            }
+
+            db::agent::DeleteQuery::new()
+                .agent_id(&payload.agent_id)
+                .room_id(room.id())
+                .execute(&mut conn)
+                .await?;
+        }
+
+        let mut response = Response::new(
+            ResponseStatus::OK,
+            json!({}),
+            context.start_timestamp(),
+            Some(authz_time),
+        );
+
+        response.add_notification(
+            "room.reject",
+            &format!("rooms/{}/events", room.id()),
+            RoomEnterLeaveEvent::new(room.id(), payload.agent_id),
+            context.start_timestamp(),
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .room_reject
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct BreakoutRoom {
+    #[serde(default)]
+    agents: Vec<AgentId>,
+    tags: Option<JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BreakoutRequest {
+    id: db::room::Id,
+    rooms: Vec<BreakoutRoom>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BreakoutPayload {
+    rooms: Vec<BreakoutRoom>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomBreakoutEventItem {
+    id: db::room::Id,
+    agents: Vec<AgentId>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomBreakoutEvent {
+    id: db::room::Id,
+    rooms: Vec<RoomBreakoutEventItem>,
+}
+
+pub async fn breakout(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+    Json(payload): Json<BreakoutPayload>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = BreakoutRequest {
+        id: room_id,
+        rooms: payload.rooms,
+    };
+
+    BreakoutHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct BreakoutHandler;
+
+#[async_trait]
+impl RequestHandler for BreakoutHandler {
+    type Payload = BreakoutRequest;
+    const ERROR_TITLE: &'static str = "Failed to create breakout rooms";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        if payload.rooms.is_empty() {
+            return Err(anyhow!("At least one breakout room is required"))
+                .error(AppErrorKind::InvalidPayload);
+        }
+
+        let parent = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.id,
+                helpers::RoomTimeRequirement::NotClosed,
+                &mut conn,
+            )
+            .await?
+        };
+
+        tracing::Span::current().record(
+            "classroom_id",
+            tracing::field::display(parent.classroom_id()),
+        );
+
+        // Authorize room updating on the tenant.
+        let classroom_id = parent.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(parent.audience().into(), reqp, object, "update".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let mut conn = context.get_conn().await?;
+
+        // Only agents which are in the parent room may be assigned to breakout rooms.
+        let ready_agents = db::agent::ListQuery::new()
+            .room_id(parent.id())
+            .status(db::agent::Status::Ready)
+            .execute(&mut conn)
+            .await?
+            .into_iter()
+            .map(|agent| agent.agent_id().to_owned())
+            .collect::<HashSet<_>>();
+
+        let unknown_agent = payload
+            .rooms
+            .iter()
+            .flat_map(|breakout_room| breakout_room.agents.iter())
+            .find(|agent_id| !ready_agents.contains(*agent_id));
+
+        if let Some(agent_id) = unknown_agent {
+            return Err(anyhow!("Agent {} is not in the parent room", agent_id))
+                .error(AppErrorKind::InvalidPayload);
+        }
+
+        // Children share the parent's classroom, backend and closing time.
+        let time = (Bound::Included(Utc::now()), parent.time().1);
+
+        let children = conn
+            .transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    let mut children = Vec::with_capacity(payload.rooms.len());
+
+                    for breakout_room in payload.rooms {
+                        let mut q = db::room::InsertQuery::new(
+                            time,
+                            parent.audience(),
+                            parent.rtc_sharing_policy(),
+                            parent.classroom_id(),
+                        )
+                        .parent_id(parent.id());
+
+                        if let Some(backend_id) = parent.backend_id() {
+                            q = q.backend_id(backend_id);
+                        }
+
+                        if let Some(ref tags) = breakout_room.tags {
+                            q = q.tags(tags);
+                        }
+
+                        let child = q.execute(conn).await?;
+
+                        // Create a default group for minigroups
+                        if child.rtc_sharing_policy() == db::rtc::SharingPolicy::Owned {
+                            let groups = Groups::new(vec![GroupItem::new(0, vec![])]);
+                            db::group_agent::UpsertQuery::new(child.id(), &groups)
+                                .execute(conn)
+                                .await?;
+                        }
+
+                        children.push((child, breakout_room.agents));
+                    }
+
+                    Ok((children, parent))
+                })
+            })
+            .await;
+
+        let (children, parent) = children?;
+
+        let mut response = Response::new(
+            ResponseStatus::CREATED,
+            children
+                .iter()
+                .map(|(child, _)| child.clone())
+                .collect::<Vec<_>>(),
+            context.start_timestamp(),
+            Some(authz_time),
+        );
+
+        for (child, _) in &children {
+            response.add_notification(
+                "room.create",
+                &format!("audiences/{}/events", child.audience()),
+                child.clone(),
+                context.start_timestamp(),
+            );
+        }
+
+        let event = RoomBreakoutEvent {
+            id: parent.id(),
+            rooms: children
+                .into_iter()
+                .map(|(child, agents)| RoomBreakoutEventItem {
+                    id: child.id(),
+                    agents,
+                })
+                .collect(),
+        };
+
+        response.add_notification(
+            "room.breakout",
+            &format!("rooms/{}/events", parent.id()),
+            event,
+            context.start_timestamp(),
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .room_breakout
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BreakoutCloseRequest {
+    id: db::room::Id,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomBreakoutCloseEvent {
+    id: db::room::Id,
+    rooms: Vec<db::room::Id>,
+}
+
+pub async fn breakout_close(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = BreakoutCloseRequest { id: room_id };
+
+    BreakoutCloseHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct BreakoutCloseHandler;
+
+#[async_trait]
+impl RequestHandler for BreakoutCloseHandler {
+    type Payload = BreakoutCloseRequest;
+    const ERROR_TITLE: &'static str = "Failed to close breakout rooms";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let parent = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(payload.id, helpers::RoomTimeRequirement::Any, &mut conn)
+                .await?
+        };
+
+        tracing::Span::current().record(
+            "classroom_id",
+            tracing::field::display(parent.classroom_id()),
+        );
+
+        // Authorize room updating on the tenant.
+        let classroom_id = parent.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(parent.audience().into(), reqp, object, "update".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let closed_children = {
+            let mut conn = context.get_conn().await?;
+
+            let children = db::room::ListQuery::new()
+                .parent_id(parent.id())
+                .closed(false)
+                .execute(&mut conn)
+                .await?;
+
+            if let Some(child) = children.iter().find(|c| c.infinite()) {
+                return Err(anyhow!(
+                    "Not closing breakout rooms because room = '{}' is infinite",
+                    child.id()
+                ))
+                .error(AppErrorKind::RoomTimeChangingForbidden);
+            }
+
+            let agent_id = reqp.as_agent_id().clone();
+
+            // Either every breakout room gets closed or none of them.
+            conn.transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    let mut closed_children = Vec::with_capacity(children.len());
+
+                    for child in children {
+                        closed_children.push(close_room(&child, &agent_id, conn).await?);
+                    }
+
+                    Ok(closed_children)
+                })
+            })
+            .await?
+        };
+
+        let mut response = Response::new(
+            ResponseStatus::OK,
+            closed_children.clone(),
+            context.start_timestamp(),
+            Some(authz_time),
+        );
+
+        for child in &closed_children {
+            response.add_notification(
+                "room.close",
+                &format!("rooms/{}/events", child.id()),
+                child.clone(),
+                context.start_timestamp(),
+            );
+
+            response.add_notification(
+                "room.close",
+                &format!("audiences/{}/events", child.audience()),
+                child.clone(),
+                context.start_timestamp(),
+            );
+        }
+
+        // Instruct the agents to return to the parent room.
+        let event = RoomBreakoutCloseEvent {
+            id: parent.id(),
+            rooms: closed_children.iter().map(|c| c.id()).collect(),
+        };
+
+        response.add_notification(
+            "room.breakout_close",
+            &format!("rooms/{}/events", parent.id()),
+            event,
+            context.start_timestamp(),
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .room_breakout_close
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::AgentId;
+
+    #[derive(Deserialize)]
+    struct DynSubRequest {
+        subject: AgentId,
+        object: Vec<String>,
+    }
+
+    mod create {
+        use std::ops::Bound;
+
+        use chrono::Utc;
+        use serde_json::json;
+
+        use crate::db::group_agent::{GroupItem, Groups};
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn create(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            // Allow user to create rooms.
+            let mut authz = TestAuthz::new();
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+
+            // Make room.create request.
+            let mut context = TestContext::new(db, authz).await;
+            let time = (Bound::Unbounded, Bound::Unbounded);
+            let classroom_id = Uuid::new_v4();
+
+            let payload = CreateRequest {
+                time,
+                audience: USR_AUDIENCE.to_owned(),
+                backend: None,
+                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Shared),
+                reserve: Some(123),
+                tags: Some(json!({ "foo": "bar" })),
+                classroom_id,
+                lobby: false,
+                co_hosts: vec![],
+                parent_id: None,
+                preferred_region: None,
+            };
+
+            let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room creation failed");
+
+            // Assert response.
+            let (room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(room.audience(), USR_AUDIENCE);
+            assert_eq!(room.time(), time);
+            assert_eq!(room.rtc_sharing_policy(), db::rtc::SharingPolicy::Shared);
+            assert_eq!(room.reserve(), Some(123));
+            assert_eq!(room.tags(), &json!({ "foo": "bar" }));
+            assert_eq!(room.classroom_id(), classroom_id);
+
+            // Assert notification.
+            let (room, evp, topic) = find_event::<Room>(messages.as_slice());
+            assert!(topic.ends_with(&format!("/audiences/{}/events", USR_AUDIENCE)));
+            assert_eq!(evp.label(), "room.create");
+            assert_eq!(room.audience(), USR_AUDIENCE);
+            assert_eq!(room.time(), time);
+            assert_eq!(room.rtc_sharing_policy(), db::rtc::SharingPolicy::Shared);
+            assert_eq!(room.reserve(), Some(123));
             assert_eq!(room.tags(), &json!({ "foo": "bar" }));
             assert_eq!(room.classroom_id(), classroom_id);
         }
 
         #[sqlx::test]
-        async fn create_room_unauthorized(pool: sqlx::PgPool) {
+        async fn create_room_unauthorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            // Make room.create request.
+            let payload = CreateRequest {
+                time: (Bound::Included(Utc::now()), Bound::Unbounded),
+                audience: USR_AUDIENCE.to_owned(),
+                backend: None,
+                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Shared),
+                reserve: None,
+                tags: None,
+                classroom_id: Uuid::new_v4(),
+                lobby: false,
+                co_hosts: vec![],
+                parent_id: None,
+                preferred_region: None,
+            };
+
+            let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room creation");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn create_default_group(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            // Allow user to create rooms.
+            let mut authz = TestAuthz::new();
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+
+            // Make room.create request.
+            let mut context = TestContext::new(db, authz).await;
+            let time = (Bound::Unbounded, Bound::Unbounded);
+            let classroom_id = Uuid::new_v4();
+
+            let payload = CreateRequest {
+                time: time.clone(),
+                audience: USR_AUDIENCE.to_owned(),
+                backend: None,
+                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Owned),
+                reserve: Some(123),
+                tags: Some(json!({ "foo": "bar" })),
+                classroom_id,
+                lobby: false,
+                co_hosts: vec![],
+                parent_id: None,
+                preferred_region: None,
+            };
+
+            let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room creation failed");
+
+            let (room, _, _) = find_response::<Room>(messages.as_slice());
+
+            let mut conn = context
+                .get_conn()
+                .await
+                .expect("failed to get db connection");
+
+            let group_agent = db::group_agent::FindQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("failed to get group");
+
+            let groups = Groups::new(vec![GroupItem::new(0, vec![])]);
+            assert_eq!(group_agent.groups(), groups);
+        }
+
+        fn build_child_payload(parent: &Room, classroom_id: Uuid) -> CreateRequest {
+            CreateRequest {
+                time: (Bound::Unbounded, Bound::Unbounded),
+                audience: USR_AUDIENCE.to_owned(),
+                backend: None,
+                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Shared),
+                reserve: None,
+                tags: None,
+                classroom_id,
+                lobby: false,
+                co_hosts: vec![],
+                parent_id: Some(parent.id()),
+                preferred_region: None,
+            }
+        }
+
+        #[sqlx::test]
+        async fn create_child_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let parent = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            // Allow user to create rooms and to update the parent's classroom.
+            let mut authz = TestAuthz::new();
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let classroom_id = parent.classroom_id().to_string();
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.create request.
+            let mut context = TestContext::new(db, authz).await;
+            let payload = build_child_payload(&parent, parent.classroom_id());
+
+            let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room creation failed");
+
+            // Assert response.
+            let (room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(room.parent_id(), Some(parent.id()));
+            assert_eq!(room.classroom_id(), parent.classroom_id());
+        }
+
+        #[sqlx::test]
+        async fn create_child_room_unauthorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let parent = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            // Allow user to create rooms but not to update the parent's classroom.
+            let mut authz = TestAuthz::new();
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+
+            // Make room.create request.
+            let mut context = TestContext::new(db, authz).await;
+            let payload = build_child_payload(&parent, parent.classroom_id());
+
+            let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room creation");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn create_child_room_in_another_classroom(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let parent = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            // Allow user to create rooms and to update the parent's classroom.
+            let mut authz = TestAuthz::new();
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let classroom_id = parent.classroom_id().to_string();
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.create request linking to a parent from another classroom.
+            let mut context = TestContext::new(db, authz).await;
+            let payload = build_child_payload(&parent, Uuid::new_v4());
+
+            let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room creation");
+
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+        }
+    }
+
+    mod read {
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn read_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                // Create room.
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            // Allow agent to read the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+
+            // Make room.read request.
+            let mut context = TestContext::new(db, authz).await;
+            let payload = ReadRequest { id: room.id() };
+
+            let messages = handle_request::<ReadHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room reading failed");
+
+            // Assert response.
+            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(resp_room.audience(), room.audience());
+            assert_eq!(resp_room.time(), room.time());
+            assert_eq!(resp_room.rtc_sharing_policy(), room.rtc_sharing_policy());
+        }
+
+        #[sqlx::test]
+        async fn read_room_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let payload = ReadRequest { id: room.id() };
+
+            let err = handle_request::<ReadHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room reading");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn read_room_missing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let payload = ReadRequest {
+                id: db::room::Id::random(),
+            };
+
+            let err = handle_request::<ReadHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room reading");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_not_found");
+        }
+    }
+
+    mod list {
+        use std::{collections::HashSet, ops::Bound};
+
+        use chrono::{Duration, SubsecRound, Utc};
+        use serde_json::json;
+
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, prelude::*},
+        };
+
+        use super::super::*;
+
+        fn build_payload() -> ListRequest {
+            ListRequest {
+                audience: USR_AUDIENCE.to_owned(),
+                classroom_id: None,
+                time: None,
+                closed: None,
+                backend_id: None,
+                tags: None,
+                parent_id: None,
+                cursor: None,
+                limit: None,
+            }
+        }
+
+        #[sqlx::test]
+        async fn list_rooms(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let now = Utc::now().trunc_subsecs(0);
+            let classroom_id = Uuid::new_v4();
+
+            let (open_room, tagged_room, closed_room) = {
+                let mut conn = db.get_conn().await;
+
+                let open_room = db::room::InsertQuery::new(
+                    (Bound::Included(now), Bound::Unbounded),
+                    USR_AUDIENCE,
+                    db::rtc::SharingPolicy::Shared,
+                    classroom_id,
+                )
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert room");
+
+                let tags = json!({ "webinar_id": "123", "scope": "foo" });
+
+                let tagged_room = db::room::InsertQuery::new(
+                    (
+                        Bound::Included(now),
+                        Bound::Excluded(now + Duration::hours(1)),
+                    ),
+                    USR_AUDIENCE,
+                    db::rtc::SharingPolicy::Shared,
+                    classroom_id,
+                )
+                .tags(&tags)
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert room");
+
+                let closed_room = shared_helpers::insert_closed_room(&mut conn).await;
+                (open_room, tagged_room, closed_room)
+            };
+
+            // Allow agent to list rooms.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            authz.allow(agent.account_id(), vec!["classrooms"], "list");
+            let mut context = TestContext::new(db, authz).await;
+
+            // List all rooms.
+            let messages = handle_request::<ListHandler>(&mut context, &agent, build_payload())
+                .await
+                .expect("Rooms listing failed");
+
+            let (rooms, respp, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(rooms.len(), 3);
+
+            // Filter by classroom and state.
+            let payload = ListRequest {
+                classroom_id: Some(classroom_id),
+                closed: Some(false),
+                ..build_payload()
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Rooms listing failed");
+
+            let (rooms, _, _) = find_response::<Vec<Room>>(messages.as_slice());
+            let ids = rooms.iter().map(|r| r.id()).collect::<Vec<_>>();
+            assert_eq!(ids, vec![open_room.id(), tagged_room.id()]);
+
+            // Filter by closed state.
+            let payload = ListRequest {
+                closed: Some(true),
+                ..build_payload()
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Rooms listing failed");
+
+            let (rooms, _, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].id(), closed_room.id());
+
+            // Filter by tags.
+            let payload = ListRequest {
+                tags: Some(json!({ "webinar_id": "123" })),
+                ..build_payload()
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Rooms listing failed");
+
+            let (rooms, _, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].id(), tagged_room.id());
+
+            // Filter by time overlap.
+            let payload = ListRequest {
+                time: Some((
+                    Bound::Included(now - Duration::hours(9)),
+                    Bound::Excluded(now - Duration::hours(8) - Duration::minutes(30)),
+                )),
+                ..build_payload()
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Rooms listing failed");
+
+            let (rooms, _, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].id(), closed_room.id());
+        }
+
+        #[sqlx::test]
+        async fn list_rooms_with_cursor(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let rooms = {
+                let mut conn = db.get_conn().await;
+                let mut rooms = vec![];
+
+                for _ in 0..3 {
+                    rooms.push(shared_helpers::insert_room(&mut conn).await);
+                }
+
+                rooms
+            };
+
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            authz.allow(agent.account_id(), vec!["classrooms"], "list");
+            let mut context = TestContext::new(db, authz).await;
+
+            // Fetch the first page.
+            let payload = ListRequest {
+                limit: Some(2),
+                ..build_payload()
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Rooms listing failed");
+
+            let (first_page, _, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(first_page.len(), 2);
+
+            // Fetch the second page using the last room as a cursor.
+            let payload = ListRequest {
+                cursor: first_page.last().map(|r| r.id()),
+                limit: Some(2),
+                ..build_payload()
+            };
+
+            let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Rooms listing failed");
+
+            let (second_page, _, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(second_page.len(), 1);
+
+            let ids = first_page
+                .iter()
+                .chain(second_page.iter())
+                .map(|r| r.id())
+                .collect::<HashSet<_>>();
+
+            let expected_ids = rooms.iter().map(|r| r.id()).collect::<HashSet<_>>();
+            assert_eq!(ids, expected_ids);
+        }
+
+        #[sqlx::test]
+        async fn list_rooms_with_invalid_pagination(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            authz.allow(agent.account_id(), vec!["classrooms"], "list");
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = ListRequest {
+                limit: Some(-1),
+                ..build_payload()
+            };
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on rooms listing");
+
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+
+            let payload = ListRequest {
+                cursor: Some(db::room::Id::random()),
+                ..build_payload()
+            };
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on rooms listing");
+
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+        }
+
+        #[sqlx::test]
+        async fn list_rooms_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, build_payload())
+                .await
+                .expect_err("Unexpected success on rooms listing");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+    }
+
+    mod update {
+        use std::ops::Bound;
+
+        use chrono::{Duration, SubsecRound, Utc};
+        use serde_json::json;
+        use uuid::Uuid;
+
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn update_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let now = Utc::now().trunc_subsecs(0);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Unbounded, Bound::Unbounded))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+            let classroom_id = Uuid::new_v4();
+
+            let time = (
+                Bound::Included(now + Duration::minutes(50)),
+                Bound::Unbounded,
+            );
+
+            let payload = UpdateRequest {
+                id: room.id(),
+                time: Some(time),
+                reserve: Some(Some(123)),
+                tags: Some(json!({"foo": "bar"})),
+                classroom_id: Some(classroom_id),
+                host: Some(agent.agent_id().clone()),
+                lobby: None,
+                locked: None,
+                co_hosts: None,
+            };
+
+            let messages = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room update failed");
+
+            // Assert response.
+            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(resp_room.id(), room.id());
+            assert_eq!(resp_room.audience(), room.audience());
+            assert_eq!(resp_room.time(), time);
+            assert_eq!(
+                resp_room.rtc_sharing_policy(),
+                db::rtc::SharingPolicy::Shared
+            );
+            assert_eq!(resp_room.reserve(), Some(123));
+            assert_eq!(resp_room.tags(), &json!({"foo": "bar"}));
+            assert_eq!(resp_room.classroom_id(), classroom_id);
+            assert_eq!(resp_room.host(), Some(agent.agent_id()));
+        }
+
+        #[sqlx::test]
+        async fn update_room_lock(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = UpdateRequest {
+                id: room.id(),
+                time: None,
+                reserve: None,
+                tags: None,
+                classroom_id: None,
+                host: None,
+                lobby: None,
+                locked: Some(true),
+                co_hosts: None,
+            };
+
+            let messages = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room update failed");
+
+            // Assert response.
+            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert!(resp_room.locked());
+
+            // Assert notification to the room topic.
+            let room_topic = format!("rooms/{}/events", room.id());
+
+            let (event_room, _, _) =
+                find_event_by_predicate::<Room, _>(messages.as_slice(), |evp, _, topic| {
+                    evp.label() == "room.update" && topic.ends_with(&room_topic)
+                })
+                .expect("Failed to find room.update event");
+
+            assert!(event_room.locked());
+        }
+
+        #[sqlx::test]
+        async fn update_room_with_wrong_time(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let now = Utc::now().trunc_subsecs(0);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((
+                        Bound::Included(now - Duration::hours(1)),
+                        Bound::Excluded(now + Duration::hours(2)),
+                    ))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let time = (
+                Bound::Included(now + Duration::hours(3)),
+                Bound::Excluded(now - Duration::hours(2)),
+            );
+
+            let payload = UpdateRequest {
+                id: room.id(),
+                time: Some(time),
+                reserve: Some(Some(123)),
+                tags: Some(json!({"foo": "bar"})),
+                classroom_id: None,
+                host: None,
+                lobby: None,
+                locked: None,
+                co_hosts: None,
+            };
+
+            handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Room update succeeded when it should've failed");
+        }
+
+        #[sqlx::test]
+        async fn update_and_close_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let now = Utc::now().trunc_subsecs(0);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((
+                        Bound::Included(now - Duration::hours(1)),
+                        Bound::Excluded(now + Duration::hours(5)),
+                    ))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let time = (
+                Bound::Included(now - Duration::hours(1)),
+                Bound::Excluded(now - Duration::seconds(5)),
+            );
+
+            let payload = UpdateRequest {
+                id: room.id(),
+                time: Some(time),
+                reserve: Some(Some(123)),
+                tags: Default::default(),
+                classroom_id: Default::default(),
+                host: None,
+                lobby: None,
+                locked: None,
+                co_hosts: None,
+            };
+
+            let messages = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room update failed");
+
+            assert_eq!(messages.len(), 4);
+
+            let (closed_tenant_notification, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, topic| {
+                    evp.label() == "room.close" && topic.contains("audiences")
+                })
+                .expect("Failed to find room.close event");
+
+            assert_eq!(
+                closed_tenant_notification
+                    .get("id")
+                    .and_then(|v| v.as_str()),
+                Some(room.id().to_string()).as_deref()
+            );
+
+            let (closed_room_notification, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, topic| {
+                    evp.label() == "room.close" && topic.contains("rooms")
+                })
+                .expect("Failed to find room.close event");
+
+            assert_eq!(
+                closed_room_notification.get("id").and_then(|v| v.as_str()),
+                Some(room.id().to_string()).as_deref()
+            );
+        }
+
+        #[sqlx::test]
+        async fn update_and_close_unbounded_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let now = Utc::now().trunc_subsecs(0);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Included(now - Duration::hours(1)), Bound::Unbounded))
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let time = (
+                Bound::Included(now - Duration::hours(1)),
+                Bound::Excluded(now - Duration::seconds(5)),
+            );
+
+            let payload = UpdateRequest {
+                id: room.id(),
+                time: Some(time),
+                reserve: Default::default(),
+                tags: Default::default(),
+                classroom_id: Default::default(),
+                host: None,
+                lobby: None,
+                locked: None,
+                co_hosts: None,
+            };
+
+            handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room update failed");
+        }
+
+        #[sqlx::test]
+        async fn update_room_missing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = UpdateRequest {
+                id: db::room::Id::random(),
+                time: Default::default(),
+                reserve: Default::default(),
+                tags: Default::default(),
+                classroom_id: Default::default(),
+                host: None,
+                lobby: None,
+                locked: None,
+                co_hosts: None,
+            };
+
+            let err = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room update");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_not_found");
+        }
+
+        #[sqlx::test]
+        async fn update_room_closed(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create closed room.
+                shared_helpers::insert_closed_room(&mut conn).await
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = UpdateRequest {
+                id: room.id(),
+                time: Some((Bound::Included(Utc::now()), Bound::Excluded(Utc::now()))),
+                reserve: Default::default(),
+                tags: Default::default(),
+                classroom_id: Default::default(),
+                host: None,
+                lobby: None,
+                locked: None,
+                co_hosts: None,
+            };
+
+            let err = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room update");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_closed");
+        }
+    }
+
+    mod close {
+        use std::ops::Bound;
+
+        use chrono::{Duration, Utc};
+
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn close_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Unbounded, Bound::Unbounded))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = CloseRequest { id: room.id() };
+
+            let messages = handle_request::<CloseHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room close failed");
+
+            // Assert response.
+            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(resp_room.id(), room.id());
+            let end = match resp_room.time().1 {
+                Bound::Excluded(t) => t,
+                _ => unreachable!("Wrong end in room close"),
+            };
+            assert!(end < Utc::now());
+            assert_eq!(
+                resp_room.rtc_sharing_policy(),
+                db::rtc::SharingPolicy::Shared
+            );
+        }
+
+        #[sqlx::test]
+        async fn close_infinite_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Unbounded, Bound::Unbounded))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .infinite()
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = CloseRequest { id: room.id() };
+
+            handle_request::<CloseHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Room close succeeded even though it shouldn't");
+        }
+
+        #[sqlx::test]
+        async fn close_bounded_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((
+                        Bound::Included(Utc::now() - Duration::hours(10)),
+                        Bound::Excluded(Utc::now() + Duration::hours(10)),
+                    ))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .insert(&mut conn)
+                    .await
+            };
+
+            // Allow agent to update the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
+
+            // Make room.update request.
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = CloseRequest { id: room.id() };
+
+            let messages = handle_request::<CloseHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room close failed");
+
+            // Assert response.
+            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(resp_room.id(), room.id());
+            let end = match resp_room.time().1 {
+                Bound::Excluded(t) => t,
+                _ => unreachable!("Wrong end in room close"),
+            };
+            assert!(end < Utc::now());
+            assert_eq!(
+                resp_room.rtc_sharing_policy(),
+                db::rtc::SharingPolicy::Shared
+            );
+        }
+
+        #[sqlx::test]
+        async fn close_room_missing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = CloseRequest {
+                id: db::room::Id::random(),
+            };
+
+            let err = handle_request::<CloseHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room close");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_not_found");
+        }
+
+        #[sqlx::test]
+        async fn close_room_closed(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create closed room.
+                shared_helpers::insert_closed_room(&mut conn).await
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = CloseRequest { id: room.id() };
+
+            let err = handle_request::<CloseHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room close");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_closed");
+        }
+    }
+
+    mod enter {
+        use chrono::{Duration, Utc};
+        use serde_json::Value as JsonValue;
+
+        use crate::db::group_agent::{GroupItem, Groups};
+        use crate::test_helpers::{db::TestDb, parse_messages, prelude::*, test_deps::LocalDeps};
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn enter_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                // Create room.
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            // Allow agent to subscribe to the rooms' events.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id, "rtcs"],
+                "create",
+            );
+
+            // Make room.enter request.
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .expect("Room entrance failed");
+        }
+
+        #[sqlx::test]
+        async fn enter_room_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            let context = TestContext::new(db, TestAuthz::new()).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .err()
+                .expect("Unexpected success on room entering");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn enter_room_with_lobby(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Included(Utc::now()), Bound::Unbounded))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .lobby()
+                    .insert(&mut conn)
+                    .await
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+
+            let context = TestContext::new(db.clone(), authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: agent.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .expect("Room entrance failed");
+
+            // Assert the agent waits in the lobby.
+            let mut conn = db.get_conn().await;
+
+            let pending = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .status(db::agent::Status::Pending)
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
+
+            assert_eq!(pending.len(), 1);
+
+            let ready = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
+
+            assert!(ready.is_empty());
+        }
+
+        #[sqlx::test]
+        async fn enter_room_with_metadata(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Included(Utc::now()), Bound::Unbounded))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                    .lobby()
+                    .insert(&mut conn)
+                    .await
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+
+            let context = TestContext::new(db.clone(), authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: Some(db::agent::Metadata {
+                    display_name: Some("John Doe".to_owned()),
+                    avatar_url: Some("https://example.org/avatar.png".to_owned()),
+                    client_version: Some("1.2.3".to_owned()),
+                    device_type: Some("desktop".to_owned()),
+                }),
+            };
+
+            let reqp = build_reqp(agent.agent_id(), "room.enter");
+            let response = EnterHandler::handle(
+                Arc::new(context),
+                payload,
+                RequestParams::MqttParams(&reqp),
+                Utc::now(),
+            )
+            .await
+            .expect("Room entrance failed");
+
+            let messages = parse_messages(
+                response
+                    .into_mqtt_messages(&reqp)
+                    .expect("Failed to build messages"),
+            )
+            .await;
+
+            // Assert the metadata is present in the notification.
+            let (event, _, _) = find_event::<JsonValue>(messages.as_slice());
+            assert_eq!(event["agent_id"], json!(agent.agent_id()));
+            assert_eq!(event["metadata"]["display_name"], "John Doe");
+            assert_eq!(event["metadata"]["device_type"], "desktop");
+
+            // Assert the metadata is stored along with the agent.
+            let mut conn = db.get_conn().await;
+
+            let agents = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .status(db::agent::Status::Pending)
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
+
+            let metadata = agents[0].metadata().expect("Missing agent metadata");
+            assert_eq!(metadata.display_name.as_deref(), Some("John Doe"));
+            assert_eq!(metadata.client_version.as_deref(), Some("1.2.3"));
+        }
+
+        #[sqlx::test]
+        async fn enter_room_with_invalid_metadata(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
+            };
+
+            let context = Arc::new(TestContext::new(db, TestAuthz::new()).await);
+
+            let invalid = vec![
+                db::agent::Metadata {
+                    display_name: Some("x".repeat(MAX_DISPLAY_NAME_LEN + 1)),
+                    ..Default::default()
+                },
+                db::agent::Metadata {
+                    avatar_url: Some("javascript:alert(1)".to_owned()),
+                    ..Default::default()
+                },
+            ];
+
+            for metadata in invalid {
+                let payload = EnterRequest {
+                    id: room.id(),
+                    metadata: Some(metadata),
+                };
+
+                let reqp = RequestParams::Http {
+                    agent_id: agent.agent_id(),
+                };
+                let err = EnterHandler::handle(context.clone(), payload, reqp, Utc::now())
+                    .await
+                    .err()
+                    .expect("Unexpected success on room entering");
+
+                assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+                assert_eq!(err.kind(), "invalid_payload");
+            }
+        }
+
+        #[sqlx::test]
+        async fn enter_locked_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                db::room::UpdateQuery::new(room.id())
+                    .locked(Some(true))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to lock room")
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .err()
+                .expect("Unexpected success on room entering");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "room_locked");
+        }
+
+        #[sqlx::test]
+        async fn reenter_locked_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+
+                db::room::UpdateQuery::new(room.id())
+                    .locked(Some(true))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to lock room")
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: agent.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .expect("Room entrance failed");
+        }
+
+        #[sqlx::test]
+        async fn enter_locked_room_from_lobby(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
-            // Make room.create request.
-            let payload = CreateRequest {
-                time: (Bound::Included(Utc::now()), Bound::Unbounded),
-                audience: USR_AUDIENCE.to_owned(),
-                backend: None,
-                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Shared),
-                reserve: None,
-                tags: None,
-                classroom_id: Uuid::new_v4(),
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                // The agent is still waiting for the host approval.
+                factory::Agent::new()
+                    .agent_id(agent.agent_id())
+                    .room_id(room.id())
+                    .status(db::agent::Status::Pending)
+                    .insert(&mut conn)
+                    .await;
+
+                db::room::UpdateQuery::new(room.id())
+                    .locked(Some(true))
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to lock room")
             };
 
-            let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
+
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .expect_err("Unexpected success on room creation");
+                .err()
+                .expect("Unexpected success on room entering");
 
             assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
-            assert_eq!(err.kind(), "access_denied");
+            assert_eq!(err.kind(), "room_locked");
         }
 
         #[sqlx::test]
-        async fn create_default_group(pool: sqlx::PgPool) {
+        async fn enter_room_banned(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-
-            // Allow user to create rooms.
-            let mut authz = TestAuthz::new();
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            authz.allow(agent.account_id(), vec!["classrooms"], "create");
 
-            // Make room.create request.
-            let mut context = TestContext::new(db, authz).await;
-            let time = (Bound::Unbounded, Bound::Unbounded);
-            let classroom_id = Uuid::new_v4();
+            let room = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
 
-            let payload = CreateRequest {
-                time: time.clone(),
-                audience: USR_AUDIENCE.to_owned(),
-                backend: None,
-                rtc_sharing_policy: Some(db::rtc::SharingPolicy::Owned),
-                reserve: Some(123),
-                tags: Some(json!({ "foo": "bar" })),
-                classroom_id,
-            };
+                db::room_ban::InsertQuery::new(room.id(), agent.agent_id(), host.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to ban agent");
 
-            let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
-                .await
-                .expect("Room creation failed");
+                room
+            };
 
-            let (room, _, _) = find_response::<Room>(messages.as_slice());
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
 
-            let mut conn = context
-                .get_conn()
-                .await
-                .expect("failed to get db connection");
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
 
-            let group_agent = db::group_agent::FindQuery::new(room.id())
-                .execute(&mut conn)
+            let reqp = RequestParams::Http {
+                agent_id: agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .expect("failed to get group");
+                .err()
+                .expect("Unexpected success on room entering");
 
-            let groups = Groups::new(vec![GroupItem::new(0, vec![])]);
-            assert_eq!(group_agent.groups(), groups);
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "agent_banned");
         }
-    }
 
-    mod read {
-        use crate::{
-            db::room::Object as Room,
-            test_helpers::{db::TestDb, prelude::*},
-        };
+        #[sqlx::test]
+        async fn enter_room_missing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let context = TestContext::new(db, TestAuthz::new()).await;
 
-        use super::super::*;
+            let payload = EnterRequest {
+                id: db::room::Id::random(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .err()
+                .expect("Unexpected success on room entering");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_not_found");
+        }
 
         #[sqlx::test]
-        async fn read_room(pool: sqlx::PgPool) {
+        async fn enter_room_closed(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
 
             let room = {
                 let mut conn = db.get_conn().await;
-                // Create room.
-                shared_helpers::insert_room(&mut conn).await
+                // Create closed room.
+                shared_helpers::insert_closed_room(&mut conn).await
             };
 
-            // Allow agent to read the room.
+            // Allow agent to subscribe to the rooms' events.
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
@@ -1104,1044 +4059,1203 @@ mod test {
                 "read",
             );
 
-            // Make room.read request.
-            let mut context = TestContext::new(db, authz).await;
-            let payload = ReadRequest { id: room.id() };
+            // Make room.enter request.
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
 
-            let messages = handle_request::<ReadHandler>(&mut context, &agent, payload)
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .expect("Room reading failed");
+                .err()
+                .expect("Unexpected success on room entering");
 
-            // Assert response.
-            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
-            assert_eq!(respp.status(), ResponseStatus::OK);
-            assert_eq!(resp_room.audience(), room.audience());
-            assert_eq!(resp_room.time(), room.time());
-            assert_eq!(resp_room.rtc_sharing_policy(), room.rtc_sharing_policy());
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_closed");
         }
 
         #[sqlx::test]
-        async fn read_room_not_authorized(pool: sqlx::PgPool) {
+        async fn enter_room_with_no_opening_time(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
             let room = {
                 let mut conn = db.get_conn().await;
-                shared_helpers::insert_room(&mut conn).await
+
+                // Create room without time.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((Bound::Unbounded, Bound::Unbounded))
+                    .insert(&mut conn)
+                    .await
             };
 
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
-            let payload = ReadRequest { id: room.id() };
+            // Allow agent to subscribe to the rooms' events.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
 
-            let err = handle_request::<ReadHandler>(&mut context, &agent, payload)
+            // Make room.enter request.
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
+
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .expect_err("Unexpected success on room reading");
+                .err()
+                .expect("Unexpected success on room entering");
 
-            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
-            assert_eq!(err.kind(), "access_denied");
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_closed");
         }
 
         #[sqlx::test]
-        async fn read_room_missing(pool: sqlx::PgPool) {
+        async fn enter_room_that_opens_in_the_future(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
 
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
-            let payload = ReadRequest {
-                id: db::room::Id::random(),
-            };
-
-            let err = handle_request::<ReadHandler>(&mut context, &agent, payload)
-                .await
-                .expect_err("Unexpected success on room reading");
-
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_not_found");
-        }
-    }
+            let room = {
+                let mut conn = db.get_conn().await;
 
-    mod update {
-        use std::ops::Bound;
+                // Create room without time.
+                factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((
+                        Bound::Included(Utc::now() + Duration::hours(1)),
+                        Bound::Unbounded,
+                    ))
+                    .insert(&mut conn)
+                    .await
+            };
 
-        use chrono::{Duration, SubsecRound, Utc};
-        use serde_json::json;
-        use uuid::Uuid;
+            // Allow agent to subscribe to the rooms' events.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id],
+                "read",
+            );
 
-        use crate::{
-            db::room::Object as Room,
-            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
-        };
+            // Make room.enter request.
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
+                id: room.id(),
+                metadata: None,
+            };
 
-        use super::super::*;
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .expect("Room entrance failed");
+        }
 
         #[sqlx::test]
-        async fn update_room(pool: sqlx::PgPool) {
+        async fn add_new_participant_to_default_group(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-            let now = Utc::now().trunc_subsecs(0);
+            let mut conn = db.get_conn().await;
 
-            let room = {
-                let mut conn = db.get_conn().await;
+            // Create room.
+            let room = shared_helpers::insert_room_with_owned(&mut conn).await;
 
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((Bound::Unbounded, Bound::Unbounded))
-                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
-                    .insert(&mut conn)
-                    .await
-            };
+            factory::GroupAgent::new(room.id(), Groups::new(vec![GroupItem::new(0, vec![])]))
+                .upsert(&mut conn)
+                .await;
 
-            // Allow agent to update the room.
+            // Allow agent to subscribe to the rooms' events.
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
                 agent.account_id(),
                 vec!["classrooms", &classroom_id],
-                "update",
+                "read",
             );
-
-            // Make room.update request.
-            let mut context = TestContext::new(db, authz).await;
-            let classroom_id = Uuid::new_v4();
-
-            let time = (
-                Bound::Included(now + Duration::minutes(50)),
-                Bound::Unbounded,
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id, "rtcs"],
+                "create",
             );
 
-            let payload = UpdateRequest {
+            // Make room.enter request.
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
                 id: room.id(),
-                time: Some(time),
-                reserve: Some(Some(123)),
-                tags: Some(json!({"foo": "bar"})),
-                classroom_id: Some(classroom_id),
-                host: Some(agent.agent_id().clone()),
+                metadata: None,
             };
 
-            let messages = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .expect("Room update failed");
+                .expect("Room entrance failed");
 
-            // Assert response.
-            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
-            assert_eq!(respp.status(), ResponseStatus::OK);
-            assert_eq!(resp_room.id(), room.id());
-            assert_eq!(resp_room.audience(), room.audience());
-            assert_eq!(resp_room.time(), time);
-            assert_eq!(
-                resp_room.rtc_sharing_policy(),
-                db::rtc::SharingPolicy::Shared
-            );
-            assert_eq!(resp_room.reserve(), Some(123));
-            assert_eq!(resp_room.tags(), &json!({"foo": "bar"}));
-            assert_eq!(resp_room.classroom_id(), classroom_id);
-            assert_eq!(resp_room.host(), Some(agent.agent_id()));
+            let group_agent = db::group_agent::FindQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("failed to find a group agent");
+
+            let groups = Groups::new(vec![GroupItem::new(0, vec![agent.agent_id().clone()])]);
+            assert_eq!(group_agent.groups(), groups);
         }
 
         #[sqlx::test]
-        async fn update_room_with_wrong_time(pool: sqlx::PgPool) {
+        async fn existed_participant_in_group(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-            let now = Utc::now().trunc_subsecs(0);
+            let mut conn = db.get_conn().await;
 
-            let room = {
-                let mut conn = db.get_conn().await;
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((
-                        Bound::Included(now - Duration::hours(1)),
-                        Bound::Excluded(now + Duration::hours(2)),
-                    ))
-                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
-                    .insert(&mut conn)
-                    .await
-            };
+            // Create room.
+            let room = shared_helpers::insert_room_with_owned(&mut conn).await;
 
-            // Allow agent to update the room.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            factory::GroupAgent::new(
+                room.id(),
+                Groups::new(vec![GroupItem::new(0, vec![agent.agent_id().clone()])]),
+            )
+            .upsert(&mut conn)
+            .await;
+
+            // Allow agent to subscribe to the rooms' events.
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
                 agent.account_id(),
                 vec!["classrooms", &classroom_id],
-                "update",
+                "read",
             );
-
-            // Make room.update request.
-            let mut context = TestContext::new(db, authz).await;
-
-            let time = (
-                Bound::Included(now + Duration::hours(3)),
-                Bound::Excluded(now - Duration::hours(2)),
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &classroom_id, "rtcs"],
+                "create",
             );
 
-            let payload = UpdateRequest {
+            // Make room.enter request.
+            let context = TestContext::new(db, authz).await;
+            let payload = EnterRequest {
                 id: room.id(),
-                time: Some(time),
-                reserve: Some(Some(123)),
-                tags: Some(json!({"foo": "bar"})),
-                classroom_id: None,
-                host: None,
+                metadata: None,
             };
 
-            handle_request::<UpdateHandler>(&mut context, &agent, payload)
+            let reqp = RequestParams::Http {
+                agent_id: &agent.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .expect_err("Room update succeeded when it should've failed");
+                .expect("Room entrance failed");
+
+            let group_agent = db::group_agent::FindQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("failed to get group agents");
+
+            assert_eq!(group_agent.groups().len(), 1);
         }
 
         #[sqlx::test]
-        async fn update_and_close_room(pool: sqlx::PgPool) {
+        async fn create_reader_configs(pool: sqlx::PgPool) {
+            let local_deps = LocalDeps::new();
+            let janus = local_deps.run_janus();
+            let (session_id, handle_id) = shared_helpers::init_janus(&janus.url).await;
             let db = TestDb::new(pool);
-            let now = Utc::now().trunc_subsecs(0);
 
-            let room = {
-                let mut conn = db.get_conn().await;
+            let agent1 = TestAgent::new("web", "user1", USR_AUDIENCE);
+            let agent2 = TestAgent::new("web", "user2", USR_AUDIENCE);
 
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((
-                        Bound::Included(now - Duration::hours(1)),
-                        Bound::Excluded(now + Duration::hours(5)),
-                    ))
-                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+            let mut conn = db.get_conn().await;
+
+            let backend =
+                shared_helpers::insert_janus_backend(&mut conn, &janus.url, session_id, handle_id)
+                    .await;
+
+            let room = factory::Room::new()
+                .audience(USR_AUDIENCE)
+                .time((Bound::Included(Utc::now()), Bound::Unbounded))
+                .rtc_sharing_policy(RtcSharingPolicy::Owned)
+                .backend_id(backend.id())
+                .insert(&mut conn)
+                .await;
+
+            factory::GroupAgent::new(
+                room.id(),
+                Groups::new(vec![
+                    GroupItem::new(0, vec![]),
+                    GroupItem::new(1, vec![agent1.agent_id().clone()]),
+                ]),
+            )
+            .upsert(&mut conn)
+            .await;
+
+            for agent in &[&agent1, &agent2] {
+                factory::Rtc::new(room.id())
+                    .created_by(agent.agent_id().to_owned())
                     .insert(&mut conn)
-                    .await
-            };
+                    .await;
+            }
 
-            // Allow agent to update the room.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            // Allow agent to subscribe to the rooms' events.
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                agent2.account_id(),
                 vec!["classrooms", &classroom_id],
-                "update",
+                "read",
             );
 
-            // Make room.update request.
+            // Make room.enter request.
             let mut context = TestContext::new(db, authz).await;
+            let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+            context.with_janus(tx);
 
-            let time = (
-                Bound::Included(now - Duration::hours(1)),
-                Bound::Excluded(now - Duration::seconds(5)),
-            );
-
-            let payload = UpdateRequest {
+            let payload = EnterRequest {
                 id: room.id(),
-                time: Some(time),
-                reserve: Some(Some(123)),
-                tags: Default::default(),
-                classroom_id: Default::default(),
-                host: None,
+                metadata: None,
             };
 
-            let messages = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+            let reqp = RequestParams::Http {
+                agent_id: &agent2.agent_id(),
+            };
+            EnterHandler::handle(Arc::new(context.clone()), payload, reqp, Utc::now())
                 .await
-                .expect("Room update failed");
-
-            assert_eq!(messages.len(), 4);
+                .expect("Room entrance failed");
 
-            let (closed_tenant_notification, _, _) =
-                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, topic| {
-                    evp.label() == "room.close" && topic.contains("audiences")
-                })
-                .expect("Failed to find room.close event");
+            let group_agent = db::group_agent::FindQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("failed to get group agents");
 
-            assert_eq!(
-                closed_tenant_notification
-                    .get("id")
-                    .and_then(|v| v.as_str()),
-                Some(room.id().to_string()).as_deref()
-            );
+            assert_eq!(group_agent.groups().len(), 2);
 
-            let (closed_room_notification, _, _) =
-                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, topic| {
-                    evp.label() == "room.close" && topic.contains("rooms")
-                })
-                .expect("Failed to find room.close event");
+            let reader_configs = db::rtc_reader_config::ListWithRtcQuery::new(
+                room.id(),
+                &[agent1.agent_id(), agent2.agent_id()],
+            )
+            .execute(&mut conn)
+            .await
+            .expect("failed to get rtc reader configs");
 
-            assert_eq!(
-                closed_room_notification.get("id").and_then(|v| v.as_str()),
-                Some(room.id().to_string()).as_deref()
-            );
+            assert_eq!(reader_configs.len(), 2);
+
+            context.janus_clients().remove_client(&backend);
         }
+    }
+
+    mod leave {
+        use crate::test_helpers::{db::TestDb, prelude::*};
+
+        use super::{super::*, DynSubRequest};
 
         #[sqlx::test]
-        async fn update_and_close_unbounded_room(pool: sqlx::PgPool) {
+        async fn leave_room(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-            let now = Utc::now().trunc_subsecs(0);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
-            let room = {
-                let mut conn = db.get_conn().await;
+            let mut conn = db.get_conn().await;
 
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((Bound::Included(now - Duration::hours(1)), Bound::Unbounded))
-                    .insert(&mut conn)
-                    .await
-            };
+            // Create room.
+            let room = shared_helpers::insert_room(&mut conn).await;
+            // Put agent online in the room.
+            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
 
-            // Allow agent to update the room.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut authz = TestAuthz::new();
-            let classroom_id = room.classroom_id().to_string();
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &classroom_id],
-                "update",
-            );
+            // Make room.leave request.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let payload = LeaveRequest { id: room.id() };
 
-            // Make room.update request.
-            let mut context = TestContext::new(db, authz).await;
+            let messages = handle_request::<LeaveHandler>(&mut context, &agent, payload)
+                .await
+                .expect("Room leaving failed");
 
-            let time = (
-                Bound::Included(now - Duration::hours(1)),
-                Bound::Excluded(now - Duration::seconds(5)),
+            // Assert dynamic subscription request.
+            let (payload, reqp, topic) = find_request::<DynSubRequest>(messages.as_slice());
+
+            let expected_topic = format!(
+                "agents/{}.{}/api/{}/out/{}",
+                context.config().agent_label,
+                context.config().id,
+                API_VERSION,
+                context.config().broker_id,
             );
 
-            let payload = UpdateRequest {
-                id: room.id(),
-                time: Some(time),
-                reserve: Default::default(),
-                tags: Default::default(),
-                classroom_id: Default::default(),
-                host: None,
-            };
+            assert_eq!(topic, expected_topic);
+            assert_eq!(reqp.method(), "subscription.delete");
+            assert_eq!(&payload.subject, agent.agent_id());
 
-            handle_request::<UpdateHandler>(&mut context, &agent, payload)
-                .await
-                .expect("Room update failed");
+            let room_id = room.id().to_string();
+            assert_eq!(payload.object, vec!["rooms", &room_id, "events"]);
         }
 
         #[sqlx::test]
-        async fn update_room_missing(pool: sqlx::PgPool) {
+        async fn leave_room_while_not_entered(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
 
-            let payload = UpdateRequest {
-                id: db::room::Id::random(),
-                time: Default::default(),
-                reserve: Default::default(),
-                tags: Default::default(),
-                classroom_id: Default::default(),
-                host: None,
+            let room = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
             };
 
-            let err = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let payload = LeaveRequest { id: room.id() };
+
+            let err = handle_request::<LeaveHandler>(&mut context, &agent, payload)
                 .await
-                .expect_err("Unexpected success on room update");
+                .expect_err("Unexpected success on room leaving");
 
             assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_not_found");
+            assert_eq!(err.kind(), "agent_not_entered_the_room");
         }
 
         #[sqlx::test]
-        async fn update_room_closed(pool: sqlx::PgPool) {
+        async fn leave_room_missing(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-
-            let room = {
-                let mut conn = db.get_conn().await;
-
-                // Create closed room.
-                shared_helpers::insert_closed_room(&mut conn).await
-            };
-
             let mut context = TestContext::new(db, TestAuthz::new()).await;
 
-            let payload = UpdateRequest {
-                id: room.id(),
-                time: Some((Bound::Included(Utc::now()), Bound::Excluded(Utc::now()))),
-                reserve: Default::default(),
-                tags: Default::default(),
-                classroom_id: Default::default(),
-                host: None,
+            let payload = LeaveRequest {
+                id: db::room::Id::random(),
             };
 
-            let err = handle_request::<UpdateHandler>(&mut context, &agent, payload)
+            let err = handle_request::<LeaveHandler>(&mut context, &agent, payload)
                 .await
-                .expect_err("Unexpected success on room update");
+                .expect_err("Unexpected success on room leaving");
 
             assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_closed");
+            assert_eq!(err.kind(), "room_not_found");
         }
     }
 
-    mod close {
-        use std::ops::Bound;
-
-        use chrono::{Duration, Utc};
-
-        use crate::{
-            db::room::Object as Room,
-            test_helpers::{db::TestDb, prelude::*},
-        };
+    mod kick {
+        use crate::test_helpers::{db::TestDb, prelude::*};
 
         use super::super::*;
 
+        #[derive(Deserialize)]
+        struct KickEvent {
+            id: db::room::Id,
+            agent_id: AgentId,
+            banned: bool,
+        }
+
         #[sqlx::test]
-        async fn close_room(pool: sqlx::PgPool) {
+        async fn kick_agent(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
             let room = {
                 let mut conn = db.get_conn().await;
-
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((Bound::Unbounded, Bound::Unbounded))
-                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
-                    .insert(&mut conn)
-                    .await
+                let room = shared_helpers::insert_room(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+                room
             };
 
-            // Allow agent to update the room.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                host.account_id(),
                 vec!["classrooms", &classroom_id],
                 "update",
             );
 
-            // Make room.update request.
-            let mut context = TestContext::new(db, authz).await;
-
-            let payload = CloseRequest { id: room.id() };
-
-            let messages = handle_request::<CloseHandler>(&mut context, &agent, payload)
-                .await
-                .expect("Room close failed");
+            let mut context = TestContext::new(db.clone(), authz).await;
 
-            // Assert response.
-            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
-            assert_eq!(respp.status(), ResponseStatus::OK);
-            assert_eq!(resp_room.id(), room.id());
-            let end = match resp_room.time().1 {
-                Bound::Excluded(t) => t,
-                _ => unreachable!("Wrong end in room close"),
+            let payload = KickRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                ban: false,
             };
-            assert!(end < Utc::now());
-            assert_eq!(
-                resp_room.rtc_sharing_policy(),
-                db::rtc::SharingPolicy::Shared
-            );
-        }
-
-        #[sqlx::test]
-        async fn close_infinite_room(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
 
-            let room = {
-                let mut conn = db.get_conn().await;
+            let messages = handle_request::<KickHandler>(&mut context, &host, payload)
+                .await
+                .expect("Room kick failed");
 
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((Bound::Unbounded, Bound::Unbounded))
-                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
-                    .infinite()
-                    .insert(&mut conn)
-                    .await
-            };
+            let (event, evp, topic) = find_event::<KickEvent>(messages.as_slice());
+            assert_eq!(evp.label(), "room.kick");
+            assert!(topic.ends_with(&format!("rooms/{}/events", room.id())));
+            assert_eq!(event.id, room.id());
+            assert_eq!(&event.agent_id, agent.agent_id());
+            assert!(!event.banned);
 
-            // Allow agent to update the room.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut authz = TestAuthz::new();
-            let classroom_id = room.classroom_id().to_string();
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &classroom_id],
-                "update",
-            );
+            let mut conn = db.get_conn().await;
 
-            // Make room.update request.
-            let mut context = TestContext::new(db, authz).await;
+            let agents = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
 
-            let payload = CloseRequest { id: room.id() };
+            assert!(agents.is_empty());
 
-            handle_request::<CloseHandler>(&mut context, &agent, payload)
+            let ban = db::room_ban::FindQuery::new(room.id(), agent.agent_id())
+                .execute(&mut conn)
                 .await
-                .expect_err("Room close succeeded even though it shouldn't");
+                .expect("Failed to find ban");
+
+            assert!(ban.is_none());
         }
 
         #[sqlx::test]
-        async fn close_bounded_room(pool: sqlx::PgPool) {
+        async fn kick_and_ban_agent(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
             let room = {
                 let mut conn = db.get_conn().await;
-
-                // Create room.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((
-                        Bound::Included(Utc::now() - Duration::hours(10)),
-                        Bound::Excluded(Utc::now() + Duration::hours(10)),
-                    ))
-                    .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
-                    .insert(&mut conn)
-                    .await
+                shared_helpers::insert_room(&mut conn).await
             };
 
-            // Allow agent to update the room.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                host.account_id(),
                 vec!["classrooms", &classroom_id],
                 "update",
             );
 
-            // Make room.update request.
-            let mut context = TestContext::new(db, authz).await;
-
-            let payload = CloseRequest { id: room.id() };
-
-            let messages = handle_request::<CloseHandler>(&mut context, &agent, payload)
-                .await
-                .expect("Room close failed");
-
-            // Assert response.
-            let (resp_room, respp, _) = find_response::<Room>(messages.as_slice());
-            assert_eq!(respp.status(), ResponseStatus::OK);
-            assert_eq!(resp_room.id(), room.id());
-            let end = match resp_room.time().1 {
-                Bound::Excluded(t) => t,
-                _ => unreachable!("Wrong end in room close"),
-            };
-            assert!(end < Utc::now());
-            assert_eq!(
-                resp_room.rtc_sharing_policy(),
-                db::rtc::SharingPolicy::Shared
-            );
-        }
-
-        #[sqlx::test]
-        async fn close_room_missing(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
-
-            let payload = CloseRequest {
-                id: db::room::Id::random(),
+            let mut context = TestContext::new(db.clone(), authz).await;
+
+            // Banning doesn't require the agent to be in the room.
+            let payload = KickRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                ban: true,
             };
 
-            let err = handle_request::<CloseHandler>(&mut context, &agent, payload)
+            let messages = handle_request::<KickHandler>(&mut context, &host, payload)
                 .await
-                .expect_err("Unexpected success on room close");
+                .expect("Room kick failed");
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_not_found");
+            let (event, _evp, _topic) = find_event::<KickEvent>(messages.as_slice());
+            assert!(event.banned);
+
+            let mut conn = db.get_conn().await;
+
+            let ban = db::room_ban::FindQuery::new(room.id(), agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find ban");
+
+            assert!(ban.is_some());
         }
 
         #[sqlx::test]
-        async fn close_room_closed(pool: sqlx::PgPool) {
+        async fn ban_kicks_every_agent_of_account(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let other_agent = TestAgent::new("mobile", "user123", USR_AUDIENCE);
+            let bystander = TestAgent::new("web", "user456", USR_AUDIENCE);
 
             let room = {
                 let mut conn = db.get_conn().await;
-
-                // Create closed room.
-                shared_helpers::insert_closed_room(&mut conn).await
+                let room = shared_helpers::insert_room(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+                shared_helpers::insert_agent(&mut conn, other_agent.agent_id(), room.id()).await;
+                shared_helpers::insert_agent(&mut conn, bystander.agent_id(), room.id()).await;
+                room
             };
 
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            authz.allow(
+                host.account_id(),
+                vec!["classrooms", &classroom_id],
+                "update",
+            );
 
-            let payload = CloseRequest { id: room.id() };
+            let mut context = TestContext::new(db.clone(), authz).await;
 
-            let err = handle_request::<CloseHandler>(&mut context, &agent, payload)
+            let payload = KickRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                ban: true,
+            };
+
+            handle_request::<KickHandler>(&mut context, &host, payload)
                 .await
-                .expect_err("Unexpected success on room close");
+                .expect("Room kick failed");
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_closed");
-        }
-    }
+            let mut conn = db.get_conn().await;
 
-    mod enter {
-        use chrono::{Duration, Utc};
+            let agents = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
 
-        use crate::db::group_agent::{GroupItem, Groups};
-        use crate::test_helpers::{db::TestDb, prelude::*, test_deps::LocalDeps};
+            assert_eq!(agents.len(), 1);
+            assert_eq!(agents[0].agent_id(), bystander.agent_id());
 
-        use super::super::*;
+            let ban = db::room_ban::FindQuery::new(room.id(), other_agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find ban");
+
+            assert!(ban.is_some());
+        }
 
         #[sqlx::test]
-        async fn enter_room(pool: sqlx::PgPool) {
+        async fn kick_agent_not_entered(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
             let room = {
                 let mut conn = db.get_conn().await;
-
-                // Create room.
                 shared_helpers::insert_room(&mut conn).await
             };
 
-            // Allow agent to subscribe to the rooms' events.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                host.account_id(),
                 vec!["classrooms", &classroom_id],
-                "read",
-            );
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &classroom_id, "rtcs"],
-                "create",
+                "update",
             );
 
-            // Make room.enter request.
-            let context = TestContext::new(db, authz).await;
-            let payload = EnterRequest { id: room.id() };
+            let mut context = TestContext::new(db, authz).await;
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+            let payload = KickRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                ban: false,
             };
-            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+
+            let err = handle_request::<KickHandler>(&mut context, &host, payload)
                 .await
-                .expect("Room entrance failed");
+                .expect_err("Unexpected success on room kick");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "agent_not_entered_the_room");
         }
 
         #[sqlx::test]
-        async fn enter_room_not_authorized(pool: sqlx::PgPool) {
+        async fn kick_agent_not_authorized(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
 
             let room = {
                 let mut conn = db.get_conn().await;
-                shared_helpers::insert_room(&mut conn).await
+                let room = shared_helpers::insert_room(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+                room
             };
 
-            let context = TestContext::new(db, TestAuthz::new()).await;
-            let payload = EnterRequest { id: room.id() };
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+            let payload = KickRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+                ban: true,
             };
-            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+
+            let err = handle_request::<KickHandler>(&mut context, &host, payload)
                 .await
-                .err()
-                .expect("Unexpected success on room entering");
+                .expect_err("Unexpected success on room kick");
 
             assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
             assert_eq!(err.kind(), "access_denied");
         }
+    }
 
-        #[sqlx::test]
-        async fn enter_room_missing(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let context = TestContext::new(db, TestAuthz::new()).await;
+    mod lobby {
+        use chrono::Utc;
 
-            let payload = EnterRequest {
-                id: db::room::Id::random(),
-            };
+        use crate::test_helpers::{db::TestDb, prelude::*};
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
-            };
-            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
-                .await
-                .err()
-                .expect("Unexpected success on room entering");
+        use super::super::*;
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_not_found");
+        async fn insert_lobby_room(db: &TestDb, agent: &TestAgent) -> db::room::Object {
+            let mut conn = db.get_conn().await;
+
+            let room = factory::Room::new()
+                .audience(USR_AUDIENCE)
+                .time((Bound::Included(Utc::now()), Bound::Unbounded))
+                .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                .lobby()
+                .insert(&mut conn)
+                .await;
+
+            factory::Agent::new()
+                .room_id(room.id())
+                .agent_id(agent.agent_id())
+                .status(db::agent::Status::Pending)
+                .insert(&mut conn)
+                .await;
+
+            room
         }
 
         #[sqlx::test]
-        async fn enter_room_closed(pool: sqlx::PgPool) {
+        async fn admit_agent(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-
-            let room = {
-                let mut conn = db.get_conn().await;
-                // Create closed room.
-                shared_helpers::insert_closed_room(&mut conn).await
-            };
-
-            // Allow agent to subscribe to the rooms' events.
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let room = insert_lobby_room(&db, &agent).await;
+
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                host.account_id(),
                 vec!["classrooms", &classroom_id],
-                "read",
+                "update",
             );
 
-            // Make room.enter request.
-            let context = TestContext::new(db, authz).await;
-            let payload = EnterRequest { id: room.id() };
+            let context = TestContext::new(db.clone(), authz).await;
+
+            let payload = AdmitRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
+            };
 
             let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+                agent_id: host.agent_id(),
             };
-            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+            AdmitHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
-                .err()
-                .expect("Unexpected success on room entering");
+                .expect("Agent admission failed");
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_closed");
+            let mut conn = db.get_conn().await;
+
+            let ready = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .agent_id(agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
+
+            assert_eq!(ready.len(), 1);
         }
 
         #[sqlx::test]
-        async fn enter_room_with_no_opening_time(pool: sqlx::PgPool) {
+        async fn admit_agent_not_pending(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-
-            let room = {
-                let mut conn = db.get_conn().await;
-
-                // Create room without time.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((Bound::Unbounded, Bound::Unbounded))
-                    .insert(&mut conn)
-                    .await
-            };
-
-            // Allow agent to subscribe to the rooms' events.
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let other = TestAgent::new("web", "user456", USR_AUDIENCE);
+            let room = insert_lobby_room(&db, &agent).await;
+
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                host.account_id(),
                 vec!["classrooms", &classroom_id],
-                "read",
+                "update",
             );
 
-            // Make room.enter request.
             let context = TestContext::new(db, authz).await;
-            let payload = EnterRequest { id: room.id() };
+
+            let payload = AdmitRequest {
+                id: room.id(),
+                agent_id: other.agent_id().to_owned(),
+            };
 
             let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+                agent_id: host.agent_id(),
             };
-            let err = EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+            let err = AdmitHandler::handle(Arc::new(context), payload, reqp, Utc::now())
                 .await
                 .err()
-                .expect("Unexpected success on room entering");
+                .expect("Unexpected success on agent admission");
 
             assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_closed");
+            assert_eq!(err.kind(), "agent_not_entered_the_room");
         }
 
         #[sqlx::test]
-        async fn enter_room_that_opens_in_the_future(pool: sqlx::PgPool) {
+        async fn admit_agent_not_authorized(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let room = insert_lobby_room(&db, &agent).await;
 
-            let room = {
-                let mut conn = db.get_conn().await;
+            let context = TestContext::new(db, TestAuthz::new()).await;
 
-                // Create room without time.
-                factory::Room::new()
-                    .audience(USR_AUDIENCE)
-                    .time((
-                        Bound::Included(Utc::now() + Duration::hours(1)),
-                        Bound::Unbounded,
-                    ))
-                    .insert(&mut conn)
-                    .await
+            let payload = AdmitRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
             };
 
-            // Allow agent to subscribe to the rooms' events.
+            // The pending agent can't admit itself.
+            let reqp = RequestParams::Http {
+                agent_id: agent.agent_id(),
+            };
+            let err = AdmitHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+                .await
+                .err()
+                .expect("Unexpected success on agent admission");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn reject_agent(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let room = insert_lobby_room(&db, &agent).await;
+
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
-                agent.account_id(),
+                host.account_id(),
                 vec!["classrooms", &classroom_id],
-                "read",
+                "update",
             );
 
-            // Make room.enter request.
-            let context = TestContext::new(db, authz).await;
-            let payload = EnterRequest { id: room.id() };
+            let mut context = TestContext::new(db.clone(), authz).await;
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+            let payload = RejectRequest {
+                id: room.id(),
+                agent_id: agent.agent_id().to_owned(),
             };
-            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+
+            let messages = handle_request::<RejectHandler>(&mut context, &host, payload)
                 .await
-                .expect("Room entrance failed");
-        }
+                .expect("Agent rejection failed");
+
+            let (_, evp, _) = find_event::<JsonValue>(messages.as_slice());
+            assert_eq!(evp.label(), "room.reject");
 
-        #[sqlx::test]
-        async fn add_new_participant_to_default_group(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
             let mut conn = db.get_conn().await;
 
-            // Create room.
-            let room = shared_helpers::insert_room_with_owned(&mut conn).await;
+            let pending = db::agent::ListQuery::new()
+                .room_id(room.id())
+                .status(db::agent::Status::Pending)
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agents");
 
-            factory::GroupAgent::new(room.id(), Groups::new(vec![GroupItem::new(0, vec![])]))
-                .upsert(&mut conn)
-                .await;
+            assert!(pending.is_empty());
+        }
+    }
 
-            // Allow agent to subscribe to the rooms' events.
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+    mod breakout {
+        use std::ops::Bound;
+
+        use chrono::Utc;
+
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
+        };
+
+        use super::super::*;
+
+        async fn insert_parent_room(db: &TestDb) -> Room {
+            let mut conn = db.get_conn().await;
+
+            factory::Room::new()
+                .audience(USR_AUDIENCE)
+                .time((Bound::Included(Utc::now()), Bound::Unbounded))
+                .rtc_sharing_policy(db::rtc::SharingPolicy::Owned)
+                .insert(&mut conn)
+                .await
+        }
+
+        fn allow_update(agent: &TestAgent, room: &Room) -> TestAuthz {
             let mut authz = TestAuthz::new();
             let classroom_id = room.classroom_id().to_string();
             authz.allow(
                 agent.account_id(),
                 vec!["classrooms", &classroom_id],
-                "read",
-            );
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &classroom_id, "rtcs"],
-                "create",
+                "update",
             );
+            authz
+        }
+
+        #[sqlx::test]
+        async fn breakout_rooms(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let parent = insert_parent_room(&db).await;
 
-            // Make room.enter request.
-            let context = TestContext::new(db, authz).await;
-            let payload = EnterRequest { id: room.id() };
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let student = TestAgent::new("web", "user123", USR_AUDIENCE);
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+            {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_agent(&mut conn, student.agent_id(), parent.id()).await;
+            }
+
+            let mut context = TestContext::new(db.clone(), allow_update(&host, &parent)).await;
+
+            let payload = BreakoutRequest {
+                id: parent.id(),
+                rooms: vec![
+                    BreakoutRoom {
+                        agents: vec![student.agent_id().to_owned()],
+                        tags: Some(json!({ "name": "group 1" })),
+                    },
+                    BreakoutRoom {
+                        agents: vec![],
+                        tags: None,
+                    },
+                ],
             };
-            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+
+            let messages = handle_request::<BreakoutHandler>(&mut context, &host, payload)
                 .await
-                .expect("Room entrance failed");
+                .expect("Breakout rooms creation failed");
 
-            let group_agent = db::group_agent::FindQuery::new(room.id())
-                .execute(&mut conn)
+            // Assert response.
+            let (children, respp, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::CREATED);
+            assert_eq!(children.len(), 2);
+
+            for child in &children {
+                assert_eq!(child.parent_id(), Some(parent.id()));
+                assert_eq!(child.classroom_id(), parent.classroom_id());
+                assert_eq!(child.time().1, parent.time().1);
+            }
+
+            assert_eq!(children[0].tags(), &json!({ "name": "group 1" }));
+
+            // Assert the assignment is broadcasted to the parent room.
+            let (event, _, topic) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "room.breakout"
+                })
+                .expect("Failed to find room.breakout event");
+
+            assert!(topic.ends_with(&format!("rooms/{}/events", parent.id())));
+            assert_eq!(event["rooms"].as_array().map(|r| r.len()), Some(2));
+            assert_eq!(event["rooms"][0]["id"], json!(children[0].id()));
+            assert_eq!(event["rooms"][0]["agents"], json!([student.agent_id()]));
+
+            // Assert children are listed on the parent room.
+            let mut authz = TestAuthz::new();
+            let classroom_id = parent.classroom_id().to_string();
+            authz.allow(host.account_id(), vec!["classrooms", &classroom_id], "read");
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = ReadRequest { id: parent.id() };
+
+            let messages = handle_request::<ReadHandler>(&mut context, &host, payload)
                 .await
-                .expect("failed to find a group agent");
+                .expect("Room reading failed");
 
-            let groups = Groups::new(vec![GroupItem::new(0, vec![agent.agent_id().clone()])]);
-            assert_eq!(group_agent.groups(), groups);
+            let (resp, _, _) = find_response::<JsonValue>(messages.as_slice());
+            assert_eq!(resp["children"].as_array().map(|c| c.len()), Some(2));
         }
 
         #[sqlx::test]
-        async fn existed_participant_in_group(pool: sqlx::PgPool) {
+        async fn breakout_rooms_empty(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
-            let mut conn = db.get_conn().await;
-
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let parent = insert_parent_room(&db).await;
 
-            // Create room.
-            let room = shared_helpers::insert_room_with_owned(&mut conn).await;
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let mut context = TestContext::new(db, allow_update(&host, &parent)).await;
 
-            factory::GroupAgent::new(
-                room.id(),
-                Groups::new(vec![GroupItem::new(0, vec![agent.agent_id().clone()])]),
-            )
-            .upsert(&mut conn)
-            .await;
+            let payload = BreakoutRequest {
+                id: parent.id(),
+                rooms: vec![],
+            };
 
-            // Allow agent to subscribe to the rooms' events.
-            let mut authz = TestAuthz::new();
-            let classroom_id = room.classroom_id().to_string();
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &classroom_id],
-                "read",
-            );
-            authz.allow(
-                agent.account_id(),
-                vec!["classrooms", &classroom_id, "rtcs"],
-                "create",
-            );
+            let err = handle_request::<BreakoutHandler>(&mut context, &host, payload)
+                .await
+                .expect_err("Unexpected success on breakout rooms creation");
 
-            // Make room.enter request.
-            let context = TestContext::new(db, authz).await;
-            let payload = EnterRequest { id: room.id() };
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+        }
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent.agent_id(),
+        #[sqlx::test]
+        async fn breakout_rooms_with_agent_not_in_parent_room(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let parent = insert_parent_room(&db).await;
+
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let student = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let mut context = TestContext::new(db.clone(), allow_update(&host, &parent)).await;
+
+            let payload = BreakoutRequest {
+                id: parent.id(),
+                rooms: vec![BreakoutRoom {
+                    agents: vec![student.agent_id().to_owned()],
+                    tags: None,
+                }],
             };
-            EnterHandler::handle(Arc::new(context), payload, reqp, Utc::now())
+
+            let err = handle_request::<BreakoutHandler>(&mut context, &host, payload)
                 .await
-                .expect("Room entrance failed");
+                .expect_err("Unexpected success on breakout rooms creation");
 
-            let group_agent = db::group_agent::FindQuery::new(room.id())
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+
+            // Assert no breakout rooms were created.
+            let mut conn = db.get_conn().await;
+
+            let children = db::room::ListQuery::new()
+                .parent_id(parent.id())
                 .execute(&mut conn)
                 .await
-                .expect("failed to get group agents");
+                .expect("Failed to list rooms");
 
-            assert_eq!(group_agent.groups().len(), 1);
+            assert!(children.is_empty());
         }
 
         #[sqlx::test]
-        async fn create_reader_configs(pool: sqlx::PgPool) {
-            let local_deps = LocalDeps::new();
-            let janus = local_deps.run_janus();
-            let (session_id, handle_id) = shared_helpers::init_janus(&janus.url).await;
+        async fn close_breakout_rooms(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
+            let parent = insert_parent_room(&db).await;
 
-            let agent1 = TestAgent::new("web", "user1", USR_AUDIENCE);
-            let agent2 = TestAgent::new("web", "user2", USR_AUDIENCE);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let mut context = TestContext::new(db.clone(), allow_update(&host, &parent)).await;
 
-            let mut conn = db.get_conn().await;
+            let payload = BreakoutRequest {
+                id: parent.id(),
+                rooms: vec![BreakoutRoom {
+                    agents: vec![],
+                    tags: None,
+                }],
+            };
 
-            let backend =
-                shared_helpers::insert_janus_backend(&mut conn, &janus.url, session_id, handle_id)
-                    .await;
+            handle_request::<BreakoutHandler>(&mut context, &host, payload)
+                .await
+                .expect("Breakout rooms creation failed");
 
-            let room = factory::Room::new()
-                .audience(USR_AUDIENCE)
-                .time((Bound::Included(Utc::now()), Bound::Unbounded))
-                .rtc_sharing_policy(RtcSharingPolicy::Owned)
-                .backend_id(backend.id())
-                .insert(&mut conn)
-                .await;
+            let payload = BreakoutCloseRequest { id: parent.id() };
 
-            factory::GroupAgent::new(
-                room.id(),
-                Groups::new(vec![
-                    GroupItem::new(0, vec![]),
-                    GroupItem::new(1, vec![agent1.agent_id().clone()]),
-                ]),
-            )
-            .upsert(&mut conn)
-            .await;
+            let messages = handle_request::<BreakoutCloseHandler>(&mut context, &host, payload)
+                .await
+                .expect("Breakout rooms closing failed");
 
-            for agent in &[&agent1, &agent2] {
-                factory::Rtc::new(room.id())
-                    .created_by(agent.agent_id().to_owned())
-                    .insert(&mut conn)
-                    .await;
-            }
+            // Assert response.
+            let (closed, respp, _) = find_response::<Vec<Room>>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(closed.len(), 1);
 
-            // Allow agent to subscribe to the rooms' events.
-            let mut authz = TestAuthz::new();
-            let classroom_id = room.classroom_id().to_string();
-            authz.allow(
-                agent2.account_id(),
-                vec!["classrooms", &classroom_id],
-                "read",
-            );
+            let (event, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "room.breakout_close"
+                })
+                .expect("Failed to find room.breakout_close event");
 
-            // Make room.enter request.
-            let mut context = TestContext::new(db, authz).await;
-            let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
-            context.with_janus(tx);
+            assert_eq!(event["rooms"], json!([closed[0].id()]));
 
-            let payload = EnterRequest { id: room.id() };
+            // Assert there are no open breakout rooms left.
+            let mut conn = db.get_conn().await;
 
-            let reqp = RequestParams::Http {
-                agent_id: &agent2.agent_id(),
-            };
-            EnterHandler::handle(Arc::new(context.clone()), payload, reqp, Utc::now())
+            let open_children = db::room::ListQuery::new()
+                .parent_id(parent.id())
+                .closed(false)
+                .execute(&mut conn)
                 .await
-                .expect("Room entrance failed");
+                .expect("Failed to list rooms");
 
-            let group_agent = db::group_agent::FindQuery::new(room.id())
+            assert!(open_children.is_empty());
+
+            // Assert the closure is in the audit log.
+            let audit = db::room_audit::ListQuery::new(closed[0].id())
                 .execute(&mut conn)
                 .await
-                .expect("failed to get group agents");
+                .expect("Failed to list room audit");
 
-            assert_eq!(group_agent.groups().len(), 2);
+            assert!(audit.iter().any(|entry| entry.action() == "room.close"));
+        }
 
-            let reader_configs = db::rtc_reader_config::ListWithRtcQuery::new(
-                room.id(),
-                &[agent1.agent_id(), agent2.agent_id()],
-            )
-            .execute(&mut conn)
-            .await
-            .expect("failed to get rtc reader configs");
+        #[sqlx::test]
+        async fn close_breakout_rooms_infinite(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let parent = insert_parent_room(&db).await;
 
-            assert_eq!(reader_configs.len(), 2);
+            let children = {
+                let mut conn = db.get_conn().await;
+                let mut children = vec![];
+
+                for infinite in [false, true] {
+                    let child = db::room::InsertQuery::new(
+                        parent.time(),
+                        parent.audience(),
+                        parent.rtc_sharing_policy(),
+                        parent.classroom_id(),
+                    )
+                    .parent_id(parent.id())
+                    .infinite(infinite)
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to insert room");
 
-            context.janus_clients().remove_client(&backend);
-        }
-    }
+                    children.push(child);
+                }
 
-    mod leave {
-        use crate::test_helpers::{db::TestDb, prelude::*};
+                children
+            };
 
-        use super::{super::*, DynSubRequest};
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let mut context = TestContext::new(db.clone(), allow_update(&host, &parent)).await;
 
-        #[sqlx::test]
-        async fn leave_room(pool: sqlx::PgPool) {
-            let db = TestDb::new(pool);
-            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let payload = BreakoutCloseRequest { id: parent.id() };
 
-            let mut conn = db.get_conn().await;
+            let err = handle_request::<BreakoutCloseHandler>(&mut context, &host, payload)
+                .await
+                .expect_err("Unexpected success on breakout rooms closing");
 
-            // Create room.
-            let room = shared_helpers::insert_room(&mut conn).await;
-            // Put agent online in the room.
-            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+            assert_eq!(err.status(), ResponseStatus::UNPROCESSABLE_ENTITY);
+            assert_eq!(err.kind(), "room_time_changing_forbidden");
 
-            // Make room.leave request.
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
-            let payload = LeaveRequest { id: room.id() };
+            // Assert none of the breakout rooms got closed.
+            let mut conn = db.get_conn().await;
 
-            let messages = handle_request::<LeaveHandler>(&mut context, &agent, payload)
+            let open_children = db::room::ListQuery::new()
+                .parent_id(parent.id())
+                .closed(false)
+                .execute(&mut conn)
                 .await
-                .expect("Room leaving failed");
+                .expect("Failed to list rooms");
 
-            // Assert dynamic subscription request.
-            let (payload, reqp, topic) = find_request::<DynSubRequest>(messages.as_slice());
+            assert_eq!(open_children.len(), children.len());
+        }
+    }
 
-            let expected_topic = format!(
-                "agents/{}.{}/api/{}/out/{}",
-                context.config().agent_label,
-                context.config().id,
-                API_VERSION,
-                context.config().broker_id,
-            );
+    mod clone {
+        use std::ops::Bound;
 
-            assert_eq!(topic, expected_topic);
-            assert_eq!(reqp.method(), "subscription.delete");
-            assert_eq!(&payload.subject, agent.agent_id());
+        use chrono::{Duration, Utc};
 
-            let room_id = room.id().to_string();
-            assert_eq!(payload.object, vec!["rooms", &room_id, "events"]);
-        }
+        use crate::{
+            db::room::Object as Room,
+            test_helpers::{db::TestDb, prelude::*},
+        };
+
+        use super::super::*;
 
         #[sqlx::test]
-        async fn leave_room_while_not_entered(pool: sqlx::PgPool) {
+        async fn clone_room(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let student = TestAgent::new("web", "user456", USR_AUDIENCE);
+            let now = Utc::now();
 
-            let room = {
+            let (source, groups) = {
                 let mut conn = db.get_conn().await;
-                shared_helpers::insert_room(&mut conn).await
+
+                // Create a closed minigroup with a custom groups layout.
+                let source = factory::Room::new()
+                    .audience(USR_AUDIENCE)
+                    .time((
+                        Bound::Included(now - Duration::hours(2)),
+                        Bound::Excluded(now - Duration::hours(1)),
+                    ))
+                    .rtc_sharing_policy(db::rtc::SharingPolicy::Owned)
+                    .reserve(10)
+                    .insert(&mut conn)
+                    .await;
+
+                let groups = Groups::new(vec![
+                    GroupItem::new(0, vec![]),
+                    GroupItem::new(1, vec![student.agent_id().to_owned()]),
+                ]);
+
+                db::group_agent::UpsertQuery::new(source.id(), &groups)
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to insert groups");
+
+                (source, groups)
             };
 
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
-            let payload = LeaveRequest { id: room.id() };
+            let mut authz = TestAuthz::new();
+            let source_classroom_id = source.classroom_id().to_string();
+            authz.allow(
+                agent.account_id(),
+                vec!["classrooms", &source_classroom_id],
+                "read",
+            );
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+            let mut context = TestContext::new(db.clone(), authz).await;
 
-            let err = handle_request::<LeaveHandler>(&mut context, &agent, payload)
+            let classroom_id = Uuid::new_v4();
+
+            let payload = CloneRequest {
+                id: source.id(),
+                time: (
+                    Bound::Included(now + Duration::days(7)),
+                    Bound::Excluded(now + Duration::days(7) + Duration::hours(1)),
+                ),
+                classroom_id,
+            };
+
+            let messages = handle_request::<CloneHandler>(&mut context, &agent, payload)
                 .await
-                .expect_err("Unexpected success on room leaving");
+                .expect("Room cloning failed");
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "agent_not_entered_the_room");
+            // Assert response.
+            let (room, respp, _) = find_response::<Room>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::CREATED);
+            assert_ne!(room.id(), source.id());
+            assert_eq!(room.classroom_id(), classroom_id);
+            assert_eq!(room.audience(), source.audience());
+            assert_eq!(room.rtc_sharing_policy(), db::rtc::SharingPolicy::Owned);
+            assert_eq!(room.reserve(), Some(10));
+
+            // Assert notification.
+            let (_, evp, topic) = find_event::<Room>(messages.as_slice());
+            assert_eq!(evp.label(), "room.create");
+            assert!(topic.ends_with(&format!("audiences/{}/events", USR_AUDIENCE)));
+
+            // Assert groups layout is copied.
+            let mut conn = db.get_conn().await;
+
+            let room_groups = db::group_agent::FindQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find groups")
+                .groups();
+
+            assert_eq!(room_groups, groups);
         }
 
         #[sqlx::test]
-        async fn leave_room_missing(pool: sqlx::PgPool) {
+        async fn clone_room_not_authorized_to_read(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
             let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
-            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let now = Utc::now();
 
-            let payload = LeaveRequest {
-                id: db::room::Id::random(),
+            let source = {
+                let mut conn = db.get_conn().await;
+                shared_helpers::insert_room(&mut conn).await
             };
 
-            let err = handle_request::<LeaveHandler>(&mut context, &agent, payload)
+            // The agent may create rooms but can't see the source one.
+            let mut authz = TestAuthz::new();
+            authz.allow(agent.account_id(), vec!["classrooms"], "create");
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = CloneRequest {
+                id: source.id(),
+                time: (
+                    Bound::Included(now + Duration::days(7)),
+                    Bound::Excluded(now + Duration::days(7) + Duration::hours(1)),
+                ),
+                classroom_id: Uuid::new_v4(),
+            };
+
+            let err = handle_request::<CloneHandler>(&mut context, &agent, payload)
                 .await
-                .expect_err("Unexpected success on room leaving");
+                .expect_err("Unexpected success on room cloning");
 
-            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
-            assert_eq!(err.kind(), "room_not_found");
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
         }
     }
 }
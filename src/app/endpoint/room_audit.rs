@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use axum::extract::{Extension, Path, Query};
+use serde::Deserialize;
+use svc_agent::mqtt::ResponseStatus;
+use svc_utils::extractors::AgentIdExtractor;
+
+use crate::{
+    app::{
+        context::{AppContext, Context},
+        endpoint::prelude::*,
+        metrics::HistogramExt,
+        service_utils::{RequestParams, Response},
+    },
+    authz::AuthzObject,
+    db,
+};
+
+const MAX_LIMIT: i64 = 100;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct ListRequest {
+    room_id: db::room::Id,
+    cursor: Option<db::room_audit::Id>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    cursor: Option<db::room_audit::Id>,
+    limit: Option<i64>,
+}
+
+pub async fn list(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(room_id): Path<db::room::Id>,
+    Query(params): Query<ListParams>,
+) -> RequestResult {
+    tracing::Span::current().record("room_id", tracing::field::display(room_id));
+
+    let request = ListRequest {
+        room_id,
+        cursor: params.cursor,
+        limit: params.limit,
+    };
+
+    ListHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct ListHandler;
+
+#[async_trait]
+impl RequestHandler for ListHandler {
+    type Payload = ListRequest;
+    const ERROR_TITLE: &'static str = "Failed to list room audit entries";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let room = {
+            let mut conn = context.get_conn().await?;
+            helpers::find_room_by_id(
+                payload.room_id,
+                helpers::RoomTimeRequirement::Any,
+                &mut conn,
+            )
+            .await?
+        };
+
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        if matches!(payload.limit, Some(limit) if limit < 1) {
+            return Err(anyhow!("Limit must be positive")).error(AppErrorKind::InvalidPayload);
+        }
+
+        // Authorize audit listing on the tenant.
+        let classroom_id = room.classroom_id().to_string();
+        let object = AuthzObject::new(&["classrooms", &classroom_id, "audit"]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(room.audience().into(), reqp, object, "list".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let mut query = db::room_audit::ListQuery::new(room.id())
+            .limit(std::cmp::min(payload.limit.unwrap_or(MAX_LIMIT), MAX_LIMIT));
+
+        if let Some(cursor) = payload.cursor {
+            query = query.after(cursor);
+        }
+
+        let entries = {
+            let mut conn = context.get_conn().await?;
+            query.execute(&mut conn).await?
+        };
+
+        context
+            .metrics()
+            .request_duration
+            .room_audit_list
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(Response::new(
+            ResponseStatus::OK,
+            entries,
+            context.start_timestamp(),
+            Some(authz_time),
+        ))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    use crate::{
+        app::endpoint::room,
+        db::room_audit::Object as RoomAudit,
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn list_audit_entries(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let now = Utc::now();
+
+        let room = {
+            let mut conn = db.get_conn().await;
+
+            factory::Room::new()
+                .audience(USR_AUDIENCE)
+                .time((
+                    Bound::Included(now),
+                    Bound::Excluded(now + Duration::hours(1)),
+                ))
+                .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+                .insert(&mut conn)
+                .await
+        };
+
+        let mut authz = TestAuthz::new();
+        let classroom_id = room.classroom_id().to_string();
+        authz.allow(
+            agent.account_id(),
+            vec!["classrooms", &classroom_id],
+            "update",
+        );
+        authz.allow(
+            agent.account_id(),
+            vec!["classrooms", &classroom_id, "audit"],
+            "list",
+        );
+
+        let mut context = TestContext::new(db, authz).await;
+
+        // Lock and then close the room.
+        let payload: room::UpdateRequest =
+            serde_json::from_value(json!({ "id": room.id(), "locked": true }))
+                .expect("Failed to build room.update payload");
+
+        handle_request::<room::UpdateHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Room update failed");
+
+        let payload: room::CloseRequest = serde_json::from_value(json!({ "id": room.id() }))
+            .expect("Failed to build room.close payload");
+
+        handle_request::<room::CloseHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Room close failed");
+
+        // List the first page.
+        let payload = ListRequest {
+            room_id: room.id(),
+            cursor: None,
+            limit: Some(1),
+        };
+
+        let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Room audit listing failed");
+
+        let (entries, respp, _) = find_response::<Vec<RoomAudit>>(messages.as_slice());
+        assert_eq!(respp.status(), ResponseStatus::OK);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action(), "room.update");
+        assert_eq!(entries[0].created_by(), agent.agent_id());
+        assert_eq!(entries[0].before(), Some(&json!({ "locked": false })));
+        assert_eq!(entries[0].after(), Some(&json!({ "locked": true })));
+
+        // List the next page.
+        let payload = ListRequest {
+            room_id: room.id(),
+            cursor: Some(entries[0].id()),
+            limit: None,
+        };
+
+        let messages = handle_request::<ListHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Room audit listing failed");
+
+        let (entries, _, _) = find_response::<Vec<RoomAudit>>(messages.as_slice());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action(), "room.close");
+
+        let after = entries[0].after().expect("Missing after value");
+        assert_eq!(after["closed_by"], json!(agent.agent_id()));
+    }
+
+    #[sqlx::test]
+    async fn list_audit_entries_not_authorized(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+        let room = {
+            let mut conn = db.get_conn().await;
+            shared_helpers::insert_room(&mut conn).await
+        };
+
+        let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+        let payload = ListRequest {
+            room_id: room.id(),
+            cursor: None,
+            limit: None,
+        };
+
+        let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+            .await
+            .expect_err("Unexpected success on room audit listing");
+
+        assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+        assert_eq!(err.kind(), "access_denied");
+    }
+
+    #[sqlx::test]
+    async fn list_audit_entries_with_invalid_limit(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+        let room = {
+            let mut conn = db.get_conn().await;
+            shared_helpers::insert_room(&mut conn).await
+        };
+
+        let mut authz = TestAuthz::new();
+        let classroom_id = room.classroom_id().to_string();
+        authz.allow(
+            agent.account_id(),
+            vec!["classrooms", &classroom_id, "audit"],
+            "list",
+        );
+
+        let mut context = TestContext::new(db, authz).await;
+
+        for limit in [0, -1] {
+            let payload = ListRequest {
+                room_id: room.id(),
+                cursor: None,
+                limit: Some(limit),
+            };
+
+            let err = handle_request::<ListHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on room audit listing");
+
+            assert_eq!(err.status(), ResponseStatus::BAD_REQUEST);
+            assert_eq!(err.kind(), "invalid_payload");
+        }
+    }
+}
@@ -0,0 +1,461 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use svc_agent::{mqtt::ResponseStatus, Addressable};
+use svc_utils::extractors::AgentIdExtractor;
+use uuid::Uuid;
+
+use crate::{
+    app::{
+        context::{AppContext, Context},
+        endpoint::{prelude::*, room::RoomSetup},
+        metrics::HistogramExt,
+        service_utils::{RequestParams, Response},
+    },
+    authz::AuthzObject,
+    db::{
+        self,
+        group_agent::Groups,
+        room::Time,
+        room_template::{Object as RoomTemplate, WriterConfig},
+        rtc::SharingPolicy as RtcSharingPolicy,
+    },
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRequest {
+    audience: String,
+    #[serde(default)]
+    rtc_sharing_policy: Option<RtcSharingPolicy>,
+    reserve: Option<i32>,
+    tags: Option<JsonValue>,
+    groups: Option<Groups>,
+    writer_config: Option<WriterConfig>,
+}
+
+pub async fn create(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Json(request): Json<CreateRequest>,
+) -> RequestResult {
+    CreateHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct CreateHandler;
+
+#[async_trait]
+impl RequestHandler for CreateHandler {
+    type Payload = CreateRequest;
+    const ERROR_TITLE: &'static str = "Failed to create room template";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let rtc_sharing_policy = payload.rtc_sharing_policy.unwrap_or(RtcSharingPolicy::None);
+
+        if payload.groups.is_some() && rtc_sharing_policy != RtcSharingPolicy::Owned {
+            return Err(anyhow!(
+                "Groups are available only for templates with owned RTC sharing policy"
+            ))
+            .error(AppErrorKind::InvalidPayload);
+        }
+
+        // Authorize template creation on the tenant.
+        let authz_time = context
+            .authz()
+            .authorize(
+                payload.audience.clone(),
+                reqp,
+                AuthzObject::new(&["room_templates"]).into(),
+                "create".into(),
+            )
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let mut q = db::room_template::InsertQuery::new(
+            &payload.audience,
+            rtc_sharing_policy,
+            reqp.as_agent_id(),
+        );
+
+        if let Some(reserve) = payload.reserve {
+            q = q.reserve(reserve);
+        }
+
+        if let Some(ref tags) = payload.tags {
+            q = q.tags(tags);
+        }
+
+        if let Some(ref groups) = payload.groups {
+            q = q.groups(groups);
+        }
+
+        if let Some(writer_config) = payload.writer_config {
+            q = q.writer_config(writer_config);
+        }
+
+        let template = {
+            let mut conn = context.get_conn().await?;
+            q.execute(&mut conn).await?
+        };
+
+        context
+            .metrics()
+            .request_duration
+            .room_template_create
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(Response::new(
+            ResponseStatus::CREATED,
+            template,
+            context.start_timestamp(),
+            Some(authz_time),
+        ))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct ReadRequest {
+    id: db::room_template::Id,
+}
+
+pub async fn read(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(id): Path<db::room_template::Id>,
+) -> RequestResult {
+    let request = ReadRequest { id };
+
+    ReadHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct ReadHandler;
+
+#[async_trait]
+impl RequestHandler for ReadHandler {
+    type Payload = ReadRequest;
+    const ERROR_TITLE: &'static str = "Failed to read room template";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let template = find_template(context, payload.id).await?;
+
+        // Authorize template reading on the tenant.
+        let template_id = template.id().to_string();
+        let object = AuthzObject::new(&["room_templates", &template_id]).into();
+
+        let authz_time = context
+            .authz()
+            .authorize(template.audience().into(), reqp, object, "read".into())
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        context
+            .metrics()
+            .request_duration
+            .room_template_read
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(Response::new(
+            ResponseStatus::OK,
+            template,
+            context.start_timestamp(),
+            Some(authz_time),
+        ))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRoomRequest {
+    id: db::room_template::Id,
+    #[serde(with = "crate::serde::ts_seconds_bound_tuple")]
+    time: Time,
+    classroom_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRoomPayload {
+    #[serde(with = "crate::serde::ts_seconds_bound_tuple")]
+    time: Time,
+    classroom_id: Uuid,
+}
+
+pub async fn create_room(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(id): Path<db::room_template::Id>,
+    Json(payload): Json<CreateRoomPayload>,
+) -> RequestResult {
+    let request = CreateRoomRequest {
+        id,
+        time: payload.time,
+        classroom_id: payload.classroom_id,
+    };
+
+    CreateRoomHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct CreateRoomHandler;
+
+#[async_trait]
+impl RequestHandler for CreateRoomHandler {
+    type Payload = CreateRoomRequest;
+    const ERROR_TITLE: &'static str = "Failed to create room from template";
+
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let template = find_template(context, payload.id).await?;
+
+        // Authorize room creation on the tenant.
+        let authz_time = context
+            .authz()
+            .authorize(
+                template.audience().into(),
+                reqp,
+                AuthzObject::new(&["classrooms"]).into(),
+                "create".into(),
+            )
+            .await?;
+        context.metrics().observe_auth(authz_time);
+
+        let room = {
+            let mut conn = context.get_conn().await?;
+
+            RoomSetup {
+                audience: template.audience().to_owned(),
+                rtc_sharing_policy: template.rtc_sharing_policy(),
+                reserve: template.reserve(),
+                tags: template.tags().to_owned(),
+                groups: template.groups().cloned(),
+                template_id: Some(template.id()),
+            }
+            .insert(payload.time, payload.classroom_id, &mut conn)
+            .await?
+        };
+
+        tracing::Span::current().record("room_id", tracing::field::display(room.id()));
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        // Respond and broadcast to the audience topic.
+        let mut response = Response::new(
+            ResponseStatus::CREATED,
+            room.clone(),
+            context.start_timestamp(),
+            Some(authz_time),
+        );
+
+        response.add_notification(
+            "room.create",
+            &format!("audiences/{}/events", room.audience()),
+            room,
+            context.start_timestamp(),
+        );
+
+        context
+            .metrics()
+            .request_duration
+            .room_template_create_room
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+async fn find_template<C: Context>(
+    context: &C,
+    id: db::room_template::Id,
+) -> Result<RoomTemplate, AppError> {
+    let mut conn = context.get_conn().await?;
+
+    db::room_template::FindQuery::new(id)
+        .execute(&mut conn)
+        .await?
+        .ok_or_else(|| anyhow!("Room template not found"))
+        .error(AppErrorKind::RoomTemplateNotFound)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    use crate::{
+        app::endpoint::rtc,
+        db::room::Object as Room,
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn create_room_from_template(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let student = TestAgent::new("web", "user456", USR_AUDIENCE);
+        let classroom_id = Uuid::new_v4();
+
+        let mut authz = TestAuthz::new();
+        authz.allow(agent.account_id(), vec!["room_templates"], "create");
+        authz.allow(agent.account_id(), vec!["classrooms"], "create");
+        let classroom_id_str = classroom_id.to_string();
+        authz.allow(
+            agent.account_id(),
+            vec!["classrooms", &classroom_id_str, "rtcs"],
+            "create",
+        );
+
+        let mut context = TestContext::new(db.clone(), authz).await;
+
+        // Make room_template.create request.
+        let groups: Groups = serde_json::from_value(json!([
+            { "number": 0, "agents": [] },
+            { "number": 1, "agents": [student.agent_id()] },
+        ]))
+        .expect("Failed to parse groups");
+
+        let payload = CreateRequest {
+            audience: USR_AUDIENCE.to_owned(),
+            rtc_sharing_policy: Some(RtcSharingPolicy::Owned),
+            reserve: Some(5),
+            tags: Some(json!({ "course": "math" })),
+            groups: Some(groups.clone()),
+            writer_config: Some(
+                serde_json::from_value(json!({ "send_video": false, "video_remb": 100000 }))
+                    .expect("Failed to parse writer config"),
+            ),
+        };
+
+        let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Room template creation failed");
+
+        let (template, respp, _) = find_response::<JsonValue>(messages.as_slice());
+        assert_eq!(respp.status(), ResponseStatus::CREATED);
+
+        let template_id: db::room_template::Id =
+            serde_json::from_value(template["id"].clone()).expect("Failed to parse template id");
+
+        // Make room_template.create_room request.
+        let now = Utc::now();
+
+        let payload = CreateRoomRequest {
+            id: template_id,
+            time: (
+                Bound::Included(now),
+                Bound::Excluded(now + Duration::hours(1)),
+            ),
+            classroom_id,
+        };
+
+        let messages = handle_request::<CreateRoomHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Room creation from template failed");
+
+        let (room, respp, _) = find_response::<Room>(messages.as_slice());
+        assert_eq!(respp.status(), ResponseStatus::CREATED);
+        assert_eq!(room.classroom_id(), classroom_id);
+        assert_eq!(room.rtc_sharing_policy(), RtcSharingPolicy::Owned);
+        assert_eq!(room.reserve(), Some(5));
+        assert_eq!(room.tags(), &json!({ "course": "math" }));
+        assert_eq!(room.template_id(), Some(template_id));
+
+        let mut conn = db.get_conn().await;
+
+        let room_groups = db::group_agent::FindQuery::new(room.id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to find groups")
+            .groups();
+
+        assert_eq!(room_groups, groups);
+
+        // Assert the template writer config is applied to new rtcs.
+        let payload: rtc::CreateRequest = serde_json::from_value(json!({ "room_id": room.id() }))
+            .expect("Failed to build rtc.create payload");
+
+        let messages = handle_request::<rtc::CreateHandler>(&mut context, &agent, payload)
+            .await
+            .expect("Rtc creation failed");
+
+        let (rtc, _, _) = find_response::<db::rtc::Object>(messages.as_slice());
+
+        let writer_config = db::rtc_writer_config::read_config(rtc.id(), &mut conn)
+            .await
+            .expect("Failed to read writer config")
+            .expect("Writer config not found");
+
+        assert!(!writer_config.send_video());
+        assert!(writer_config.send_audio());
+        assert_eq!(writer_config.video_remb(), Some(100000));
+    }
+
+    #[sqlx::test]
+    async fn create_room_from_missing_template(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let mut context = TestContext::new(db, TestAuthz::new()).await;
+        let now = Utc::now();
+
+        let payload = CreateRoomRequest {
+            id: db::room_template::Id::random(),
+            time: (Bound::Included(now), Bound::Unbounded),
+            classroom_id: Uuid::new_v4(),
+        };
+
+        let err = handle_request::<CreateRoomHandler>(&mut context, &agent, payload)
+            .await
+            .expect_err("Unexpected success on room creation from template");
+
+        assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+        assert_eq!(err.kind(), "room_template_not_found");
+    }
+}
@@ -31,6 +31,7 @@ use crate::{
             CreateStreamRequest, CreateStreamRequestBody, CreateStreamTransaction, ReaderConfig,
             WriterConfig,
         },
+        detach_handle::DetachHandleRequest,
         read_stream::{ReadStreamRequest, ReadStreamRequestBody, ReadStreamTransaction},
         Jsep, JsonSdp,
     },
@@ -56,16 +57,26 @@ impl ConnectResponseData {
 #[derive(Debug, Deserialize)]
 pub struct CreateRequest {
     room_id: db::room::Id,
+    #[serde(default)]
+    kind: db::rtc::Kind,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePayload {
+    #[serde(default)]
+    kind: db::rtc::Kind,
 }
 
 pub async fn create(
     Extension(ctx): Extension<Arc<AppContext>>,
     AgentIdExtractor(agent_id): AgentIdExtractor,
     Path(room_id): Path<db::room::Id>,
+    payload: Option<Json<CreatePayload>>,
 ) -> RequestResult {
     tracing::Span::current().record("room_id", &tracing::field::display(room_id));
 
-    let request = CreateRequest { room_id };
+    let kind = payload.map(|Json(p)| p.kind).unwrap_or_default();
+    let request = CreateRequest { room_id, kind };
     CreateHandler::handle(
         &mut ctx.start_message(),
         request,
@@ -96,6 +107,7 @@ impl RequestHandler for CreateHandler {
         } = RtcCreate {
             ctx: context,
             room: Either::Right(payload.room_id),
+            kind: payload.kind,
             reqp,
         }
         .run()
@@ -130,6 +142,7 @@ impl RequestHandler for CreateHandler {
 pub struct RtcCreate<'a, C: ?Sized> {
     pub ctx: &'a C,
     pub room: Either<db::room::Object, db::room::Id>,
+    pub kind: db::rtc::Kind,
     pub reqp: RequestParams<'a>,
 }
 
@@ -169,9 +182,36 @@ impl<'a, C: GlobalContext + ?Sized> RtcCreate<'a, C> {
         // Create an rtc.
         let mut conn = self.ctx.get_conn().await?;
         let rtc = db::rtc::InsertQuery::new(room.id(), self.reqp.as_agent_id())
+            .kind(self.kind)
             .execute(&mut conn)
             .await?;
 
+        // Apply default writer config of the template the room was created from.
+        if let Some(template_id) = room.template_id() {
+            let writer_config = db::room_template::FindQuery::new(template_id)
+                .execute(&mut conn)
+                .await?
+                .and_then(|template| template.writer_config());
+
+            if let Some(writer_config) = writer_config {
+                let mut q = db::rtc_writer_config::UpsertQuery::new(rtc.id());
+
+                if let Some(send_video) = writer_config.send_video() {
+                    q = q.send_video(send_video);
+                }
+
+                if let Some(send_audio) = writer_config.send_audio() {
+                    q = q.send_audio(send_audio);
+                }
+
+                if let Some(video_remb) = writer_config.video_remb() {
+                    q = q.video_remb(video_remb);
+                }
+
+                q.execute(&mut conn).await?;
+            }
+        }
+
         let notification_topic = format!("rooms/{}/events", rtc.room_id());
         Ok(RtcCreateResult {
             rtc,
@@ -268,6 +308,189 @@ impl RequestHandler for ReadHandler {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Debug, Deserialize)]
+pub struct DeleteRequest {
+    id: db::rtc::Id,
+}
+
+pub async fn delete(
+    Extension(ctx): Extension<Arc<AppContext>>,
+    AgentIdExtractor(agent_id): AgentIdExtractor,
+    Path(rtc_id): Path<db::rtc::Id>,
+) -> RequestResult {
+    tracing::Span::current().record("rtc_id", tracing::field::display(rtc_id));
+
+    let request = DeleteRequest { id: rtc_id };
+    DeleteHandler::handle(
+        &mut ctx.start_message(),
+        request,
+        RequestParams::Http {
+            agent_id: &agent_id,
+        },
+    )
+    .await
+}
+
+pub struct DeleteHandler;
+
+#[async_trait]
+impl RequestHandler for DeleteHandler {
+    type Payload = DeleteRequest;
+    const ERROR_TITLE: &'static str = "Failed to delete rtc";
+
+    #[instrument(skip(context, payload, reqp), fields(rtc_id = %payload.id))]
+    async fn handle<C: Context + Send + Sync>(
+        context: &mut C,
+        payload: Self::Payload,
+        reqp: RequestParams<'_>,
+    ) -> RequestResult {
+        let (room, rtc, is_moderator) = {
+            let mut conn = context.get_conn().await?;
+
+            let room = helpers::find_room_by_rtc_id(
+                payload.id,
+                helpers::RoomTimeRequirement::Open,
+                &mut conn,
+            )
+            .await?;
+
+            let rtc = db::rtc::FindQuery::new(payload.id)
+                .execute(&mut conn)
+                .await?
+                .context("RTC not found")
+                .error(AppErrorKind::RtcNotFound)?;
+
+            let is_moderator = helpers::is_moderator(&room, reqp.as_agent_id());
+
+            (room, rtc, is_moderator)
+        };
+
+        tracing::Span::current().record("room_id", tracing::field::display(room.id()));
+        tracing::Span::current()
+            .record("classroom_id", tracing::field::display(room.classroom_id()));
+
+        // In owned rooms agents may delete their own RTCs. The shared RTC belongs to the whole room
+        // so deleting it as well as other agents' RTCs requires hosts, co-hosts or the permission.
+        let is_owner = room.rtc_sharing_policy() == RtcSharingPolicy::Owned
+            && rtc.created_by() == reqp.as_agent_id();
+
+        let maybe_authz_time = if is_owner || is_moderator {
+            None
+        } else {
+            let rtc_id = rtc.id().to_string();
+            let classroom_id = room.classroom_id().to_string();
+            let object = AuthzObject::new(&["classrooms", &classroom_id, "rtcs", &rtc_id]).into();
+
+            let authz_time = context
+                .authz()
+                .authorize(room.audience().into(), reqp, object, "delete".into())
+                .await?;
+            context.metrics().observe_auth(authz_time);
+            Some(authz_time)
+        };
+
+        let mut conn = context.get_conn().await?;
+
+        let maybe_backend = match room.backend_id() {
+            None => None,
+            Some(backend_id) => {
+                db::janus_backend::FindQuery::new(backend_id)
+                    .execute(&mut conn)
+                    .await?
+            }
+        };
+
+        let rtc_id = rtc.id();
+
+        let (stopped_streams, handles) = conn
+            .transaction::<_, _, AppError>(|conn| {
+                Box::pin(async move {
+                    // Stop running streams. The recording stays in place to be uploaded on vacuum.
+                    let running_streams = db::janus_rtc_stream::ListQuery::new()
+                        .rtc_id(rtc_id)
+                        .active(true)
+                        .execute(conn)
+                        .await?;
+
+                    let mut stopped_streams = Vec::with_capacity(running_streams.len());
+
+                    for stream in running_streams {
+                        if let Some(stream) = db::janus_rtc_stream::stop(stream.id(), conn).await? {
+                            stopped_streams.push(stream);
+                        }
+                    }
+
+                    let handles = db::agent_connection::ListByRtcQuery::new(rtc_id)
+                        .execute(conn)
+                        .await?
+                        .into_iter()
+                        .map(|c| c.handle_id())
+                        .collect::<Vec<_>>();
+
+                    db::agent_connection::BulkDisconnectByRtcQuery::new(rtc_id)
+                        .execute(conn)
+                        .await?;
+
+                    db::rtc::DeleteQuery::new(rtc_id).execute(conn).await?;
+
+                    Ok((stopped_streams, handles))
+                })
+            })
+            .await?;
+
+        // Detach writer and reader handles. Failures are not fatal since the handles might be gone already.
+        if let Some(backend) = maybe_backend {
+            match context.janus_clients().get_or_insert(&backend) {
+                Ok(client) => {
+                    for handle_id in handles {
+                        let request = DetachHandleRequest {
+                            session_id: backend.session_id(),
+                            handle_id,
+                        };
+
+                        if let Err(err) = client.detach_handle(request).await {
+                            warn!(?err, %handle_id, "Failed to detach janus handle");
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!(?err, "Failed to get janus client");
+                }
+            }
+        }
+
+        let topic = format!("rooms/{}/events", room.id());
+
+        let mut response = Response::new(
+            ResponseStatus::OK,
+            rtc.clone(),
+            context.start_timestamp(),
+            maybe_authz_time,
+        );
+
+        for stream in stopped_streams {
+            response.add_notification(
+                "rtc_stream.update",
+                &topic,
+                stream,
+                context.start_timestamp(),
+            );
+        }
+
+        response.add_notification("rtc.delete", &topic, rtc, context.start_timestamp());
+
+        context
+            .metrics()
+            .request_duration
+            .rtc_delete
+            .observe_timestamp(context.start_timestamp());
+
+        Ok(response)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 const MAX_LIMIT: i64 = 25;
 
 #[derive(Debug, Deserialize)]
@@ -400,6 +623,8 @@ pub struct ConnectRequest {
     id: db::rtc::Id,
     #[serde(default = "ConnectRequest::default_intent")]
     intent: ConnectIntent,
+    #[serde(default)]
+    preferred_region: Option<String>,
 }
 
 impl ConnectRequest {
@@ -414,6 +639,8 @@ pub struct ConnectAndSignalPayload {
     intent: ConnectIntent,
     jsep: JsonSdp,
     label: Option<String>,
+    #[serde(default)]
+    preferred_region: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -422,6 +649,33 @@ pub struct ConnectAndSignalResult {
     jsep: Option<serde_json::Value>,
 }
 
+/// Refuses viewers to connect with write intent and returns whether the agent has been
+/// granted the speaker role by a moderator so the tenant authorization is not required to publish.
+async fn check_write_role<C: Context>(
+    context: &C,
+    room: &db::room::Object,
+    agent_id: &AgentId,
+) -> Result<bool, AppError> {
+    let mut conn = context.get_conn().await?;
+    let maybe_role = db::agent_role::FindQuery::new(room.id(), agent_id)
+        .execute(&mut conn)
+        .await?;
+
+    let role = db::agent_role::Role::resolve(room, agent_id, maybe_role.as_ref().map(|r| r.role()));
+
+    match role {
+        db::agent_role::Role::Viewer => Err(anyhow!(
+            "Viewers are not allowed to connect with write intent"
+        ))
+        .error(AppErrorKind::AccessDenied),
+        db::agent_role::Role::Speaker => Ok(room.rtc_sharing_policy() == RtcSharingPolicy::Shared
+            && maybe_role.is_some_and(|r| {
+                r.role() == db::agent_role::Role::Speaker && r.updated_by().is_some()
+            })),
+        _ => Ok(false),
+    }
+}
+
 #[instrument(skip(ctx, payload), fields(
     rtc_id = %rtc_id,
     intent = %payload.intent,
@@ -441,6 +695,7 @@ pub async fn connect_and_signal(
         agent_id,
         jsep: payload.jsep,
         label: payload.label,
+        preferred_region: payload.preferred_region,
     }
     .run()
     .await?;
@@ -460,6 +715,7 @@ struct ConnectAndSignal<'a, C> {
     agent_id: AgentId,
     jsep: JsonSdp,
     label: Option<String>,
+    preferred_region: Option<String>,
 }
 
 impl<C> ConnectAndSignal<'_, C>
@@ -491,6 +747,14 @@ where
         Ok(())
     }
 
+    async fn check_role(&self, room: &db::room::Object) -> Result<bool, AppError> {
+        if self.intent != ConnectIntent::Write {
+            return Ok(false);
+        }
+
+        check_write_role(self.ctx, room, &self.agent_id).await
+    }
+
     async fn check_room_policy(&self, room: &db::room::Object) -> Result<(), AppError> {
         // Authorize connecting to the rtc.
         match room.rtc_sharing_policy() {
@@ -557,72 +821,45 @@ where
             &tracing::field::display(room.classroom_id()),
         );
 
-        tokio::try_join!(self.check_room_policy(&room), self.authz(&room))?;
+        let (_, speaker_granted) =
+            tokio::try_join!(self.check_room_policy(&room), self.check_role(&room))?;
+
+        if !speaker_granted {
+            self.authz(&room).await?;
+        }
 
         let mut conn = self.ctx.get_conn().await?;
-        let group = self.ctx.config().janus_group.clone();
-        // There are 4 cases:
-        // 1. Connecting as a writer for a webinar for the first time. There's no `backend_id` in that case.
-        //    Select the most loaded backend that is capable to host the room's reservation.
-        //    If there's no capable backend then select the least loaded and send a warning
-        //    to Sentry. If there are no backends at all then return `no available backends`
-        // 2. Connecting as a writer for a minigroup for the first time. There's no `backend_id` in that case.
-        //    Select the least loaded backend and fallback on most loaded. Minigroups have a fixed size,
-        //    that is why least loaded should work fine.
-        // 3. Connecting as reader with existing `backend_id`. Choose it because Janus doesn't
+        // There are 3 cases:
+        // 1. Connecting as a writer for the first time. There's no `backend_id` in that case.
+        //    Select the backend with the balancer strategy configured for the room preferring
+        //    the requested region or the room's one if there's capacity left in it.
+        //    If there are no backends at all then return `no available backends`.
+        // 2. Connecting as reader with existing `backend_id`. Choose it because Janus doesn't
         //    support clustering and it must be the same server that the writer is connected to.
-        // 4. Reconnecting as writer with existing `backend_id`. Select it to avoid partitioning
+        // 3. Reconnecting as writer with existing `backend_id`. Select it to avoid partitioning
         //    of the record across multiple servers.
         let backend = match room.backend_id() {
-                Some(backend_id) => db::janus_backend::FindQuery::new(backend_id)
-                    .execute(&mut conn)
-                    .await?
-                    .context("No backend found for stream")
-                    .error(AppErrorKind::BackendNotFound)?,
-                None if group.as_deref() == Some("minigroup") => {
-                    let b = db::janus_backend::least_loaded(room.id(), group.as_deref(), &mut conn).await.transpose();
-                    let b = match b {
-                        Some(b) => Some(b),
-                        None => {
-                            db::janus_backend::most_loaded(room.id(), group.as_deref(), &mut conn).await.transpose()
-                        }
-                    };
-
-                    b
-                        .context("No available backends")
-                        .error(AppErrorKind::NoAvailableBackends)??
-                }
-                None => match db::janus_backend::most_loaded(room.id(), group.as_deref(), &mut conn).await? {
-                    Some(backend) => backend,
-                    None => db::janus_backend::least_loaded(room.id(), group.as_deref(), &mut conn).await?
-                        .map(|backend| {
-                            use sentry::protocol::{value::Value, Event, Level};
-                            let backend_id = backend.id().to_string();
-
-                            warn!(%backend_id, "No capable backends to host the reserve; falling back to the least loaded backend");
-
-                            let mut extra = std::collections::BTreeMap::new();
-                            extra.insert(String::from("room_id"), Value::from(room.id().to_string()));
-                            extra.insert(String::from("rtc_id"), Value::from(self.rtc_id.to_string()));
-                            extra.insert(String::from("backend_id"), Value::from(backend_id));
-
-                            if let Some(reserve) = room.reserve() {
-                                extra.insert(String::from("reserve"), Value::from(reserve));
-                            }
-
-                            sentry::capture_event(Event {
-                                message: Some(String::from("No capable backends to host the reserve; falling back to the least loaded backend")),
-                                level: Level::Warning,
-                                extra,
-                                ..Default::default()
-                            });
-
-                            backend
-                        })
-                        .context("No available backends")
-                        .error(AppErrorKind::NoAvailableBackends)?,
-                },
-            };
+            Some(backend_id) => db::janus_backend::FindQuery::new(backend_id)
+                .execute(&mut conn)
+                .await?
+                .context("No backend found for stream")
+                .error(AppErrorKind::BackendNotFound)?,
+            None => self
+                .ctx
+                .janus_clients()
+                .balancer()
+                .select(
+                    &room,
+                    Some(self.rtc_id),
+                    self.preferred_region
+                        .as_deref()
+                        .or_else(|| room.preferred_region()),
+                    &mut conn,
+                )
+                .await?
+                .context("No available backends")
+                .error(AppErrorKind::NoAvailableBackends)?,
+        };
 
         match self.intent {
             ConnectIntent::Read => {
@@ -819,8 +1056,11 @@ where
         // Create recording if a writer connects for the first time.
         // We run it after successful signaling to avoid in-progress recording entries
         // which are not really bound to anything.
+        // The part of the recording is bound to the backend since the room may move
+        // to another one in the middle of the recording.
         if let ConnectIntent::Write = self.intent {
             let id = self.rtc_id;
+            let backend_id = backend.id().clone();
             let mut conn = self.ctx.get_conn().await?;
 
             conn.transaction::<_, _, AppError>(|conn| {
@@ -831,6 +1071,10 @@ where
                         db::recording::InsertQuery::new(id).execute(conn).await?;
                     }
 
+                    db::recording::InsertPartQuery::new(id, &backend_id)
+                        .execute(conn)
+                        .await?;
+
                     Ok(())
                 })
             })
@@ -856,6 +1100,8 @@ pub struct ConnectPayload {
     intent: ConnectIntent,
     #[serde(default)]
     agent_label: Option<String>,
+    #[serde(default)]
+    preferred_region: Option<String>,
 }
 
 pub async fn connect(
@@ -869,6 +1115,7 @@ pub async fn connect(
     let request = ConnectRequest {
         id: rtc_id,
         intent: intent.intent,
+        preferred_region: intent.preferred_region,
     };
     let agent_id = intent
         .agent_label
@@ -942,87 +1189,63 @@ impl RequestHandler for ConnectHandler {
             }
         }
 
-        let rtc_id = payload.id.to_string();
-        let classroom_id = room.classroom_id().to_string();
-        let object = AuthzObject::new(&["classrooms", &classroom_id, "rtcs", &rtc_id]).into();
-
-        let action = match payload.intent {
-            ConnectIntent::Read => "read",
-            ConnectIntent::Write => "update",
+        let speaker_granted = match payload.intent {
+            ConnectIntent::Read => false,
+            ConnectIntent::Write => check_write_role(context, &room, reqp.as_agent_id()).await?,
         };
 
-        let authz_time = context
-            .authz()
-            .authorize(room.audience().into(), reqp, object, action.into())
-            .await?;
-        context.metrics().observe_auth(authz_time);
+        if !speaker_granted {
+            let rtc_id = payload.id.to_string();
+            let classroom_id = room.classroom_id().to_string();
+            let object = AuthzObject::new(&["classrooms", &classroom_id, "rtcs", &rtc_id]).into();
+
+            let action = match payload.intent {
+                ConnectIntent::Read => "read",
+                ConnectIntent::Write => "update",
+            };
+
+            let authz_time = context
+                .authz()
+                .authorize(room.audience().into(), reqp, object, action.into())
+                .await?;
+            context.metrics().observe_auth(authz_time);
+        }
+
         let room_id = room.id();
 
         // Choose backend to connect.
         let mut conn = context.get_conn().await?;
-        let group = context.config().janus_group.clone();
-        // There are 4 cases:
-        // 1. Connecting as a writer for a webinar for the first time. There's no `backend_id` in that case.
-        //    Select the most loaded backend that is capable to host the room's reservation.
-        //    If there's no capable backend then select the least loaded and send a warning
-        //    to Sentry. If there are no backends at all then return `no available backends`
-        // 2. Connecting as a writer for a minigroup for the first time. There's no `backend_id` in that case.
-        //    Select the least loaded backend and fallback on most loaded. Minigroups have a fixed size,
-        //    that is why least loaded should work fine.
-        // 3. Connecting as reader with existing `backend_id`. Choose it because Janus doesn't
+        // There are 3 cases:
+        // 1. Connecting as a writer for the first time. There's no `backend_id` in that case.
+        //    Select the backend with the balancer strategy configured for the room preferring
+        //    the requested region or the room's one if there's capacity left in it.
+        //    If there are no backends at all then return `no available backends`.
+        // 2. Connecting as reader with existing `backend_id`. Choose it because Janus doesn't
         //    support clustering and it must be the same server that the writer is connected to.
-        // 4. Reconnecting as writer with existing `backend_id`. Select it to avoid partitioning
+        // 3. Reconnecting as writer with existing `backend_id`. Select it to avoid partitioning
         //    of the record across multiple servers.
         let backend = match room.backend_id() {
-                Some(backend_id) => db::janus_backend::FindQuery::new(backend_id)
-                    .execute(&mut conn)
-                    .await?
-                    .context("No backend found for stream")
-                    .error(AppErrorKind::BackendNotFound)?,
-                None if group.as_deref() == Some("minigroup") => {
-                    let b = db::janus_backend::least_loaded(room.id(), group.as_deref(), &mut conn).await.transpose();
-                    let b = match b {
-                        Some(b) => Some(b),
-                        None => {
-                            db::janus_backend::most_loaded(room.id(), group.as_deref(), &mut conn).await.transpose()
-                        }
-                    };
-
-                    b
-                        .context("No available backends")
-                        .error(AppErrorKind::NoAvailableBackends)??
-                }
-                None => match db::janus_backend::most_loaded(room.id(), group.as_deref(), &mut conn).await? {
-                    Some(backend) => backend,
-                    None => db::janus_backend::least_loaded(room.id(), group.as_deref(), &mut conn).await?
-                        .map(|backend| {
-                            use sentry::protocol::{value::Value, Event, Level};
-                            let backend_id = backend.id().to_string();
-
-                            warn!(%backend_id, "No capable backends to host the reserve; falling back to the least loaded backend");
-
-                            let mut extra = std::collections::BTreeMap::new();
-                            extra.insert(String::from("room_id"), Value::from(room.id().to_string()));
-                            extra.insert(String::from("rtc_id"), Value::from(payload.id.to_string()));
-                            extra.insert(String::from("backend_id"), Value::from(backend_id));
-
-                            if let Some(reserve) = room.reserve() {
-                                extra.insert(String::from("reserve"), Value::from(reserve));
-                            }
-
-                            sentry::capture_event(Event {
-                                message: Some(String::from("No capable backends to host the reserve; falling back to the least loaded backend")),
-                                level: Level::Warning,
-                                extra,
-                                ..Default::default()
-                            });
-
-                            backend
-                        })
-                        .context("No available backends")
-                        .error(AppErrorKind::NoAvailableBackends)?,
-                },
-            };
+            Some(backend_id) => db::janus_backend::FindQuery::new(backend_id)
+                .execute(&mut conn)
+                .await?
+                .context("No backend found for stream")
+                .error(AppErrorKind::BackendNotFound)?,
+            None => context
+                .janus_clients()
+                .balancer()
+                .select(
+                    &room,
+                    Some(payload.id),
+                    payload
+                        .preferred_region
+                        .as_deref()
+                        .or_else(|| room.preferred_region()),
+                    &mut conn,
+                )
+                .await?
+                .context("No available backends")
+                .error(AppErrorKind::NoAvailableBackends)?,
+        };
 
         match payload.intent {
             ConnectIntent::Read => {
@@ -1145,7 +1368,10 @@ mod test {
 
             // Make rtc.create request.
             let mut context = TestContext::new(db, authz).await;
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
 
             let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
                 .await
@@ -1171,6 +1397,7 @@ mod test {
             let mut context = TestContext::new(db, TestAuthz::new()).await;
             let payload = CreateRequest {
                 room_id: db::room::Id::random(),
+                kind: db::rtc::Kind::Camera,
             };
 
             let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
@@ -1199,7 +1426,10 @@ mod test {
 
             // Make rtc.create request.
             let mut context = TestContext::new(db, authz).await;
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
 
             let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
                 .await
@@ -1211,7 +1441,10 @@ mod test {
             assert_eq!(rtc.room_id(), room.id());
 
             // Make rtc.create request second time.
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
             let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
                 .await
                 .expect_err("Unexpected success on rtc creation");
@@ -1240,13 +1473,19 @@ mod test {
 
             // Make two rtc.create requests.
             let mut context = TestContext::new(db, authz).await;
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
 
             let messages1 = handle_request::<CreateHandler>(&mut context, &agent1, payload)
                 .await
                 .expect("RTC creation failed");
 
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
 
             let messages2 = handle_request::<CreateHandler>(&mut context, &agent2, payload)
                 .await
@@ -1281,7 +1520,10 @@ mod test {
 
             // Make the first rtc.create request.
             let mut context = TestContext::new(db, authz).await;
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
 
             let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
                 .await
@@ -1294,7 +1536,58 @@ mod test {
             assert_eq!(rtc.created_by(), agent.agent_id());
 
             // Make the second rtc.create request and expect fail.
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
+
+            let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on RTC creation");
+
+            assert_eq!(err.status(), ResponseStatus::UNPROCESSABLE_ENTITY);
+            assert_eq!(err.kind(), "database_query_failed");
+        }
+
+        #[sqlx::test]
+        async fn create_screen_rtc_with_owned_sharing_policy(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut authz = TestAuthz::new();
+
+            // Insert a room.
+            let mut conn = db.get_conn().await;
+            let room = shared_helpers::insert_room_with_owned(&mut conn).await;
+
+            // Allow agent to create RTCs in the room.
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let classroom_id = room.classroom_id().to_string();
+            let object = vec!["classrooms", &classroom_id, "rtcs"];
+            authz.allow(agent.account_id(), object, "create");
+
+            // Create camera and screen RTCs for the same agent.
+            let mut context = TestContext::new(db, authz).await;
+
+            for kind in [db::rtc::Kind::Camera, db::rtc::Kind::Screen] {
+                let payload = CreateRequest {
+                    room_id: room.id(),
+                    kind,
+                };
+
+                let messages = handle_request::<CreateHandler>(&mut context, &agent, payload)
+                    .await
+                    .expect("RTC creation failed");
+
+                let (rtc, respp, _) = find_response::<Rtc>(messages.as_slice());
+                assert_eq!(respp.status(), ResponseStatus::CREATED);
+                assert_eq!(rtc.created_by(), agent.agent_id());
+                assert_eq!(rtc.kind(), kind);
+            }
+
+            // The second screen RTC is not allowed.
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Screen,
+            };
 
             let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
                 .await
@@ -1316,7 +1609,10 @@ mod test {
 
             // Make rtc.create request.
             let mut context = TestContext::new(db, TestAuthz::new()).await;
-            let payload = CreateRequest { room_id: room.id() };
+            let payload = CreateRequest {
+                room_id: room.id(),
+                kind: db::rtc::Kind::Camera,
+            };
 
             let err = handle_request::<CreateHandler>(&mut context, &agent, payload)
                 .await
@@ -1412,6 +1708,182 @@ mod test {
         }
     }
 
+    mod delete {
+        use std::ops::Bound;
+
+        use serde_json::Value as JsonValue;
+
+        use crate::{
+            db::{janus_rtc_stream::Object as JanusRtcStream, rtc::Object as Rtc},
+            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn delete_own_rtc(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let reader = TestAgent::new("web", "user456", USR_AUDIENCE);
+
+            let rtc = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room_with_owned(&mut conn).await;
+
+                let rtc = factory::Rtc::new(room.id())
+                    .created_by(agent.agent_id().to_owned())
+                    .insert(&mut conn)
+                    .await;
+
+                shared_helpers::insert_recording(&mut conn, &rtc).await;
+
+                shared_helpers::insert_connected_agent(
+                    &mut conn,
+                    reader.agent_id(),
+                    room.id(),
+                    rtc.id(),
+                )
+                .await;
+
+                let stream = factory::JanusRtcStream::new(USR_AUDIENCE)
+                    .rtc(&rtc)
+                    .insert(&mut conn)
+                    .await;
+
+                db::janus_rtc_stream::start(stream.id(), &mut conn)
+                    .await
+                    .expect("Failed to start rtc stream");
+
+                rtc
+            };
+
+            // Make rtc.delete request. The owner doesn't need the permission.
+            let mut context = TestContext::new(db.clone(), TestAuthz::new()).await;
+            let payload = DeleteRequest { id: rtc.id() };
+
+            let messages = handle_request::<DeleteHandler>(&mut context, &agent, payload)
+                .await
+                .expect("RTC deletion failed");
+
+            // Assert response.
+            let (resp_rtc, respp, _) = find_response::<Rtc>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+            assert_eq!(resp_rtc.id(), rtc.id());
+
+            // Assert notifications.
+            let (stream, _, _) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "rtc_stream.update"
+                })
+                .expect("Missing rtc_stream.update event");
+
+            let stream = serde_json::from_value::<JanusRtcStream>(stream)
+                .expect("Failed to parse rtc_stream.update event");
+
+            assert_eq!(stream.rtc_id(), rtc.id());
+            assert!(matches!(stream.time(), Some((_, Bound::Excluded(_)))));
+
+            let (event_rtc, _, topic) =
+                find_event_by_predicate::<JsonValue, _>(messages.as_slice(), |evp, _, _| {
+                    evp.label() == "rtc.delete"
+                })
+                .expect("Missing rtc.delete event");
+
+            let event_rtc =
+                serde_json::from_value::<Rtc>(event_rtc).expect("Failed to parse rtc.delete event");
+
+            assert!(topic.ends_with(&format!("/rooms/{}/events", rtc.room_id())));
+            assert_eq!(event_rtc.id(), rtc.id());
+
+            // Assert the RTC is gone while its recording is kept for upload.
+            let mut conn = db.get_conn().await;
+
+            let maybe_rtc = db::rtc::FindQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find rtc");
+
+            assert!(maybe_rtc.is_none());
+
+            let recording = db::recording::FindQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find recording");
+
+            assert!(recording.is_some());
+
+            let connections = db::agent_connection::ListByRtcQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list agent connections");
+
+            assert!(connections.is_empty());
+
+            // The agent may create a new RTC instead of the deleted one.
+            db::rtc::InsertQuery::new(rtc.room_id(), agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to create rtc");
+        }
+
+        #[sqlx::test]
+        async fn delete_shared_rtc(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let (rtc, classroom_id) = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room(&mut conn).await;
+
+                let rtc = factory::Rtc::new(room.id())
+                    .created_by(agent.agent_id().to_owned())
+                    .insert(&mut conn)
+                    .await;
+
+                (rtc, room.classroom_id().to_string())
+            };
+
+            // Allow agent to delete the rtc.
+            let mut authz = TestAuthz::new();
+            let rtc_id = rtc.id().to_string();
+            let object = vec!["classrooms", &classroom_id, "rtcs", &rtc_id];
+            authz.allow(agent.account_id(), object, "delete");
+
+            let mut context = TestContext::new(db, authz).await;
+            let payload = DeleteRequest { id: rtc.id() };
+
+            let messages = handle_request::<DeleteHandler>(&mut context, &agent, payload)
+                .await
+                .expect("RTC deletion failed");
+
+            let (_, respp, _) = find_response::<Rtc>(messages.as_slice());
+            assert_eq!(respp.status(), ResponseStatus::OK);
+        }
+
+        #[sqlx::test]
+        async fn delete_rtc_not_authorized(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let rtc = {
+                let mut conn = db.get_conn().await;
+                let room = shared_helpers::insert_room_with_owned(&mut conn).await;
+                shared_helpers::insert_rtc_with_room(&mut conn, &room).await
+            };
+
+            // Owned RTCs of other agents can't be deleted without the permission.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+            let payload = DeleteRequest { id: rtc.id() };
+
+            let err = handle_request::<DeleteHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on rtc deletion");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+    }
+
     mod list {
         use crate::{
             db::rtc::Object as Rtc,
@@ -1600,6 +2072,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc3.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             let messages = handle_request::<ConnectHandler>(&mut context, &s3a1, payload)
@@ -1655,6 +2128,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             let messages = handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -1744,6 +2218,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             let messages = handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -1846,6 +2321,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc2.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             // Should be ok since we disregard reserves.
@@ -1867,6 +2343,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc1.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             // Expect success.
@@ -1929,6 +2406,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             handle_request::<ConnectHandler>(&mut context, &reader, payload)
@@ -2004,6 +2482,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             let err = handle_request::<ConnectHandler>(&mut context, &reader2, payload)
@@ -2067,6 +2546,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Write,
+                preferred_region: None,
             };
 
             handle_request::<ConnectHandler>(&mut context, &writer, payload)
@@ -2252,6 +2732,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Write,
+                preferred_region: None,
             };
 
             let messages = handle_request::<ConnectHandler>(&mut context, &new_writer, payload)
@@ -2407,6 +2888,7 @@ mod test {
                 let payload = ConnectRequest {
                     id: rtc.0.id(),
                     intent: ConnectIntent::Read,
+                    preferred_region: None,
                 };
 
                 // Make an rtc.connect request.
@@ -2426,6 +2908,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtcs[2].0.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             // Last room has NO reserve AND there is free capacity BUT it was exhausted by first two rooms
@@ -2489,6 +2972,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Write,
+                preferred_region: None,
             };
 
             handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -2545,6 +3029,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Write,
+                preferred_region: None,
             };
 
             let err = handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -2604,6 +3089,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -2683,6 +3169,7 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             let messages = handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -2713,6 +3200,60 @@ mod test {
             let payload = ConnectRequest {
                 id: rtc.id(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
+            };
+
+            let err = handle_request::<ConnectHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on rtc connecting");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        #[sqlx::test]
+        async fn connect_as_viewer_for_writing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let (rtc, room) = {
+                let mut conn = db.get_conn().await;
+                let rtc = shared_helpers::insert_rtc(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), rtc.room_id()).await;
+
+                db::agent_role::UpsertQuery::new(
+                    rtc.room_id(),
+                    agent.agent_id(),
+                    db::agent_role::Role::Viewer,
+                )
+                .execute(&mut conn)
+                .await
+                .expect("Failed to assign agent role");
+
+                let room = helpers::find_room_by_rtc_id(
+                    rtc.id(),
+                    helpers::RoomTimeRequirement::Open,
+                    &mut conn,
+                )
+                .await
+                .expect("Failed to find room");
+
+                (rtc, room)
+            };
+
+            // Allow agent to update the RTC so only the role prevents connecting.
+            let mut authz = TestAuthz::new();
+            let classroom_id = room.classroom_id().to_string();
+            let rtc_id = rtc.id().to_string();
+            let object = vec!["classrooms", &classroom_id, "rtcs", &rtc_id];
+            authz.allow(agent.account_id(), object, "update");
+
+            let mut context = TestContext::new(db, authz).await;
+
+            let payload = ConnectRequest {
+                id: rtc.id(),
+                intent: ConnectIntent::Write,
+                preferred_region: None,
             };
 
             let err = handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -2733,6 +3274,40 @@ mod test {
             let payload = ConnectRequest {
                 id: db::rtc::Id::random(),
                 intent: ConnectIntent::Read,
+                preferred_region: None,
+            };
+
+            let err = handle_request::<ConnectHandler>(&mut context, &agent, payload)
+                .await
+                .expect_err("Unexpected success on rtc connecting");
+
+            assert_eq!(err.status(), ResponseStatus::NOT_FOUND);
+            assert_eq!(err.kind(), "room_not_found");
+        }
+
+        #[sqlx::test]
+        async fn connect_to_rtc_deleted(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let rtc = {
+                let mut conn = db.get_conn().await;
+                let rtc = shared_helpers::insert_rtc(&mut conn).await;
+
+                db::rtc::DeleteQuery::new(rtc.id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to delete rtc");
+
+                rtc
+            };
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = ConnectRequest {
+                id: rtc.id(),
+                intent: ConnectIntent::Read,
+                preferred_region: None,
             };
 
             let err = handle_request::<ConnectHandler>(&mut context, &agent, payload)
@@ -2743,4 +3318,146 @@ mod test {
             assert_eq!(err.kind(), "room_not_found");
         }
     }
+
+    mod connect_and_signal {
+        use serde_json::json;
+
+        use crate::{
+            db::agent_role::{Role, UpsertQuery as AgentRoleUpsertQuery},
+            test_helpers::{db::TestDb, prelude::*},
+        };
+
+        use super::super::*;
+
+        #[sqlx::test]
+        async fn connect_as_viewer_for_writing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let rtc = {
+                let mut conn = db.get_conn().await;
+                let rtc = shared_helpers::insert_rtc(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), rtc.room_id()).await;
+
+                AgentRoleUpsertQuery::new(rtc.room_id(), agent.agent_id(), Role::Viewer)
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to assign agent role");
+
+                rtc
+            };
+
+            let mut authz = TestAuthz::new();
+            let classroom_id = {
+                let mut conn = db.get_conn().await;
+                helpers::find_room_by_rtc_id(
+                    rtc.id(),
+                    helpers::RoomTimeRequirement::Open,
+                    &mut conn,
+                )
+                .await
+                .expect("Failed to find room")
+                .classroom_id()
+                .to_string()
+            };
+            let rtc_id = rtc.id().to_string();
+            let object = vec!["classrooms", &classroom_id, "rtcs", &rtc_id];
+            authz.allow(agent.account_id(), object, "update");
+
+            let mut context = TestContext::new(db, authz).await;
+
+            let jsep = serde_json::from_value(json!({ "type": "offer", "sdp": "v=0" }))
+                .expect("Failed to build jsep");
+
+            let err = ConnectAndSignal {
+                ctx: &mut context,
+                rtc_id: rtc.id(),
+                intent: ConnectIntent::Write,
+                agent_id: agent.agent_id().to_owned(),
+                jsep,
+                label: None,
+                preferred_region: None,
+            }
+            .run()
+            .await
+            .err()
+            .expect("Unexpected success on rtc connecting");
+
+            assert_eq!(err.status(), ResponseStatus::FORBIDDEN);
+            assert_eq!(err.kind(), "access_denied");
+        }
+
+        fn granted_speaker_connect<'a>(
+            context: &'a mut TestContext,
+            rtc_id: db::rtc::Id,
+            agent_id: &AgentId,
+        ) -> ConnectAndSignal<'a, TestContext> {
+            let jsep = serde_json::from_value(json!({ "type": "offer", "sdp": "v=0" }))
+                .expect("Failed to build jsep");
+
+            ConnectAndSignal {
+                ctx: context,
+                rtc_id,
+                intent: ConnectIntent::Write,
+                agent_id: agent_id.to_owned(),
+                jsep,
+                label: None,
+                preferred_region: None,
+            }
+        }
+
+        #[sqlx::test]
+        async fn connect_as_granted_speaker_for_writing(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+
+            let rtc = {
+                let mut conn = db.get_conn().await;
+                let rtc = shared_helpers::insert_rtc(&mut conn).await;
+                shared_helpers::insert_agent(&mut conn, agent.agent_id(), rtc.room_id()).await;
+
+                // The role every agent gets on enter.
+                AgentRoleUpsertQuery::new(rtc.room_id(), agent.agent_id(), Role::Speaker)
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to assign agent role");
+
+                rtc
+            };
+
+            // The agent is not authorized to publish on the tenant.
+            let mut context = TestContext::new(db.clone(), TestAuthz::new()).await;
+            let (tx, _) = tokio::sync::mpsc::unbounded_channel();
+            context.with_janus(tx);
+
+            let err = granted_speaker_connect(&mut context, rtc.id(), agent.agent_id())
+                .run()
+                .await
+                .err()
+                .expect("Unexpected success on rtc connecting");
+
+            assert_eq!(err.kind(), "access_denied");
+
+            // A moderator accepts the agent's hand.
+            {
+                let mut conn = db.get_conn().await;
+
+                AgentRoleUpsertQuery::new(rtc.room_id(), agent.agent_id(), Role::Speaker)
+                    .updated_by(host.agent_id())
+                    .execute(&mut conn)
+                    .await
+                    .expect("Failed to assign agent role");
+            }
+
+            // Passes the authorization and fails on choosing the backend since there are none.
+            let err = granted_speaker_connect(&mut context, rtc.id(), agent.agent_id())
+                .run()
+                .await
+                .err()
+                .expect("Unexpected success on rtc connecting");
+
+            assert_eq!(err.kind(), "no_available_backends");
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::stream;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -9,6 +9,7 @@ use svc_agent::{
     mqtt::{
         IncomingEventProperties, IncomingRequestProperties, IncomingResponseProperties,
         IntoPublishableMessage, OutgoingEvent, ResponseStatus, ShortTermTimingProperties,
+        TrackingProperties,
     },
     Addressable, AgentId,
 };
@@ -44,11 +45,21 @@ impl CorrelationDataPayload {
 pub struct RoomEnterLeaveEvent {
     id: db::room::Id,
     agent_id: AgentId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<db::agent::Metadata>,
 }
 
 impl RoomEnterLeaveEvent {
     pub fn new(id: db::room::Id, agent_id: AgentId) -> Self {
-        Self { id, agent_id }
+        Self {
+            id,
+            agent_id,
+            metadata: None,
+        }
+    }
+
+    pub fn metadata(self, metadata: Option<db::agent::Metadata>) -> Self {
+        Self { metadata, ..self }
     }
 }
 
@@ -75,8 +86,14 @@ impl ResponseHandler for DeleteResponseHandler {
     ) -> MqttResult {
         ensure_broker(context, respp)?;
         let room_id = try_room_id(&corr_data.object)?;
-        let maybe_left = leave_room(context, &corr_data.subject, room_id).await?;
-        if maybe_left {
+        let maybe_left = leave_room(
+            context,
+            &corr_data.subject,
+            room_id,
+            db::attendance::LeaveReason::Leave,
+        )
+        .await?;
+        if let Some(left) = maybe_left {
             let response = helpers::build_response(
                 ResponseStatus::OK,
                 json!({}),
@@ -98,7 +115,15 @@ impl ResponseHandler for DeleteResponseHandler {
                 .subscription_delete_response
                 .observe_timestamp(context.start_timestamp());
 
-            Ok(Box::new(stream::iter(vec![response, notification])))
+            let mut messages = vec![response, notification];
+            messages.extend(
+                left.host_change_notifications(
+                    corr_data.reqp.tracking(),
+                    context.start_timestamp(),
+                ),
+            );
+
+            Ok(Box::new(stream::iter(messages)))
         } else {
             Err(anyhow!("The agent is not found")).error(AppErrorKind::AgentNotEnteredTheRoom)
         }
@@ -124,7 +149,14 @@ impl EventHandler for DeleteEventHandler {
     ) -> MqttResult {
         ensure_broker(context, evp)?;
         let room_id = try_room_id(&payload.object)?;
-        if leave_room(context, &payload.subject, room_id).await? {
+        if let Some(left) = leave_room(
+            context,
+            &payload.subject,
+            room_id,
+            db::attendance::LeaveReason::Disconnect,
+        )
+        .await?
+        {
             let outgoing_event_payload =
                 RoomEnterLeaveEvent::new(room_id, payload.subject.to_owned());
             let short_term_timing = ShortTermTimingProperties::until_now(context.start_timestamp());
@@ -139,7 +171,11 @@ impl EventHandler for DeleteEventHandler {
                 .subscription_delete_event
                 .observe_timestamp(context.start_timestamp());
 
-            Ok(Box::new(stream::once(std::future::ready(notification))))
+            let mut messages = vec![notification];
+            messages
+                .extend(left.host_change_notifications(evp.tracking(), context.start_timestamp()));
+
+            Ok(Box::new(stream::iter(messages)))
         } else {
             Ok(Box::new(stream::empty()))
         }
@@ -184,7 +220,8 @@ async fn leave_room<C: Context>(
     context: &mut C,
     agent_id: &AgentId,
     room_id: db::room::Id,
-) -> StdResult<bool, AppError> {
+    reason: db::attendance::LeaveReason,
+) -> StdResult<Option<LeftRoom>, AppError> {
     let mut conn = context.get_conn().await?;
     let row_count = db::agent::DeleteQuery::new()
         .agent_id(agent_id)
@@ -205,30 +242,81 @@ async fn leave_room<C: Context>(
         .execute(&mut conn)
         .await?;
 
+    // Attendance entries are closed the same way as agent rows are deleted above.
+    db::attendance::CloseQuery::new(reason)
+        .agent_id(agent_id)
+        .execute(&mut conn)
+        .await?;
+
+    db::raised_hand::DeleteQuery::new(room_id, agent_id)
+        .execute(&mut conn)
+        .await?;
+
     let left = if row_count < 1 {
-        false
+        None
     } else {
-        make_orphaned_if_host_left(room_id, agent_id, &mut conn).await?;
-
-        true
+        let updated_room = make_orphaned_if_host_left(room_id, agent_id, &mut conn).await?;
+        Some(LeftRoom { updated_room })
     };
 
     Ok(left)
 }
 
+struct LeftRoom {
+    /// The room with a co-host promoted to host if the host has left.
+    updated_room: Option<db::room::Object>,
+}
+
+impl LeftRoom {
+    fn host_change_notifications(
+        self,
+        tracking: &TrackingProperties,
+        start_timestamp: DateTime<Utc>,
+    ) -> Vec<Box<dyn IntoPublishableMessage + Send + Sync + 'static>> {
+        match self.updated_room {
+            Some(room) => vec![
+                helpers::build_notification(
+                    "room.update",
+                    &format!("audiences/{}/events", room.audience()),
+                    room.clone(),
+                    tracking,
+                    start_timestamp,
+                ),
+                helpers::build_notification(
+                    "room.update",
+                    &format!("rooms/{}/events", room.id()),
+                    room,
+                    tracking,
+                    start_timestamp,
+                ),
+            ],
+            None => vec![],
+        }
+    }
+}
+
+/// Passes the host role to the next present co-host when the host leaves the room.
+/// The room becomes orphaned only if there's nobody to pass the role to.
 async fn make_orphaned_if_host_left(
     room_id: db::room::Id,
     agent_left: &AgentId,
     connection: &mut sqlx::PgConnection,
-) -> sqlx::Result<()> {
+) -> sqlx::Result<Option<db::room::Object>> {
     let room = db::room::FindQuery::new(room_id)
         .execute(connection)
         .await?;
 
-    if room.as_ref().and_then(|x| x.host()) == Some(agent_left) {
+    if room.as_ref().and_then(|x| x.host()) != Some(agent_left) {
+        return Ok(None);
+    }
+
+    let updated_room = db::room::promote_co_host(room_id, agent_left, connection).await?;
+
+    if updated_room.is_none() {
         db::orphaned_room::upsert_room(room_id, Utc::now(), connection).await?;
     }
-    Ok(())
+
+    Ok(updated_room)
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -389,7 +477,7 @@ mod tests {
     mod delete_event {
         use crate::{
             db::agent::ListQuery as AgentListQuery,
-            test_helpers::{db::TestDb, prelude::*},
+            test_helpers::{db::TestDb, find_event_by_predicate, prelude::*},
         };
 
         use super::super::*;
@@ -458,6 +546,105 @@ mod tests {
             assert_eq!(db_agents.len(), 0);
         }
 
+        #[sqlx::test]
+        async fn delete_subscription_closes_attendance(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let room = shared_helpers::insert_room(&mut conn).await;
+            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+
+            db::attendance::InsertQuery::new(room.id(), agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert attendance entry");
+
+            // Send subscription.delete event.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = DeleteEventPayload {
+                subject: agent.agent_id().to_owned(),
+                object: vec![
+                    "rooms".to_string(),
+                    room.id().to_string(),
+                    "events".to_string(),
+                ],
+            };
+
+            let broker_account_label = context.config().broker_id.label();
+            let broker = TestAgent::new("alpha", broker_account_label, SVC_AUDIENCE);
+
+            handle_event::<DeleteEventHandler>(&mut context, &broker, payload)
+                .await
+                .expect("Subscription deletion failed");
+
+            // Assert the attendance entry is closed as disconnected.
+            let mut conn = context
+                .get_conn()
+                .await
+                .expect("Failed to get DB connection");
+
+            let summary = db::attendance::SummaryQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to execute attendance summary query");
+
+            assert_eq!(summary.len(), 1);
+            assert!(!summary[0].online());
+            assert_eq!(
+                summary[0].last_leave_reason(),
+                Some(db::attendance::LeaveReason::Disconnect)
+            );
+        }
+
+        #[sqlx::test]
+        async fn delete_subscription_lowers_hand(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+            let room = shared_helpers::insert_room(&mut conn).await;
+            shared_helpers::insert_agent(&mut conn, agent.agent_id(), room.id()).await;
+
+            db::raised_hand::InsertQuery::new(room.id(), agent.agent_id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to raise hand");
+
+            // Send subscription.delete event.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = DeleteEventPayload {
+                subject: agent.agent_id().to_owned(),
+                object: vec![
+                    "rooms".to_string(),
+                    room.id().to_string(),
+                    "events".to_string(),
+                ],
+            };
+
+            let broker_account_label = context.config().broker_id.label();
+            let broker = TestAgent::new("alpha", broker_account_label, SVC_AUDIENCE);
+
+            handle_event::<DeleteEventHandler>(&mut context, &broker, payload)
+                .await
+                .expect("Subscription deletion failed");
+
+            // Assert the agent has left the raise hand queue.
+            let mut conn = context
+                .get_conn()
+                .await
+                .expect("Failed to get DB connection");
+
+            let queue = db::raised_hand::ListQuery::new(room.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to list raised hands");
+
+            assert!(queue.is_empty());
+        }
+
         #[sqlx::test]
         async fn delete_subscription_missing_agent(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
@@ -485,5 +672,138 @@ mod tests {
 
             assert!(messages.is_empty());
         }
+
+        #[sqlx::test]
+        async fn host_left_co_host_promoted(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let absent_co_host = TestAgent::new("web", "co-host-1", USR_AUDIENCE);
+            let co_host = TestAgent::new("web", "co-host-2", USR_AUDIENCE);
+
+            // Create a room where the first co-host is absent and the second one is online.
+            let room = shared_helpers::insert_room(&mut conn).await;
+            let co_hosts = vec![
+                absent_co_host.agent_id().to_owned(),
+                co_host.agent_id().to_owned(),
+            ];
+
+            db::room::UpdateQuery::new(room.id())
+                .host(Some(host.agent_id()))
+                .co_hosts(Some(&co_hosts))
+                .execute(&mut conn)
+                .await
+                .expect("Failed to update room");
+
+            shared_helpers::insert_agent(&mut conn, host.agent_id(), room.id()).await;
+            shared_helpers::insert_agent(&mut conn, co_host.agent_id(), room.id()).await;
+
+            // Send subscription.delete event for the host.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = DeleteEventPayload {
+                subject: host.agent_id().to_owned(),
+                object: vec![
+                    "rooms".to_string(),
+                    room.id().to_string(),
+                    "events".to_string(),
+                ],
+            };
+
+            let broker_account_label = context.config().broker_id.label();
+            let broker = TestAgent::new("alpha", broker_account_label, SVC_AUDIENCE);
+
+            let messages = handle_event::<DeleteEventHandler>(&mut context, &broker, payload)
+                .await
+                .expect("Subscription deletion failed");
+
+            // Assert room.update notification with the new host.
+            let (room_update, _, _) = find_event_by_predicate::<serde_json::Value, _>(
+                messages.as_slice(),
+                |evp, _, topic| {
+                    evp.label() == "room.update"
+                        && topic.ends_with(&format!("/rooms/{}/events", room.id()))
+                },
+            )
+            .expect("Failed to find room.update event");
+
+            assert_eq!(
+                room_update.get("host").and_then(|v| v.as_str()),
+                Some(co_host.agent_id().to_string().as_str())
+            );
+
+            // Assert the room is not orphaned.
+            let mut conn = context
+                .get_conn()
+                .await
+                .expect("Failed to get DB connection");
+
+            let orphaned = db::orphaned_room::get_timed_out(Utc::now(), &mut conn)
+                .await
+                .expect("Failed to get orphaned rooms");
+
+            assert!(orphaned.is_empty());
+        }
+
+        #[sqlx::test]
+        async fn host_left_no_co_host_present(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let host = TestAgent::new("web", "host", USR_AUDIENCE);
+            let co_host = TestAgent::new("web", "co-host", USR_AUDIENCE);
+
+            // Create a room with an absent co-host.
+            let room = shared_helpers::insert_room(&mut conn).await;
+            let co_hosts = vec![co_host.agent_id().to_owned()];
+
+            db::room::UpdateQuery::new(room.id())
+                .host(Some(host.agent_id()))
+                .co_hosts(Some(&co_hosts))
+                .execute(&mut conn)
+                .await
+                .expect("Failed to update room");
+
+            shared_helpers::insert_agent(&mut conn, host.agent_id(), room.id()).await;
+
+            // Send subscription.delete event for the host.
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let payload = DeleteEventPayload {
+                subject: host.agent_id().to_owned(),
+                object: vec![
+                    "rooms".to_string(),
+                    room.id().to_string(),
+                    "events".to_string(),
+                ],
+            };
+
+            let broker_account_label = context.config().broker_id.label();
+            let broker = TestAgent::new("alpha", broker_account_label, SVC_AUDIENCE);
+
+            let messages = handle_event::<DeleteEventHandler>(&mut context, &broker, payload)
+                .await
+                .expect("Subscription deletion failed");
+
+            // Assert only room.leave notification.
+            assert_eq!(messages.len(), 1);
+
+            // Assert the room is orphaned.
+            let mut conn = context
+                .get_conn()
+                .await
+                .expect("Failed to get DB connection");
+
+            let orphaned = db::orphaned_room::get_timed_out(
+                Utc::now() + chrono::Duration::seconds(1),
+                &mut conn,
+            )
+            .await
+            .expect("Failed to get orphaned rooms");
+
+            assert_eq!(orphaned.len(), 1);
+            assert_eq!(orphaned[0].0.id, room.id());
+        }
     }
 }
@@ -13,7 +13,7 @@ use crate::{
     db,
     db::{
         recording::{Object as Recording, Status as RecordingStatus},
-        room::Object as Room,
+        room::{FindQueryable, Object as Room},
         rtc::SharingPolicy,
     },
 };
@@ -23,6 +23,7 @@ use chrono::Utc;
 use futures::stream;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sqlx::Connection;
 use std::{ops::Bound, result::Result as StdResult};
 use svc_agent::{
     mqtt::{
@@ -33,7 +34,7 @@ use svc_agent::{
 };
 use svc_authn::Authenticable;
 
-use tracing::error;
+use tracing::{error, info};
 use tracing_attributes::instrument;
 
 use super::MqttResult;
@@ -53,6 +54,7 @@ struct RtcUploadEventData {
     #[serde(skip_serializing_if = "Option::is_none")]
     uri: Option<String>,
     created_by: AgentId,
+    kind: db::rtc::Kind,
     mjr_dumps_uris: Option<Vec<String>>,
 }
 
@@ -101,42 +103,9 @@ impl RequestHandler for VacuumHandler {
             None,
         );
 
-        let mut conn = context.get_conn().await?;
-        let rooms = db::room::finished_with_in_progress_recordings(
-            &mut conn,
-            context.config().janus_group.as_deref(),
-        )
-        .await?;
-
-        for (room, recording, backend) in rooms.into_iter() {
-            db::agent::DeleteQuery::new()
-                .room_id(room.id())
-                .execute(&mut conn)
-                .await?;
-
-            let config = upload_config(context, &room)?;
-            let request = UploadStreamRequest {
-                body: UploadStreamRequestBody::new(
-                    recording.rtc_id(),
-                    &config.backend,
-                    &config.bucket,
-                ),
-                handle_id: backend.handle_id(),
-                session_id: backend.session_id(),
-            };
-            let transaction = UploadStreamTransaction {
-                rtc_id: recording.rtc_id(),
-                start_timestamp: context.start_timestamp(),
-            };
-            // TODO: Send the error as an event to "app/${APP}/audiences/${AUD}" topic
-            context
-                .janus_clients()
-                .get_or_insert(&backend)
-                .error(AppErrorKind::BackendClientCreationFailed)?
-                .upload_stream(request, transaction)
-                .await
-                .error(AppErrorKind::BackendRequestFailed)?;
+        let (rooms, uploads) = vacuum(context).await?;
 
+        for room in rooms {
             // Publish room closed notification
             response.add_notification(
                 "room.close",
@@ -146,10 +115,106 @@ impl RequestHandler for VacuumHandler {
             );
         }
 
+        for upload in uploads {
+            response.add_message(Box::new(upload));
+        }
+
         Ok(response)
     }
 }
 
+/// Starts uploading recordings of the finished rooms and returns these rooms along with
+/// `room.upload` events for the rooms which recordings got completed without uploading.
+pub async fn vacuum<C: Context>(
+    context: &mut C,
+) -> StdResult<(Vec<Room>, Vec<RoomUploadEvent>), AppError> {
+    let mut conn = context.get_conn().await?;
+    let mut uploads = Vec::new();
+
+    for rtc_id in db::recording::finished_with_settled_parts(&mut conn).await? {
+        let r = conn
+            .transaction(|conn| {
+                Box::pin(async move {
+                    if db::recording::complete_from_parts(rtc_id, conn)
+                        .await?
+                        .is_none()
+                    {
+                        return Ok(None);
+                    }
+
+                    // The RTC may have been deleted with its recording left to be uploaded.
+                    db::room::FindByRtcIdQuery::new(rtc_id)
+                        .include_deleted()
+                        .execute(conn)
+                        .await?
+                        .context("Room not found")
+                        .error(AppErrorKind::RoomNotFound)
+                        .map(Some)
+                })
+            })
+            .await;
+
+        let room = match r {
+            Ok(Some(room)) => room,
+            Ok(None) => continue,
+            Err(err) => {
+                error!(?err, %rtc_id, "Completing recording failed");
+                continue;
+            }
+        };
+
+        match room_upload_event(context, &room, &mut conn).await {
+            Ok(Some(event)) => uploads.push(event),
+            Ok(None) => (),
+            Err(err) => error!(?err, room_id = %room.id(), "Building room.upload event failed"),
+        }
+    }
+
+    let rooms = db::room::finished_with_in_progress_recordings(
+        &mut conn,
+        context.config().janus_group.as_deref(),
+    )
+    .await?;
+
+    let mut vacuumed_rooms = Vec::with_capacity(rooms.len());
+
+    for (room, recording, backend) in rooms.into_iter() {
+        db::agent::DeleteQuery::new()
+            .room_id(room.id())
+            .execute(&mut conn)
+            .await?;
+
+        db::attendance::CloseQuery::new(db::attendance::LeaveReason::RoomClose)
+            .room_id(room.id())
+            .execute(&mut conn)
+            .await?;
+
+        let config = upload_config(context, &room)?;
+        let request = UploadStreamRequest {
+            body: UploadStreamRequestBody::new(recording.rtc_id(), &config.backend, &config.bucket),
+            handle_id: backend.handle_id(),
+            session_id: backend.session_id(),
+        };
+        let transaction = UploadStreamTransaction {
+            rtc_id: recording.rtc_id(),
+            start_timestamp: context.start_timestamp(),
+            backend_id: Some(backend.id().clone()),
+        };
+        // TODO: Send the error as an event to "app/${APP}/audiences/${AUD}" topic
+        context
+            .janus_clients()
+            .get_or_insert(&backend)
+            .error(AppErrorKind::BackendClientCreationFailed)?
+            .upload_stream(request, transaction)
+            .await
+            .error(AppErrorKind::BackendRequestFailed)?;
+
+        vacuumed_rooms.push(room);
+    }
+
+    Ok((vacuumed_rooms, uploads))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct OrphanedRoomCloseEvent {}
 
@@ -177,69 +242,126 @@ impl EventHandler for OrphanedRoomCloseHandler {
             )
             .await?;
 
-        let load_till = Utc::now()
-            - chrono::Duration::from_std(context.config().orphaned_room_timeout)
-                .expect("Orphaned room timeout misconfigured");
-
-        let mut closed_rooms = vec![];
         let mut notifications = vec![];
 
-        {
-            // to close this connection right after the loop
-            let mut conn = context.get_conn().await?;
+        for room in close_orphaned_rooms(context).await? {
+            notifications.push(helpers::build_notification(
+                "room.close",
+                &format!("rooms/{}/events", room.id()),
+                room.clone(),
+                evp.tracking(),
+                context.start_timestamp(),
+            ));
+            notifications.push(helpers::build_notification(
+                "room.close",
+                &format!("audiences/{}/events", room.audience()),
+                room,
+                evp.tracking(),
+                context.start_timestamp(),
+            ));
+        }
+
+        Ok(Box::new(stream::iter(notifications)))
+    }
+}
+
+/// Closes rooms which host has left for too long and returns the closed rooms.
+pub async fn close_orphaned_rooms<C: Context>(context: &mut C) -> StdResult<Vec<Room>, AppError> {
+    let load_till = Utc::now()
+        - chrono::Duration::from_std(context.config().orphaned_room_timeout)
+            .expect("Orphaned room timeout misconfigured");
+
+    let mut removed_orphans = vec![];
+    let mut closed_rooms = vec![];
+
+    // to close this connection right after the loop
+    let mut conn = context.get_conn().await?;
 
-            let timed_out = db::orphaned_room::get_timed_out(load_till, &mut conn).await?;
-
-            for (orphan, room) in timed_out {
-                match room {
-                    Some(room) if !room.is_closed() => {
-                        let r = db::room::UpdateQuery::new(room.id())
-                            .time(Some((room.time().0, Bound::Excluded(Utc::now()))))
-                            .timed_out()
-                            .execute(&mut conn)
-                            .await;
-
-                        match r {
-                            Ok(room) => {
-                                closed_rooms.push(room.id());
-                                notifications.push(helpers::build_notification(
-                                    "room.close",
-                                    &format!("rooms/{}/events", room.id()),
-                                    room.clone(),
-                                    evp.tracking(),
-                                    context.start_timestamp(),
-                                ));
-                                notifications.push(helpers::build_notification(
-                                    "room.close",
-                                    &format!("audiences/{}/events", room.audience()),
-                                    room,
-                                    evp.tracking(),
-                                    context.start_timestamp(),
-                                ));
-                            }
-                            Err(err) => {
-                                error!(?err, "Closing room failed");
-                            }
+    let timed_out = db::orphaned_room::get_timed_out(load_till, &mut conn).await?;
+
+    for (orphan, room) in timed_out {
+        match room {
+            Some(room) if !room.is_closed() => {
+                let r = db::room::UpdateQuery::new(room.id())
+                    .time(Some((room.time().0, Bound::Excluded(Utc::now()))))
+                    .timed_out()
+                    .execute(&mut conn)
+                    .await;
+
+                match r {
+                    Ok(room) => {
+                        let r =
+                            db::attendance::CloseQuery::new(db::attendance::LeaveReason::RoomClose)
+                                .room_id(room.id())
+                                .execute(&mut conn)
+                                .await;
+
+                        if let Err(err) = r {
+                            error!(?err, "Closing room attendance failed");
                         }
-                    }
 
-                    _ => {
-                        closed_rooms.push(orphan.id);
+                        removed_orphans.push(room.id());
+                        closed_rooms.push(room);
+                    }
+                    Err(err) => {
+                        error!(?err, "Closing room failed");
                     }
                 }
             }
 
-            if let Err(err) = db::orphaned_room::remove_rooms(&closed_rooms, &mut conn).await {
-                error!(?err, "Error removing rooms fron orphan table");
+            _ => {
+                removed_orphans.push(orphan.id);
             }
         }
+    }
 
-        Ok(Box::new(stream::iter(notifications)))
+    if let Err(err) = db::orphaned_room::remove_rooms(&removed_orphans, &mut conn).await {
+        error!(?err, "Error removing rooms fron orphan table");
     }
+
+    Ok(closed_rooms)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Builds `room.upload` event if every recording of the room is ready.
+pub async fn room_upload_event<C: Context>(
+    context: &C,
+    room: &Room,
+    conn: &mut sqlx::PgConnection,
+) -> StdResult<Option<RoomUploadEvent>, AppError> {
+    let rtcs_with_recs = db::rtc::ListWithRecordingQuery::new(room.id())
+        .execute(conn)
+        .await?;
+
+    // Ensure that all rtcs with a recording have ready recording.
+    let room_done = rtcs_with_recs
+        .iter()
+        .all(|(_rtc, maybe_recording)| match maybe_recording {
+            None => true,
+            Some(recording) => recording.status() == RecordingStatus::Ready,
+        });
+
+    if !room_done {
+        return Ok(None);
+    }
+
+    let recs_with_rtcs = rtcs_with_recs
+        .into_iter()
+        .filter_map(|(rtc, maybe_recording)| {
+            let recording = maybe_recording?;
+            matches!(recording.status(), RecordingStatus::Ready).then(|| (recording, rtc))
+        });
+
+    info!(
+        class_id = %room.classroom_id(),
+        room_id = %room.id(),
+        "sending room.upload event"
+    );
+
+    upload_event(context, room, recs_with_rtcs).map(Some)
+}
+
 pub fn upload_event<C: Context, I>(
     context: &C,
     room: &db::room::Object,
@@ -273,6 +395,7 @@ where
             status: recording.status().to_owned(),
             uri,
             created_by: rtc.created_by().to_owned(),
+            kind: rtc.kind(),
             mjr_dumps_uris: recording.mjr_dumps_uris().cloned(),
         };
 
@@ -327,9 +450,13 @@ fn record_name(recording: &Recording, room: &Room) -> String {
 
 mod agent_cleanup;
 mod agent_connection_cleanup;
+mod lobby_cleanup;
 
-pub use agent_cleanup::Handler as AgentCleanupHandler;
-pub use agent_connection_cleanup::Handler as AgentConnectionCleanupHandler;
+pub use agent_cleanup::{cleanup_agents, Handler as AgentCleanupHandler};
+pub use agent_connection_cleanup::{
+    cleanup_agent_connections, Handler as AgentConnectionCleanupHandler,
+};
+pub use lobby_cleanup::{cleanup_lobby, Handler as LobbyCleanupHandler};
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -414,12 +541,16 @@ mod test {
         use svc_agent::mqtt::ResponseStatus;
 
         use crate::{
-            backend::janus::client::{
-                events::EventResponse,
-                transactions::{Transaction, TransactionKind},
-                IncomingEvent,
+            backend::janus::{
+                balancer::Balancer,
+                client::{
+                    events::{EventResponse, EventResponsePluginData},
+                    transactions::{Transaction, TransactionKind},
+                    HandleId, IncomingEvent, SessionId,
+                },
+                client_pool,
             },
-            test_helpers::{db::TestDb, prelude::*, test_deps::LocalDeps},
+            test_helpers::{db::TestDb, parse_messages, prelude::*, test_deps::LocalDeps},
         };
 
         use super::super::*;
@@ -486,7 +617,7 @@ mod test {
                                 kind:
                                     Some(TransactionKind::UploadStream(UploadStreamTransaction {
                                         rtc_id,
-                                        start_timestamp: _start_timestamp,
+                                        ..
                                     })),
                                 ..
                             },
@@ -500,6 +631,191 @@ mod test {
             assert_eq!(recv_rtcs, rtcs);
         }
 
+        #[sqlx::test]
+        async fn vacuum_uploads_deleted_rtc(pool: sqlx::PgPool) {
+            let local_deps = LocalDeps::new();
+            let janus = local_deps.run_janus();
+            let db = TestDb::new(pool);
+
+            let (session_id, handle_id) = shared_helpers::init_janus(&janus.url).await;
+            let mut authz = TestAuthz::new();
+            authz.set_audience(SVC_AUDIENCE);
+
+            let mut conn = db.get_conn().await;
+            let backend =
+                shared_helpers::insert_janus_backend(&mut conn, &janus.url, session_id, handle_id)
+                    .await;
+
+            let room =
+                shared_helpers::insert_closed_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+            shared_helpers::insert_recording(&mut conn, &rtc).await;
+
+            // The RTC has been deleted while its recording is still waiting for the upload.
+            db::rtc::DeleteQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to delete rtc");
+
+            let agent = TestAgent::new("alpha", "cron", SVC_AUDIENCE);
+            authz.allow(agent.account_id(), vec!["system"], "update");
+
+            let mut context = TestContext::new(db, authz).await;
+            let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+            context.with_janus(tx);
+
+            let (rooms, uploads) = vacuum(&mut context).await.expect("System vacuum failed");
+            assert_eq!(rooms.len(), 1);
+            assert!(uploads.is_empty());
+
+            // Janus confirms the upload.
+            let confirmation = IncomingEvent::Event(EventResponse {
+                transaction: Transaction::new(TransactionKind::UploadStream(
+                    UploadStreamTransaction {
+                        rtc_id: rtc.id(),
+                        start_timestamp: Utc::now(),
+                        backend_id: Some(backend.id().to_owned()),
+                    },
+                )),
+                session_id,
+                opaque_id: None,
+                plugindata: EventResponsePluginData {
+                    data: Some(json!({
+                        "status": "200",
+                        "id": rtc.id(),
+                        "mjr_dumps_uris": ["dump"],
+                    })),
+                    plugin: "janus.plugin.conference".to_owned(),
+                },
+                jsep: None,
+            });
+
+            let messages = parse_messages(
+                crate::backend::janus::handle_event(&mut context, confirmation).await,
+            )
+            .await;
+
+            let (payload, evp, _) = find_event::<serde_json::Value>(messages.as_slice());
+            assert_eq!(evp.label(), "room.upload");
+            assert_eq!(payload["id"], room.id().to_string());
+            assert_eq!(payload["rtcs"][0]["id"], rtc.id().to_string());
+            assert_eq!(payload["rtcs"][0]["status"], "ready");
+
+            context.janus_clients().remove_client(&backend);
+        }
+
+        #[sqlx::test]
+        async fn vacuum_completes_recording_after_failover(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let failed_backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "test",
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+
+            let backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "test",
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+
+            let room =
+                shared_helpers::insert_closed_room_with_backend_id(&mut conn, failed_backend.id())
+                    .await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+            shared_helpers::insert_recording(&mut conn, &rtc).await;
+
+            // The room has moved to another backend in the middle of the recording
+            // and that part has been uploaded already.
+            db::recording::InsertPartQuery::new(rtc.id(), backend.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert recording part");
+
+            db::recording::UpdatePartQuery::new(rtc.id(), backend.id())
+                .status(RecordingStatus::Ready)
+                .mjr_dumps_uris(vec!["dump".to_owned()])
+                .execute(&mut conn)
+                .await
+                .expect("Failed to update recording part");
+
+            // The backend holding the other part has gone before uploading it.
+            client_pool::teardown_backend(
+                &failed_backend,
+                &Balancer::new(&Default::default(), None),
+                &mut conn,
+            )
+            .await
+            .expect("Failed to tear down backend");
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let (rooms, uploads) = vacuum(&mut context).await.expect("System vacuum failed");
+            assert!(rooms.is_empty());
+            assert_eq!(uploads.len(), 1);
+
+            let recording = db::recording::FindQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find recording")
+                .expect("Recording not found");
+
+            assert_eq!(recording.status(), RecordingStatus::Ready);
+            assert_eq!(recording.mjr_dumps_uris(), Some(&vec!["dump".to_owned()]));
+        }
+
+        #[sqlx::test]
+        async fn vacuum_completes_recording_of_deleted_rtc(pool: sqlx::PgPool) {
+            let db = TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "test",
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+
+            let room =
+                shared_helpers::insert_closed_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+            shared_helpers::insert_recording(&mut conn, &rtc).await;
+
+            db::rtc::DeleteQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to delete rtc");
+
+            // The only part has been uploaded but the recording is yet to be completed.
+            db::recording::UpdatePartQuery::new(rtc.id(), backend.id())
+                .status(RecordingStatus::Ready)
+                .mjr_dumps_uris(vec!["dump".to_owned()])
+                .execute(&mut conn)
+                .await
+                .expect("Failed to update recording part");
+
+            let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+            let (rooms, uploads) = vacuum(&mut context).await.expect("System vacuum failed");
+            assert!(rooms.is_empty());
+            assert_eq!(uploads.len(), 1);
+
+            let recording = db::recording::FindQuery::new(rtc.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to find recording")
+                .expect("Recording not found");
+
+            assert_eq!(recording.status(), RecordingStatus::Ready);
+        }
+
         #[sqlx::test]
         async fn vacuum_system_unauthorized(pool: sqlx::PgPool) {
             let db = TestDb::new(pool);
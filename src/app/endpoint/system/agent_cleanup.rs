@@ -10,6 +10,7 @@ use crate::{
     app::{
         context::Context,
         endpoint::prelude::*,
+        error::Error as AppError,
         service_utils::{RequestParams, Response},
     },
     authz::AuthzObject,
@@ -52,12 +53,21 @@ impl RequestHandler for Handler {
             None,
         );
 
-        let mut conn = context.get_conn().await?;
-        // TODO: move to constant but chrono doesnt support const fns
-        db::agent::CleanupQuery::new(Utc::now() - chrono::Duration::days(1))
-            .execute(&mut conn)
-            .await?;
+        cleanup_agents(context).await?;
 
         Ok(response)
     }
 }
+
+/// Removes agents which have been in the rooms for too long.
+pub async fn cleanup_agents<C: Context>(context: &mut C) -> Result<(), AppError> {
+    let mut conn = context.get_conn().await?;
+    // TODO: move to constant but chrono doesnt support const fns
+    db::agent::CleanupQuery::new(Utc::now() - chrono::Duration::days(1))
+        .execute(&mut conn)
+        .await?;
+
+    db::attendance::close_absent(db::attendance::LeaveReason::Disconnect, &mut conn).await?;
+
+    Ok(())
+}
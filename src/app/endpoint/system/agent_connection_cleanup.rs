@@ -10,6 +10,7 @@ use crate::{
     app::{
         context::Context,
         endpoint::prelude::*,
+        error::Error as AppError,
         service_utils::{RequestParams, Response},
     },
     authz::AuthzObject,
@@ -52,14 +53,19 @@ impl RequestHandler for Handler {
             None,
         );
 
-        let mut conn = context.get_conn().await?;
-        // TODO: move to constant but chrono doesnt support const fns
-        db::agent_connection::CleanupNotConnectedQuery::new(
-            Utc::now() - chrono::Duration::minutes(2),
-        )
-        .execute(&mut conn)
-        .await?;
+        cleanup_agent_connections(context).await?;
 
         Ok(response)
     }
 }
+
+/// Removes agent connections which have not been established in time.
+pub async fn cleanup_agent_connections<C: Context>(context: &mut C) -> Result<(), AppError> {
+    let mut conn = context.get_conn().await?;
+    // TODO: move to constant but chrono doesnt support const fns
+    db::agent_connection::CleanupNotConnectedQuery::new(Utc::now() - chrono::Duration::minutes(2))
+        .execute(&mut conn)
+        .await?;
+
+    Ok(())
+}
@@ -19,6 +19,7 @@ struct ErrorKindProperties {
 )]
 pub enum ErrorKind {
     AccessDenied,
+    AgentBanned,
     AgentNotConnected,
     AgentNotEnteredTheRoom,
     AuthorizationFailed,
@@ -33,6 +34,7 @@ pub enum ErrorKind {
     ConfigKeyMissing,
     DbConnAcquisitionFailed,
     DbQueryFailed,
+    HandNotRaised,
     InvalidHandleId,
     InvalidJsepFormat,
     InvalidRoomTime,
@@ -48,7 +50,9 @@ pub enum ErrorKind {
     PublishFailed,
     ResubscriptionFailed,
     RoomClosed,
+    RoomLocked,
     RoomNotFound,
+    RoomTemplateNotFound,
     RoomTimeChangingForbidden,
     RtcNotFound,
     MethodNotSupported,
@@ -98,6 +102,12 @@ impl From<ErrorKind> for ErrorKindProperties {
                 title: "Access denied",
                 is_notify_sentry: false,
             },
+            ErrorKind::AgentBanned => ErrorKindProperties {
+                status: ResponseStatus::FORBIDDEN,
+                kind: "agent_banned",
+                title: "Agent is banned in the room",
+                is_notify_sentry: false,
+            },
             ErrorKind::AgentNotConnected => ErrorKindProperties {
                 status: ResponseStatus::UNPROCESSABLE_ENTITY,
                 kind: "agent_not_connected",
@@ -176,6 +186,12 @@ impl From<ErrorKind> for ErrorKindProperties {
                 title: "Database query failed",
                 is_notify_sentry: true,
             },
+            ErrorKind::HandNotRaised => ErrorKindProperties {
+                status: ResponseStatus::NOT_FOUND,
+                kind: "hand_not_raised",
+                title: "Hand not raised",
+                is_notify_sentry: false,
+            },
             ErrorKind::InvalidHandleId => ErrorKindProperties {
                 status: ResponseStatus::BAD_REQUEST,
                 kind: "invalid_handle_id",
@@ -260,12 +276,24 @@ impl From<ErrorKind> for ErrorKindProperties {
                 title: "Room closed",
                 is_notify_sentry: false,
             },
+            ErrorKind::RoomLocked => ErrorKindProperties {
+                status: ResponseStatus::FORBIDDEN,
+                kind: "room_locked",
+                title: "Room is locked",
+                is_notify_sentry: false,
+            },
             ErrorKind::RoomNotFound => ErrorKindProperties {
                 status: ResponseStatus::NOT_FOUND,
                 kind: "room_not_found",
                 title: "Room not found",
                 is_notify_sentry: false,
             },
+            ErrorKind::RoomTemplateNotFound => ErrorKindProperties {
+                status: ResponseStatus::NOT_FOUND,
+                kind: "room_template_not_found",
+                title: "Room template not found",
+                is_notify_sentry: false,
+            },
             ErrorKind::RoomTimeChangingForbidden => ErrorKindProperties {
                 status: ResponseStatus::UNPROCESSABLE_ENTITY,
                 kind: "room_time_changing_forbidden",
@@ -21,10 +21,14 @@ pub async fn update(
         .execute(conn)
         .await?;
 
-    let agent_rtcs = rtcs
-        .iter()
-        .map(|rtc| (rtc.created_by(), rtc.id()))
-        .collect::<HashMap<_, _>>();
+    // An agent may own several RTCs, e.g. camera and screen.
+    let mut agent_rtcs = HashMap::<_, Vec<_>>::new();
+    for rtc in &rtcs {
+        agent_rtcs
+            .entry(rtc.created_by())
+            .or_default()
+            .push(rtc.id());
+    }
 
     // Use HashMap to avoid duplicated configs in cases
     // where a teacher can be in several groups at the same time
@@ -47,12 +51,12 @@ pub async fn update(
                 continue;
             }
 
-            let rtc_id = match agent_rtcs.get(agent2) {
+            let rtc_ids = match agent_rtcs.get(agent2) {
                 None => {
                     warn!(%agent2, "rtc_id not found");
                     continue;
                 }
-                Some(rtc_id) => rtc_id,
+                Some(rtc_ids) => rtc_ids,
             };
 
             // Checks the case where a teacher can be in several groups at the same time.
@@ -88,9 +92,11 @@ pub async fn update(
                 }
             }
 
-            configs
-                .entry((*rtc_id, agent1.to_owned()))
-                .or_insert(group1 == group2);
+            for rtc_id in rtc_ids {
+                configs
+                    .entry((*rtc_id, agent1.to_owned()))
+                    .or_insert(group1 == group2);
+            }
         }
     }
 
@@ -305,4 +311,75 @@ mod tests {
         let agent4_agent2_cfg = agent4_configs.get(agent2.agent_id()).unwrap();
         assert!(!agent4_agent2_cfg.receive_video());
     }
+
+    #[sqlx::test]
+    async fn distribution_with_screen_rtcs(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+
+        let agent1 = TestAgent::new("web", "user1", USR_AUDIENCE);
+        let agent2 = TestAgent::new("web", "user2", USR_AUDIENCE);
+        let agent3 = TestAgent::new("web", "user3", USR_AUDIENCE);
+
+        let mut conn = db.get_conn().await;
+
+        let room = factory::Room::new()
+            .audience(USR_AUDIENCE)
+            .time((Bound::Included(Utc::now()), Bound::Unbounded))
+            .rtc_sharing_policy(RtcSharingPolicy::Owned)
+            .insert(&mut conn)
+            .await;
+
+        let groups = Groups::new(vec![
+            GroupItem::new(
+                0,
+                vec![agent1.agent_id().clone(), agent3.agent_id().clone()],
+            ),
+            GroupItem::new(1, vec![agent2.agent_id().clone()]),
+        ]);
+
+        factory::GroupAgent::new(room.id(), groups.clone())
+            .upsert(&mut conn)
+            .await;
+
+        // The first agent shares both the camera and the screen.
+        let mut agent1_rtcs = vec![];
+        for kind in [db::rtc::Kind::Camera, db::rtc::Kind::Screen] {
+            let rtc = factory::Rtc::new(room.id())
+                .created_by(agent1.agent_id().to_owned())
+                .kind(kind)
+                .insert(&mut conn)
+                .await;
+
+            agent1_rtcs.push(rtc.id());
+        }
+
+        for agent in [&agent2, &agent3] {
+            factory::Rtc::new(room.id())
+                .created_by(agent.agent_id().to_owned())
+                .insert(&mut conn)
+                .await;
+        }
+
+        update(&mut conn, room.id(), groups)
+            .await
+            .expect("group reader config update failed");
+
+        let readers = [agent2.agent_id(), agent3.agent_id()];
+        let reader_configs = db::rtc_reader_config::ListWithRtcQuery::new(room.id(), &readers)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        // Both RTCs of the first agent are configured for each reader.
+        for reader in readers {
+            let configs = reader_configs
+                .iter()
+                .filter(|(cfg, _)| cfg.reader_id() == reader && agent1_rtcs.contains(&cfg.rtc_id()))
+                .map(|(cfg, _)| cfg.receive_video())
+                .collect::<Vec<_>>();
+
+            // Only the agent from the same group receives the video.
+            assert_eq!(configs, vec![reader == agent3.agent_id(); 2]);
+        }
+    }
 }
@@ -6,7 +6,7 @@ use std::{
 
 use axum::{
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
     Extension, Router,
 };
 use futures::future::BoxFuture;
@@ -42,11 +42,55 @@ pub fn build_router(
         )
         .metered_route("/rooms/:id/enter", post(endpoint::room::enter))
         .metered_route("/rooms/:id/close", post(endpoint::room::close))
-        .metered_route("/rooms", post(endpoint::room::create))
+        .metered_route("/rooms/:id/clone", post(endpoint::room::clone))
+        .metered_route("/rooms/:id/audit", get(endpoint::room_audit::list))
+        .metered_route("/rooms/:id/attendance", get(endpoint::attendance::list))
+        .metered_route(
+            "/rooms/:id/breakout",
+            post(endpoint::room::breakout).delete(endpoint::room::breakout_close),
+        )
+        .metered_route(
+            "/rooms/:id/agents/:agent_id/kick",
+            post(endpoint::room::kick),
+        )
+        .metered_route(
+            "/rooms/:id/hand",
+            post(endpoint::agent::raise_hand).delete(endpoint::agent::lower_hand),
+        )
+        .metered_route(
+            "/rooms/:id/agents/:agent_id/hand",
+            delete(endpoint::agent::lower_agent_hand),
+        )
+        .metered_route(
+            "/rooms/:id/agents/:agent_id/hand/accept",
+            post(endpoint::agent::accept_hand),
+        )
+        .metered_route(
+            "/rooms/:id/agents/:agent_id/role",
+            post(endpoint::agent::update_role),
+        )
+        .metered_route(
+            "/rooms/:id/agents/:agent_id/admit",
+            post(endpoint::room::admit),
+        )
+        .metered_route(
+            "/rooms/:id/agents/:agent_id/reject",
+            post(endpoint::room::reject),
+        )
+        .metered_route(
+            "/rooms",
+            get(endpoint::room::list).post(endpoint::room::create),
+        )
         .metered_route(
             "/rooms/:id",
             get(endpoint::room::read).patch(endpoint::room::update),
         )
+        .metered_route("/room_templates", post(endpoint::room_template::create))
+        .metered_route("/room_templates/:id", get(endpoint::room_template::read))
+        .metered_route(
+            "/room_templates/:id/rooms",
+            post(endpoint::room_template::create_room),
+        )
         .metered_route(
             "/rooms/:id/rtcs",
             get(endpoint::rtc::list).post(endpoint::rtc::create),
@@ -55,7 +99,10 @@ pub fn build_router(
             "/rooms/:id/groups",
             get(endpoint::group::list).post(endpoint::group::update),
         )
-        .metered_route("/rtcs/:id", get(endpoint::rtc::read))
+        .metered_route(
+            "/rtcs/:id",
+            get(endpoint::rtc::read).delete(endpoint::rtc::delete),
+        )
         .metered_route("/rtcs/:id/streams", post(endpoint::rtc::connect))
         .metered_route("/rooms/:id/streams", get(endpoint::rtc_stream::list))
         .metered_route("/streams/signal", post(endpoint::rtc_signal::create))
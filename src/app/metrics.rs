@@ -8,6 +8,7 @@ use prometheus::{
 use prometheus_static_metric::make_static_metric;
 
 use super::{endpoint, error::ErrorKind};
+use crate::backend::janus::metrics::EventMetrics as JanusEventMetrics;
 
 pub trait HistogramExt {
     fn observe_timestamp(&self, start: DateTime<Utc>);
@@ -31,25 +32,42 @@ fn duration_to_seconds(d: Duration) -> f64 {
 make_static_metric! {
     struct RequestDuration: Histogram {
         "method" => {
+            agent_accept_hand,
             agent_list,
+            agent_lower_hand,
+            agent_raise_hand,
+            agent_update_role,
             agent_reader_config_read,
             agent_reader_config_update,
             agent_writer_config_read,
             agent_writer_config_update,
+            attendance_list,
             group_list,
             group_update,
             message_broadcast,
             message_callback,
             message_unicast_request,
             message_unicast_response,
+            room_admit,
+            room_breakout,
+            room_breakout_close,
+            room_clone,
             room_close,
             room_create,
             room_enter,
+            room_kick,
             room_leave,
+            room_list,
             room_read,
+            room_reject,
+            room_audit_list,
+            room_template_create,
+            room_template_create_room,
+            room_template_read,
             room_update,
             rtc_connect,
             rtc_create,
+            rtc_delete,
             rtc_list,
             rtc_read,
             rtc_signal_create,
@@ -75,6 +93,9 @@ pub struct Metrics {
     pub authorization_time: Histogram,
     pub running_requests_total: IntGauge,
     pub outbox_errors: HashMap<String, IntCounter>,
+    pub scheduler_job_runs: IntCounterVec,
+    pub scheduler_job_duration: HistogramVec,
+    pub janus_events: JanusEventMetrics,
 }
 
 impl Metrics {
@@ -102,7 +123,17 @@ impl Metrics {
         registry.register(Box::new(total_requests.clone()))?;
         registry.register(Box::new(authorization_time.clone()))?;
         registry.register(Box::new(running_requests_total.clone()))?;
+        let scheduler_job_runs = IntCounterVec::new(
+            Opts::new("scheduler_job_runs", "Scheduler job runs"),
+            &["job", "status"],
+        )?;
+        let scheduler_job_duration = HistogramVec::new(
+            HistogramOpts::new("scheduler_job_duration", "Scheduler job duration"),
+            &["job"],
+        )?;
         registry.register(Box::new(outbox_stats.clone()))?;
+        registry.register(Box::new(scheduler_job_runs.clone()))?;
+        registry.register(Box::new(scheduler_job_duration.clone()))?;
         Ok(Self {
             request_duration: RequestDuration::from(&request_duration),
             total_requests,
@@ -130,6 +161,9 @@ impl Metrics {
                     ))
                 })
                 .collect::<anyhow::Result<_>>()?,
+            scheduler_job_runs,
+            scheduler_job_duration,
+            janus_events: JanusEventMetrics::new(registry)?,
         })
     }
 
@@ -158,6 +192,19 @@ impl Metrics {
         }
     }
 
+    pub fn observe_scheduler_job(&self, job: &str, start: DateTime<Utc>, is_ok: bool) {
+        let status = if is_ok { "ok" } else { "error" };
+        self.scheduler_job_runs
+            .with_label_values(&[job, status])
+            .inc();
+
+        if let Ok(elapsed) = (Utc::now() - start).to_std() {
+            self.scheduler_job_duration
+                .with_label_values(&[job])
+                .observe(duration_to_seconds(elapsed));
+        }
+    }
+
     /// This is helpful in MQTT handlers.
     pub fn observe_app_result(&self, result: &endpoint::RequestResult) {
         match result {
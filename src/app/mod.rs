@@ -5,7 +5,10 @@ use crate::{
         error::{Error as AppError, ErrorKind as AppErrorKind},
         http::build_router,
     },
-    backend::janus::{client_pool::Clients, online_handler::start_internal_api, JANUS_API_VERSION},
+    backend::janus::{
+        balancer::Balancer, client_pool::Clients, online_handler::start_internal_api,
+        JANUS_API_VERSION,
+    },
     client::{conference::ConferenceHttpClient, mqtt_gateway::MqttGatewayHttpClient},
     config::{self, Config},
 };
@@ -87,6 +90,7 @@ pub async fn run(
         config.waitlist_epoch_duration,
         own_ip_addr,
         Some(agent.clone()),
+        Balancer::new(&config.balancer, config.janus_group.clone()),
     );
 
     task::spawn({
@@ -162,8 +166,19 @@ pub async fn run(
             }),
     );
 
+    let scheduler_jobs = scheduler::run(
+        context.clone(),
+        agent.clone(),
+        config.scheduler.clone(),
+        graceful_rx.clone(),
+    );
+
     let ctx: Arc<dyn GlobalContext + Send + Sync> = Arc::new(context.clone());
-    let outbox_handler = outbox_handler::run(ctx, graceful_rx.clone())?;
+    let outbox_handler = outbox_handler::run(ctx.clone(), graceful_rx.clone())?;
+    let closing_soon_notifier = config
+        .closing_soon
+        .clone()
+        .map(|cfg| closing_soon_notifier::run(ctx, agent.clone(), cfg, graceful_rx.clone()));
 
     // Message handler
     let message_handler = Arc::new(MessageHandler::new(agent.clone(), context));
@@ -204,6 +219,18 @@ pub async fn run(
         error!(%err, "failed to await outbox handler completion");
     }
 
+    for job in scheduler_jobs {
+        if let Err(err) = job.await {
+            error!(%err, "failed to await scheduler job completion");
+        }
+    }
+
+    if let Some(closing_soon_notifier) = closing_soon_notifier {
+        if let Err(err) = closing_soon_notifier.await {
+            error!(%err, "failed to await closing soon notifier completion");
+        }
+    }
+
     tokio::time::sleep(Duration::from_secs(3)).await;
     info!(
         requests_left = metrics.running_requests_total.get(),
@@ -370,6 +397,7 @@ async fn start_metrics_collector(registry: Registry, bind_addr: SocketAddr) -> a
     Ok(())
 }
 
+mod closing_soon_notifier;
 mod cluster_ip;
 pub mod context;
 pub mod endpoint;
@@ -382,4 +410,5 @@ pub mod service_utils;
 
 mod group_reader_config;
 mod outbox_handler;
+mod scheduler;
 mod stage;
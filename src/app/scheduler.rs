@@ -0,0 +1,327 @@
+use crate::{
+    app::{
+        context::{AppContext, Context, GlobalContext},
+        endpoint::system,
+        error::Error as AppError,
+    },
+    config::SchedulerConfig,
+    db,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Connection, PgConnection};
+use std::time::Duration;
+use svc_agent::mqtt::{
+    Agent, IntoPublishableMessage, OutgoingEvent, OutgoingEventProperties,
+    ShortTermTimingProperties,
+};
+use tokio::{sync::watch, task::JoinHandle, time::MissedTickBehavior};
+use tracing::{error, info, warn};
+
+type Notifications = Vec<Box<dyn IntoPublishableMessage + Send + Sync + 'static>>;
+
+#[derive(Debug, Clone, Copy)]
+enum Job {
+    Vacuum,
+    AgentCleanup,
+    AgentConnectionCleanup,
+    LobbyCleanup,
+    CloseOrphanedRooms,
+    ClosingWarningCleanup,
+}
+
+impl Job {
+    fn name(self) -> &'static str {
+        match self {
+            Job::Vacuum => "vacuum",
+            Job::AgentCleanup => "agent_cleanup",
+            Job::AgentConnectionCleanup => "agent_connection_cleanup",
+            Job::LobbyCleanup => "lobby_cleanup",
+            Job::CloseOrphanedRooms => "close_orphaned_rooms",
+            Job::ClosingWarningCleanup => "closing_warning_cleanup",
+        }
+    }
+
+    fn lock_key(self) -> String {
+        format!("conference.scheduler.{}", self.name())
+    }
+}
+
+/// Runs system jobs on the configured intervals.
+///
+/// Every replica runs the scheduler but each job is executed only by the replica which
+/// holds the job's advisory lock. The lock is held for the whole replica lifetime
+/// so another replica takes the job over only when the leader goes away.
+pub fn run(
+    ctx: AppContext,
+    agent: Agent,
+    config: SchedulerConfig,
+    shutdown_rx: watch::Receiver<()>,
+) -> Vec<JoinHandle<()>> {
+    let jobs = vec![
+        (Job::Vacuum, config.vacuum_interval),
+        (Job::AgentCleanup, config.agent_cleanup_interval),
+        (
+            Job::AgentConnectionCleanup,
+            config.agent_connection_cleanup_interval,
+        ),
+        (Job::LobbyCleanup, config.lobby_cleanup_interval),
+        (
+            Job::CloseOrphanedRooms,
+            config.close_orphaned_rooms_interval,
+        ),
+        (
+            Job::ClosingWarningCleanup,
+            config.closing_warning_cleanup_interval,
+        ),
+    ];
+
+    jobs.into_iter()
+        .filter_map(|(job, interval)| interval.map(|interval| (job, interval)))
+        .map(|(job, interval)| {
+            info!(job = job.name(), ?interval, "Scheduler job started");
+
+            tokio::spawn(run_job_loop(
+                ctx.clone(),
+                agent.clone(),
+                job,
+                interval,
+                shutdown_rx.clone(),
+            ))
+        })
+        .collect()
+}
+
+async fn run_job_loop(
+    ctx: AppContext,
+    mut agent: Agent,
+    job: Job,
+    interval: Duration,
+    mut shutdown_rx: watch::Receiver<()>,
+) {
+    let mut check_interval = tokio::time::interval(interval);
+    check_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    let mut lock_conn = None;
+
+    loop {
+        tokio::select! {
+            _ = check_interval.tick() => {
+                match ensure_leadership(ctx.db(), job, lock_conn.take()).await {
+                    Ok(Some(conn)) => lock_conn = Some(conn),
+                    Ok(None) => continue,
+                    Err(err) => {
+                        error!(?err, job = job.name(), "Failed to take scheduler job lock");
+                        continue;
+                    }
+                }
+
+                let start = Utc::now();
+                let result = run_job(&mut ctx.start_message(), job).await;
+                ctx.metrics().observe_scheduler_job(job.name(), start, result.is_ok());
+
+                match result {
+                    Ok(notifications) => {
+                        for notification in notifications {
+                            if let Err(err) = agent.publish_publishable(notification) {
+                                error!(?err, job = job.name(), "Failed to publish scheduler job notification");
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        error!(?err, job = job.name(), "Scheduler job failed");
+                        err.notify_sentry();
+                    }
+                }
+            }
+            // Graceful shutdown
+            _ = shutdown_rx.changed() => {
+                if let Some(mut conn) = lock_conn.take() {
+                    if let Err(err) = db::advisory_lock::unlock(&job.lock_key(), &mut conn).await {
+                        warn!(?err, job = job.name(), "Failed to release scheduler job lock");
+                    }
+                }
+
+                warn!(job = job.name(), "Scheduler job completes its work");
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the connection holding the job lock if the replica is the job leader.
+///
+/// The connection is detached from the pool so the lock gets released when it's closed.
+async fn ensure_leadership(
+    db: &sqlx::PgPool,
+    job: Job,
+    lock_conn: Option<PgConnection>,
+) -> sqlx::Result<Option<PgConnection>> {
+    if let Some(mut conn) = lock_conn {
+        match conn.ping().await {
+            Ok(()) => return Ok(Some(conn)),
+            Err(err) => warn!(?err, job = job.name(), "Scheduler job lock connection lost"),
+        }
+    }
+
+    let mut conn = db.acquire().await?;
+
+    if db::advisory_lock::try_lock(&job.lock_key(), &mut conn).await? {
+        info!(job = job.name(), "Scheduler job leadership taken");
+        Ok(Some(conn.detach()))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn run_job<C: Context>(context: &mut C, job: Job) -> Result<Notifications, AppError> {
+    let mut notifications = Notifications::new();
+
+    match job {
+        Job::Vacuum => {
+            let (rooms, uploads) = system::vacuum(context).await?;
+
+            for room in rooms {
+                let path = format!("rooms/{}/events", room.id());
+                notifications.push(build_notification(
+                    "room.close",
+                    &path,
+                    room,
+                    context.start_timestamp(),
+                ));
+            }
+
+            for upload in uploads {
+                notifications.push(Box::new(upload));
+            }
+        }
+        Job::AgentCleanup => system::cleanup_agents(context).await?,
+        Job::AgentConnectionCleanup => system::cleanup_agent_connections(context).await?,
+        Job::LobbyCleanup => system::cleanup_lobby(context).await?,
+        Job::CloseOrphanedRooms => {
+            for room in system::close_orphaned_rooms(context).await? {
+                let path = format!("rooms/{}/events", room.id());
+                notifications.push(build_notification(
+                    "room.close",
+                    &path,
+                    room.clone(),
+                    context.start_timestamp(),
+                ));
+
+                let path = format!("audiences/{}/events", room.audience());
+                notifications.push(build_notification(
+                    "room.close",
+                    &path,
+                    room,
+                    context.start_timestamp(),
+                ));
+            }
+        }
+        Job::ClosingWarningCleanup => {
+            let mut conn = context.get_conn().await?;
+            db::room_closing_warning::DeleteExpiredQuery::new(Utc::now())
+                .execute(&mut conn)
+                .await?;
+        }
+    }
+
+    Ok(notifications)
+}
+
+fn build_notification(
+    label: &'static str,
+    path: &str,
+    payload: impl Serialize + Send + Sync + 'static,
+    start_timestamp: DateTime<Utc>,
+) -> Box<dyn IntoPublishableMessage + Send + Sync + 'static> {
+    let timing = ShortTermTimingProperties::until_now(start_timestamp);
+    let props = OutgoingEventProperties::new(label, timing);
+    Box::new(OutgoingEvent::broadcast(payload, props, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::Duration;
+
+    use crate::{
+        db::orphaned_room,
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn only_one_leader_per_job(pool: sqlx::PgPool) {
+        let leader = ensure_leadership(&pool, Job::Vacuum, None)
+            .await
+            .expect("Failed to take the lock")
+            .expect("Expected to become the leader");
+
+        // Another replica can't take the same job but can take another one.
+        let follower = ensure_leadership(&pool, Job::Vacuum, None)
+            .await
+            .expect("Failed to take the lock");
+
+        assert!(follower.is_none());
+
+        let other_job_leader = ensure_leadership(&pool, Job::AgentCleanup, None)
+            .await
+            .expect("Failed to take the lock");
+
+        assert!(other_job_leader.is_some());
+
+        // The leader keeps the leadership.
+        let leader = ensure_leadership(&pool, Job::Vacuum, Some(leader))
+            .await
+            .expect("Failed to take the lock");
+
+        assert!(leader.is_some());
+
+        // The job is taken over when the leader goes away.
+        leader
+            .expect("Expected to stay the leader")
+            .close()
+            .await
+            .expect("Failed to close connection");
+
+        let new_leader = ensure_leadership(&pool, Job::Vacuum, None)
+            .await
+            .expect("Failed to take the lock");
+
+        assert!(new_leader.is_some());
+    }
+
+    #[sqlx::test]
+    async fn close_orphaned_rooms_job(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let mut conn = db.get_conn().await;
+
+        let now = Utc::now();
+        let room = factory::Room::new()
+            .audience(USR_AUDIENCE)
+            .time((Bound::Included(now), Bound::Unbounded))
+            .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+            .insert(&mut conn)
+            .await;
+
+        orphaned_room::upsert_room(room.id(), now - Duration::hours(1), &mut conn)
+            .await
+            .expect("Failed to insert orphaned room");
+
+        let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+        let notifications = run_job(&mut context, Job::CloseOrphanedRooms)
+            .await
+            .expect("Scheduler job failed");
+
+        assert_eq!(notifications.len(), 2);
+
+        let timed_out = orphaned_room::get_timed_out(Utc::now(), &mut conn)
+            .await
+            .expect("Failed to get orphaned rooms");
+
+        assert!(timed_out.is_empty());
+    }
+}
@@ -0,0 +1,576 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use rand::Rng;
+use tracing::warn;
+
+use crate::{
+    config::{BalancerConfigs, BalancerStrategyConfig},
+    db::{
+        janus_backend::{self, LoadedBackend},
+        room,
+        rtc::{self, SharingPolicy as RtcSharingPolicy},
+    },
+};
+
+/// Chooses a backend for the room which isn't bound to any.
+#[async_trait]
+pub trait BalancerStrategy: Send + Sync {
+    /// Considers only backends in the `region` if it's given.
+    /// Returns `None` if there are no backends at all.
+    ///
+    /// `rtc_id` is the RTC the backend is chosen for, it's reported along with the warnings.
+    async fn select(
+        &self,
+        room: &room::Object,
+        rtc_id: Option<rtc::Id>,
+        group: Option<&str>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>>;
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Selects the most loaded backend that is capable to host the room's reservation.
+/// If there's no capable backend then selects the least loaded and sends a warning to Sentry.
+pub struct MostLoaded;
+
+#[async_trait]
+impl BalancerStrategy for MostLoaded {
+    async fn select(
+        &self,
+        room: &room::Object,
+        rtc_id: Option<rtc::Id>,
+        group: Option<&str>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>> {
+        if let Some(backend) = janus_backend::most_loaded(room.id(), group, region, conn).await? {
+            return Ok(Some(backend));
+        }
+
+        let maybe_backend = janus_backend::least_loaded(room.id(), group, region, conn).await?;
+
+        if let Some(ref backend) = maybe_backend {
+            use sentry::protocol::{value::Value, Event, Level};
+            let backend_id = backend.id().to_string();
+
+            warn!(%backend_id, "No capable backends to host the reserve; falling back to the least loaded backend");
+
+            let mut extra = std::collections::BTreeMap::new();
+            extra.insert(String::from("room_id"), Value::from(room.id().to_string()));
+
+            if let Some(rtc_id) = rtc_id {
+                extra.insert(String::from("rtc_id"), Value::from(rtc_id.to_string()));
+            }
+
+            extra.insert(String::from("backend_id"), Value::from(backend_id));
+
+            if let Some(reserve) = room.reserve() {
+                extra.insert(String::from("reserve"), Value::from(reserve));
+            }
+
+            sentry::capture_event(Event {
+                message: Some(String::from("No capable backends to host the reserve; falling back to the least loaded backend")),
+                level: Level::Warning,
+                extra,
+                ..Default::default()
+            });
+        }
+
+        Ok(maybe_backend)
+    }
+}
+
+/// Selects one of the least loaded backends. Suits rooms of a fixed size like minigroups.
+pub struct LeastLoaded;
+
+#[async_trait]
+impl BalancerStrategy for LeastLoaded {
+    async fn select(
+        &self,
+        room: &room::Object,
+        _rtc_id: Option<rtc::Id>,
+        group: Option<&str>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>> {
+        janus_backend::least_loaded(room.id(), group, region, conn).await
+    }
+}
+
+/// Selects a random backend capable to host the room's reservation with the probability
+/// proportional to its free capacity.
+pub struct WeightedByCapacity;
+
+#[async_trait]
+impl BalancerStrategy for WeightedByCapacity {
+    async fn select(
+        &self,
+        room: &room::Object,
+        _rtc_id: Option<rtc::Id>,
+        group: Option<&str>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>> {
+        let backends = janus_backend::list_with_load(group, region, conn).await?;
+        let capable = capable_backends(&backends, room);
+
+        if capable.is_empty() {
+            return Ok(freest(backends.iter()));
+        }
+
+        let total = capable.iter().map(|b| b.free_capacity()).sum::<f64>();
+
+        // Rooms with zero reserve fit fully loaded backends.
+        if total <= 0.0 {
+            return Ok(Some(capable[0].backend.clone()));
+        }
+
+        let mut point = rand::thread_rng().gen_range(0.0..total);
+
+        for backend in &capable {
+            point -= backend.free_capacity();
+
+            if point < 0.0 {
+                return Ok(Some(backend.backend.clone()));
+            }
+        }
+
+        Ok(capable.last().map(|b| b.backend.clone()))
+    }
+}
+
+/// Selects backends capable to host the room's reservation in turn.
+///
+/// The turn is kept by each replica on its own.
+#[derive(Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+#[async_trait]
+impl BalancerStrategy for RoundRobin {
+    async fn select(
+        &self,
+        room: &room::Object,
+        _rtc_id: Option<rtc::Id>,
+        group: Option<&str>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>> {
+        let backends = janus_backend::list_with_load(group, region, conn).await?;
+        let capable = capable_backends(&backends, room);
+
+        if capable.is_empty() {
+            return Ok(freest(backends.iter()));
+        }
+
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % capable.len();
+        Ok(Some(capable[idx].backend.clone()))
+    }
+}
+
+/// Packs rooms into the most loaded backend while its load stays under the threshold
+/// share of its capacity. Then spreads them selecting the backend with the most free capacity.
+pub struct PackUntilThreshold {
+    threshold: f64,
+}
+
+impl PackUntilThreshold {
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold }
+    }
+}
+
+#[async_trait]
+impl BalancerStrategy for PackUntilThreshold {
+    async fn select(
+        &self,
+        room: &room::Object,
+        _rtc_id: Option<rtc::Id>,
+        group: Option<&str>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>> {
+        let backends = janus_backend::list_with_load(group, region, conn).await?;
+        let capable = capable_backends(&backends, room);
+        let reserve = f64::from(room_reserve(room));
+
+        let packed = capable
+            .iter()
+            .filter(|b| b.load + reserve <= self.threshold * f64::from(b.capacity))
+            .max_by(|a, b| a.load.total_cmp(&b.load));
+
+        if let Some(backend) = packed {
+            return Ok(Some(backend.backend.clone()));
+        }
+
+        if capable.is_empty() {
+            Ok(freest(backends.iter()))
+        } else {
+            Ok(freest(capable.into_iter()))
+        }
+    }
+}
+
+// The same default reserve as `most_loaded` considers.
+fn room_reserve(room: &room::Object) -> i32 {
+    room.reserve().unwrap_or(1)
+}
+
+fn capable_backends<'a>(
+    backends: &'a [LoadedBackend],
+    room: &room::Object,
+) -> Vec<&'a LoadedBackend> {
+    let reserve = f64::from(room_reserve(room));
+
+    backends
+        .iter()
+        .filter(|b| b.free_capacity() >= reserve)
+        .collect()
+}
+
+fn freest<'a>(backends: impl Iterator<Item = &'a LoadedBackend>) -> Option<janus_backend::Object> {
+    backends
+        .max_by(|a, b| a.free_capacity().total_cmp(&b.free_capacity()))
+        .map(|b| b.backend.clone())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Holds the strategies configured for the rooms.
+#[derive(Clone)]
+pub struct Balancer {
+    group: Option<String>,
+    shared: Arc<dyn BalancerStrategy>,
+    owned: Arc<dyn BalancerStrategy>,
+    audiences: HashMap<String, Arc<dyn BalancerStrategy>>,
+}
+
+impl Balancer {
+    pub fn new(config: &BalancerConfigs, group: Option<String>) -> Self {
+        // Minigroups have a fixed size, that is why least loaded should work fine.
+        let default: Arc<dyn BalancerStrategy> = if group.as_deref() == Some("minigroup") {
+            Arc::new(LeastLoaded)
+        } else {
+            Arc::new(MostLoaded)
+        };
+
+        let build = |config: &Option<BalancerStrategyConfig>| match config {
+            Some(config) => build_strategy(config),
+            None => default.clone(),
+        };
+
+        Self {
+            shared: build(&config.shared),
+            owned: build(&config.owned),
+            audiences: config
+                .audiences
+                .iter()
+                .map(|(audience, config)| (audience.to_owned(), build_strategy(config)))
+                .collect(),
+            group,
+        }
+    }
+
+    /// Chooses a backend for the room with its strategy.
+    ///
+    /// The backend is chosen in the preferred region when any backend there is capable to host
+    /// the room's reservation. Otherwise it's chosen among all regions.
+    pub async fn select(
+        &self,
+        room: &room::Object,
+        rtc_id: Option<rtc::Id>,
+        region: Option<&str>,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<janus_backend::Object>> {
+        let strategy = self.strategy(room);
+        let group = self.group.as_deref();
+
+        if let Some(region) = region {
+            let backends = janus_backend::list_with_load(group, Some(region), conn).await?;
+
+            if !capable_backends(&backends, room).is_empty() {
+                if let Some(backend) = strategy
+                    .select(room, rtc_id, group, Some(region), conn)
+                    .await?
+                {
+                    return Ok(Some(backend));
+                }
+            }
+        }
+
+        strategy.select(room, rtc_id, group, None, conn).await
+    }
+
+    fn strategy(&self, room: &room::Object) -> &dyn BalancerStrategy {
+        if let Some(strategy) = self.audiences.get(room.audience()) {
+            return strategy.as_ref();
+        }
+
+        match room.rtc_sharing_policy() {
+            RtcSharingPolicy::Owned => self.owned.as_ref(),
+            RtcSharingPolicy::Shared | RtcSharingPolicy::None => self.shared.as_ref(),
+        }
+    }
+}
+
+fn build_strategy(config: &BalancerStrategyConfig) -> Arc<dyn BalancerStrategy> {
+    match config {
+        BalancerStrategyConfig::MostLoaded => Arc::new(MostLoaded),
+        BalancerStrategyConfig::LeastLoaded => Arc::new(LeastLoaded),
+        BalancerStrategyConfig::WeightedByCapacity => Arc::new(WeightedByCapacity),
+        BalancerStrategyConfig::RoundRobin => Arc::new(RoundRobin::default()),
+        BalancerStrategyConfig::PackUntilThreshold { threshold } => {
+            Arc::new(PackUntilThreshold::new(*threshold))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::{Duration, SubsecRound, Utc};
+    use svc_agent::AgentId;
+
+    use crate::{
+        backend::janus::client::{HandleId, SessionId},
+        config::{BalancerConfigs, BalancerStrategyConfig},
+        db::{janus_backend, room},
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    async fn insert_backend(
+        conn: &mut sqlx::PgConnection,
+        label: &str,
+        capacity: i32,
+    ) -> janus_backend::Object {
+        let agent = TestAgent::new("alpha", label, SVC_AUDIENCE);
+
+        factory::JanusBackend::new(
+            agent.agent_id().to_owned(),
+            HandleId::random(),
+            SessionId::random(),
+            "test".to_owned(),
+        )
+        .balancer_capacity(capacity)
+        .insert(conn)
+        .await
+    }
+
+    async fn insert_room(
+        conn: &mut sqlx::PgConnection,
+        reserve: i32,
+        backend_id: Option<&AgentId>,
+    ) -> room::Object {
+        let now = Utc::now().trunc_subsecs(0);
+
+        let mut room = factory::Room::new()
+            .audience(USR_AUDIENCE)
+            .time((
+                Bound::Included(now),
+                Bound::Excluded(now + Duration::hours(1)),
+            ))
+            .rtc_sharing_policy(RtcSharingPolicy::Shared)
+            .reserve(reserve);
+
+        if let Some(backend_id) = backend_id {
+            room = room.backend_id(backend_id);
+        }
+
+        room.insert(conn).await
+    }
+
+    #[sqlx::test]
+    async fn pack_until_threshold(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend1 = insert_backend(&mut conn, "janus1", 10).await;
+        let backend2 = insert_backend(&mut conn, "janus2", 10).await;
+        insert_room(&mut conn, 5, Some(backend1.id())).await;
+
+        let strategy = PackUntilThreshold::new(0.8);
+
+        // 5 + 2 fits 80% of backend1.
+        let room = insert_room(&mut conn, 2, None).await;
+        let backend = strategy
+            .select(&room, None, None, None, &mut conn)
+            .await
+            .expect("Failed to select backend")
+            .expect("No backend selected");
+
+        assert_eq!(backend.id(), backend1.id());
+
+        // 5 + 4 doesn't so the room goes to the freest backend.
+        let room = insert_room(&mut conn, 4, None).await;
+        let backend = strategy
+            .select(&room, None, None, None, &mut conn)
+            .await
+            .expect("Failed to select backend")
+            .expect("No backend selected");
+
+        assert_eq!(backend.id(), backend2.id());
+    }
+
+    #[sqlx::test]
+    async fn round_robin(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend1 = insert_backend(&mut conn, "janus1", 10).await;
+        let backend2 = insert_backend(&mut conn, "janus2", 10).await;
+        // Not capable to host the reserve.
+        let backend3 = insert_backend(&mut conn, "janus3", 1).await;
+
+        let strategy = RoundRobin::default();
+        let room = insert_room(&mut conn, 2, None).await;
+        let mut selected = vec![];
+
+        for _ in 0..4 {
+            let backend = strategy
+                .select(&room, None, None, None, &mut conn)
+                .await
+                .expect("Failed to select backend")
+                .expect("No backend selected");
+
+            selected.push(backend.id().to_owned());
+        }
+
+        assert!(!selected.contains(backend3.id()));
+        assert_ne!(selected[0], selected[1]);
+        assert_eq!(selected[0], selected[2]);
+        assert_eq!(selected[1], selected[3]);
+        assert!(selected.contains(backend1.id()));
+        assert!(selected.contains(backend2.id()));
+    }
+
+    #[sqlx::test]
+    async fn weighted_by_capacity_skips_incapable_backends(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend1 = insert_backend(&mut conn, "janus1", 10).await;
+        let backend2 = insert_backend(&mut conn, "janus2", 10).await;
+        insert_room(&mut conn, 9, Some(backend2.id())).await;
+
+        let room = insert_room(&mut conn, 2, None).await;
+
+        for _ in 0..10 {
+            let backend = WeightedByCapacity
+                .select(&room, None, None, None, &mut conn)
+                .await
+                .expect("Failed to select backend")
+                .expect("No backend selected");
+
+            assert_eq!(backend.id(), backend1.id());
+        }
+    }
+
+    #[sqlx::test]
+    async fn audience_strategy_takes_precedence(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend1 = insert_backend(&mut conn, "janus1", 10).await;
+        let backend2 = insert_backend(&mut conn, "janus2", 10).await;
+        insert_room(&mut conn, 5, Some(backend1.id())).await;
+
+        let room = insert_room(&mut conn, 2, None).await;
+
+        let config = BalancerConfigs {
+            shared: Some(BalancerStrategyConfig::MostLoaded),
+            owned: None,
+            audiences: Default::default(),
+        };
+
+        let backend = Balancer::new(&config, None)
+            .select(&room, None, None, &mut conn)
+            .await
+            .expect("Failed to select backend")
+            .expect("No backend selected");
+
+        assert_eq!(backend.id(), backend1.id());
+
+        let config = BalancerConfigs {
+            audiences: HashMap::from([(
+                USR_AUDIENCE.to_owned(),
+                BalancerStrategyConfig::PackUntilThreshold { threshold: 0.5 },
+            )]),
+            ..config
+        };
+
+        let backend = Balancer::new(&config, None)
+            .select(&room, None, None, &mut conn)
+            .await
+            .expect("Failed to select backend")
+            .expect("No backend selected");
+
+        assert_eq!(backend.id(), backend2.id());
+    }
+
+    #[sqlx::test]
+    async fn preferred_region(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend1 = insert_backend(&mut conn, "janus1", 10).await;
+        let agent = TestAgent::new("alpha", "janus2", SVC_AUDIENCE);
+
+        let backend2 = factory::JanusBackend::new(
+            agent.agent_id().to_owned(),
+            HandleId::random(),
+            SessionId::random(),
+            "test".to_owned(),
+        )
+        .balancer_capacity(5)
+        .region("eu")
+        .insert(&mut conn)
+        .await;
+
+        let balancer = Balancer::new(&BalancerConfigs::default(), None);
+
+        // Most loaded strategy would choose backend1 without the region.
+        insert_room(&mut conn, 3, Some(backend1.id())).await;
+        let room = insert_room(&mut conn, 2, None).await;
+
+        let backend = balancer
+            .select(&room, None, Some("eu"), &mut conn)
+            .await
+            .expect("Failed to select backend")
+            .expect("No backend selected");
+
+        assert_eq!(backend.id(), backend2.id());
+
+        // The region is out of capacity so the room goes elsewhere.
+        insert_room(&mut conn, 4, Some(backend2.id())).await;
+
+        let backend = balancer
+            .select(&room, None, Some("eu"), &mut conn)
+            .await
+            .expect("Failed to select backend")
+            .expect("No backend selected");
+
+        assert_eq!(backend.id(), backend1.id());
+    }
+
+    #[test]
+    fn pack_until_threshold_config() {
+        let parse = |threshold: serde_json::Value| {
+            serde_json::from_value::<BalancerStrategyConfig>(serde_json::json!({
+                "strategy": "pack_until_threshold",
+                "threshold": threshold,
+            }))
+        };
+
+        assert!(parse(serde_json::json!(0.8)).is_ok());
+        assert!(parse(serde_json::json!(-0.1)).is_err());
+        assert!(parse(serde_json::json!(1.5)).is_err());
+    }
+}
@@ -4,6 +4,7 @@ use self::{
     create_handle::{CreateHandleRequest, CreateHandleResponse, OpaqueId},
     create_session::CreateSessionResponse,
     create_stream::{CreateStreamRequest, CreateStreamTransaction},
+    detach_handle::DetachHandleRequest,
     events::{
         DetachedEvent, EventResponse, HangUpEvent, MediaEvent, SlowLinkEvent, TimeoutEvent,
         WebRtcUpEvent,
@@ -15,6 +16,7 @@ use self::{
     update_agent_reader_config::UpdateReaderConfigRequest,
     update_agent_writer_config::UpdateWriterConfigRequest,
     upload_stream::{UploadStreamRequest, UploadStreamTransaction},
+    websocket::WsTransport,
 };
 use anyhow::Context;
 
@@ -27,6 +29,7 @@ use derive_more::{Display, FromStr};
 pub mod create_handle;
 pub mod create_session;
 pub mod create_stream;
+pub mod detach_handle;
 pub mod events;
 pub mod read_stream;
 pub mod service_ping;
@@ -35,25 +38,42 @@ pub mod trickle;
 pub mod update_agent_reader_config;
 pub mod update_agent_writer_config;
 pub mod upload_stream;
+mod websocket;
 
 #[derive(Debug, Clone)]
 pub struct JanusClient {
-    http: Client,
-    janus_url: Url,
+    transport: ClientTransport,
+}
+
+#[derive(Debug, Clone)]
+enum ClientTransport {
+    Http { http: Client, janus_url: Url },
+    WebSocket(WsTransport),
 }
 
 impl JanusClient {
-    pub fn new(janus_url: &str) -> anyhow::Result<Self> {
-        Ok(Self {
-            http: Client::new(),
-            janus_url: janus_url.parse()?,
-        })
+    pub fn new(janus_url: &str, transport: Transport) -> anyhow::Result<Self> {
+        let transport = match transport {
+            Transport::Http => ClientTransport::Http {
+                http: Client::new(),
+                janus_url: janus_url.parse()?,
+            },
+            Transport::WebSocket => {
+                ClientTransport::WebSocket(WsTransport::new(janus_url.parse()?))
+            }
+        };
+
+        Ok(Self { transport })
     }
 
     pub async fn poll(&self, session_id: SessionId) -> anyhow::Result<PollResult> {
-        let response = self
-            .http
-            .get(format!("{}/{}?maxev=5", self.janus_url, session_id))
+        let (http, janus_url) = match &self.transport {
+            ClientTransport::Http { http, janus_url } => (http, janus_url),
+            ClientTransport::WebSocket(ws) => return ws.poll(session_id).await,
+        };
+
+        let response = http
+            .get(format!("{}/{}?maxev=5", janus_url, session_id))
             .send()
             .await?;
         if response.status() == StatusCode::NOT_FOUND {
@@ -120,6 +140,11 @@ impl JanusClient {
         Ok(response.data)
     }
 
+    pub async fn detach_handle(&self, request: DetachHandleRequest) -> anyhow::Result<()> {
+        let _response: SuccessResponse = self.send_request(detach_handle(request)).await?;
+        Ok(())
+    }
+
     pub async fn create_session(&self) -> anyhow::Result<CreateSessionResponse> {
         let response: JanusResponse<CreateSessionResponse> =
             self.send_request(create_session()).await?;
@@ -132,15 +157,21 @@ impl JanusClient {
     }
 
     async fn send_request<R: DeserializeOwned>(&self, body: impl Serialize) -> anyhow::Result<R> {
-        let body = serde_json::to_vec(&body)?;
-        let response = self
-            .http
-            .post(self.janus_url.clone())
-            .body(body)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = match &self.transport {
+            ClientTransport::Http { http, janus_url } => {
+                let body = serde_json::to_vec(&body)?;
+                http.post(janus_url.clone())
+                    .body(body)
+                    .send()
+                    .await?
+                    .text()
+                    .await?
+            }
+            ClientTransport::WebSocket(ws) => {
+                let body = serde_json::to_value(&body)?;
+                ws.send_request(body).await?.to_string()
+            }
+        };
         serde_json::from_str(&response).context(response)
     }
 }
@@ -240,6 +271,18 @@ impl SessionId {
     }
 }
 
+/// The way the service talks to a Janus instance.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, Hash, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "janus_transport", rename_all = "lowercase")]
+pub enum Transport {
+    /// Requests are sent with HTTP POST and events are long-polled.
+    #[default]
+    Http,
+    /// Requests and events go over a single WebSocket connection.
+    WebSocket,
+}
+
 /////////////////////////////////////////////////
 
 #[derive(Debug, Deserialize)]
@@ -261,7 +304,7 @@ impl IncomingEvent {
         match self {
             IncomingEvent::WebRtcUp(_) => "WebRtcUp",
             IncomingEvent::Media(_) => "Media",
-            IncomingEvent::Timeout(_) => "Media",
+            IncomingEvent::Timeout(_) => "Timeout",
             IncomingEvent::HangUp(_) => "HangUp",
             IncomingEvent::SlowLink(_) => "SlowLink",
             IncomingEvent::Detached(_) => "Detached",
@@ -318,6 +361,12 @@ enum Success {
     Success,
 }
 
+#[derive(Deserialize, Debug)]
+struct SuccessResponse {
+    #[allow(dead_code)]
+    janus: Success,
+}
+
 #[derive(Deserialize, Debug)]
 struct JanusResponse<T> {
     data: T,
@@ -354,6 +403,15 @@ fn create_handle(request: CreateHandleRequest) -> JanusRequest<CreateHandleReque
     }
 }
 
+fn detach_handle(request: DetachHandleRequest) -> JanusRequest<DetachHandleRequest> {
+    JanusRequest {
+        transaction: Transaction::only_id(),
+        janus: "detach",
+        plugin: None,
+        data: request,
+    }
+}
+
 fn trickle(request: TrickleRequest) -> JanusRequest<TrickleRequest> {
     JanusRequest {
         transaction: Transaction::only_id(),
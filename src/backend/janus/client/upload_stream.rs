@@ -1,6 +1,7 @@
 use crate::db;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use svc_agent::AgentId;
 
 use super::{HandleId, SessionId};
 
@@ -15,6 +16,10 @@ pub struct UploadStreamRequest {
 pub struct UploadStreamTransaction {
     pub rtc_id: db::rtc::Id,
     pub start_timestamp: DateTime<Utc>,
+    /// The backend uploading its part of the recording.
+    /// Missing in the transactions started before recordings were split into parts.
+    #[serde(default)]
+    pub backend_id: Option<AgentId>,
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,426 @@
+//! Janus WebSocket transport.
+//!
+//! Every request goes over a single connection with its transaction prefixed by a unique id,
+//! so the response could be routed back to the caller. Everything else the connection
+//! receives is an event of the session, which is returned by `poll`.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context};
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use reqwest::Url;
+use serde_json::{json, Value};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc, oneshot},
+};
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use tracing::warn;
+
+use super::{PollResult, SessionId};
+
+const PROTOCOL: &str = "janus-protocol";
+const KEEPALIVE_TRANSACTION: &str = "keepalive";
+// Janus times out sessions in 60 seconds (by default) without any activity.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(25);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_TIMEOUT: Duration = Duration::from_secs(30);
+// The same as `maxev` of HTTP long-polling.
+const MAX_EVENTS: usize = 5;
+// Janus error code for a missing session.
+const SESSION_NOT_FOUND: i64 = 458;
+
+#[derive(Debug, Clone)]
+pub struct WsTransport {
+    url: Url,
+    connection: Arc<tokio::sync::Mutex<Option<Connection>>>,
+}
+
+impl WsTransport {
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            connection: Arc::new(tokio::sync::Mutex::new(None)),
+        }
+    }
+
+    pub async fn send_request(&self, request: Value) -> anyhow::Result<Value> {
+        self.connection().await?.request(request).await
+    }
+
+    pub async fn poll(&self, session_id: SessionId) -> anyhow::Result<PollResult> {
+        let connection = self.connection().await?;
+
+        // Janus pushes events of a session only to the connection which has created or claimed it.
+        if connection.session() != Some(session_id) {
+            let response = connection
+                .request(json!({
+                    "janus": "claim",
+                    "session_id": session_id,
+                    "transaction": "claim",
+                }))
+                .await?;
+
+            match response.get("janus").and_then(Value::as_str) {
+                Some("success") => connection.set_session(session_id),
+                _ if error_code(&response) == Some(SESSION_NOT_FOUND) => {
+                    return Ok(PollResult::SessionNotFound)
+                }
+                _ => bail!("Failed to claim session: {}", response),
+            }
+        }
+
+        if connection.is_session_lost() {
+            return Ok(PollResult::SessionNotFound);
+        }
+
+        let mut events_rx = connection.events_rx.lock().await;
+
+        let event = match tokio::time::timeout(POLL_TIMEOUT, events_rx.recv()).await {
+            Ok(Some(event)) => event,
+            Ok(None) => bail!("Connection closed"),
+            Err(_) => return Ok(PollResult::Events(vec![])),
+        };
+
+        let mut events = vec![event];
+
+        while events.len() < MAX_EVENTS {
+            match events_rx.try_recv() {
+                Ok(event) => events.push(event),
+                Err(_) => break,
+            }
+        }
+
+        Ok(PollResult::Events(events))
+    }
+
+    async fn connection(&self) -> anyhow::Result<Connection> {
+        let mut guard = self.connection.lock().await;
+
+        if let Some(connection) = guard.as_ref().filter(|c| !c.is_closed()) {
+            return Ok(connection.clone());
+        }
+
+        let mut request = self.url.as_str().into_client_request()?;
+        request
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", HeaderValue::from_static(PROTOCOL));
+
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .context("Failed to connect to Janus")?;
+
+        let connection = Connection::spawn(socket);
+        *guard = Some(connection.clone());
+        Ok(connection)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// The socket is closed once every handle of the connection is dropped.
+#[derive(Debug, Clone)]
+struct Connection {
+    outgoing_tx: mpsc::UnboundedSender<Message>,
+    events_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Value>>>,
+    state: Arc<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+    next_id: AtomicU64,
+    session: Mutex<Option<SessionId>>,
+    is_session_lost: AtomicBool,
+}
+
+impl Connection {
+    fn spawn(socket: WebSocketStream<MaybeTlsStream<TcpStream>>) -> Self {
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let state = Arc::new(State::default());
+
+        tokio::spawn(run(socket, outgoing_rx, events_tx, state.clone()));
+
+        Self {
+            outgoing_tx,
+            events_rx: Arc::new(tokio::sync::Mutex::new(events_rx)),
+            state,
+        }
+    }
+
+    async fn request(&self, mut request: Value) -> anyhow::Result<Value> {
+        let id = self.state.next_id.fetch_add(1, Ordering::SeqCst);
+        let transaction = request
+            .get("transaction")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        request["transaction"] = Value::from(format!("{id}/{transaction}"));
+
+        let (response_tx, response_rx) = oneshot::channel();
+        self.state.pending.lock().insert(id, response_tx);
+
+        if self
+            .outgoing_tx
+            .send(Message::Text(request.to_string()))
+            .is_err()
+        {
+            self.state.pending.lock().remove(&id);
+            bail!("Connection closed");
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, response_rx).await {
+            Ok(response) => response.map_err(|_| anyhow!("Connection closed")),
+            Err(_) => {
+                self.state.pending.lock().remove(&id);
+                bail!("Request timed out")
+            }
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.outgoing_tx.is_closed()
+    }
+
+    fn session(&self) -> Option<SessionId> {
+        *self.state.session.lock()
+    }
+
+    fn set_session(&self, session_id: SessionId) {
+        *self.state.session.lock() = Some(session_id);
+        self.state.is_session_lost.store(false, Ordering::SeqCst);
+    }
+
+    fn is_session_lost(&self) -> bool {
+        self.state.is_session_lost.load(Ordering::SeqCst)
+    }
+}
+
+impl State {
+    fn dispatch(&self, text: &str, events_tx: &mpsc::UnboundedSender<Value>) {
+        let mut message = match serde_json::from_str::<Value>(text) {
+            Ok(message) => message,
+            Err(err) => {
+                warn!(?err, "Got malformed message from Janus");
+                return;
+            }
+        };
+
+        let kind = message
+            .get("janus")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+
+        let transaction = message
+            .get("transaction")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+
+        if transaction.as_deref() == Some(KEEPALIVE_TRANSACTION) {
+            if error_code(&message) == Some(SESSION_NOT_FOUND) {
+                self.is_session_lost.store(true, Ordering::SeqCst);
+            }
+
+            return;
+        }
+
+        // Restore the original transaction since plugin events are matched by it.
+        let id = transaction.as_deref().and_then(|t| {
+            let (id, original) = t.split_once('/')?;
+            let id = id.parse::<u64>().ok()?;
+            message["transaction"] = Value::from(original);
+            Some(id)
+        });
+
+        // Asynchronous plugin events carry the transaction of the request as well
+        // but they must be handled as the rest of events.
+        if kind.as_deref() != Some("event") {
+            if let Some(response_tx) = id.and_then(|id| self.pending.lock().remove(&id)) {
+                let _ = response_tx.send(message);
+                return;
+            }
+        }
+
+        match kind.as_deref() {
+            Some("ack") | Some("success") | Some("error") => {
+                // The caller has given up waiting.
+            }
+            _ => {
+                let _ = events_tx.send(message);
+            }
+        }
+    }
+}
+
+async fn run(
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    mut outgoing_rx: mpsc::UnboundedReceiver<Message>,
+    events_tx: mpsc::UnboundedSender<Value>,
+    state: Arc<State>,
+) {
+    let (mut write, mut read) = socket.split();
+    let mut keepalive_interval = tokio::time::interval(KEEPALIVE_INTERVAL);
+
+    loop {
+        tokio::select! {
+            message = outgoing_rx.recv() => {
+                let message = match message {
+                    Some(message) => message,
+                    None => break,
+                };
+
+                if let Err(err) = write.send(message).await {
+                    warn!(?err, "Failed to send message to Janus");
+                    break;
+                }
+            }
+            message = read.next() => match message {
+                Some(Ok(Message::Text(text))) => state.dispatch(&text, &events_tx),
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => {}
+                Some(Err(err)) => {
+                    warn!(?err, "Janus connection failed");
+                    break;
+                }
+            },
+            _ = keepalive_interval.tick() => {
+                let session_id = *state.session.lock();
+
+                if let Some(session_id) = session_id {
+                    let keepalive = json!({
+                        "janus": "keepalive",
+                        "session_id": session_id,
+                        "transaction": KEEPALIVE_TRANSACTION,
+                    });
+
+                    if let Err(err) = write.send(Message::Text(keepalive.to_string())).await {
+                        warn!(?err, "Failed to send keepalive to Janus");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    // Fail the requests waiting for responses. The next call reconnects.
+    state.pending.lock().clear();
+}
+
+fn error_code(message: &Value) -> Option<i64> {
+    message.get("error")?.get("code")?.as_i64()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    // Replies to requests like Janus does and returns the server's address.
+    async fn run_janus(claim_response: Value) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let addr = listener.local_addr().expect("Failed to get address");
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("Failed to accept");
+            let mut socket = tokio_tungstenite::accept_async(stream)
+                .await
+                .expect("Failed to handshake");
+
+            while let Some(Ok(Message::Text(text))) = socket.next().await {
+                let request: Value = serde_json::from_str(&text).expect("Invalid request");
+                let transaction = request["transaction"].clone();
+
+                let responses = match request["janus"].as_str() {
+                    Some("create") => vec![json!({
+                        "janus": "success",
+                        "transaction": transaction,
+                        "data": {"id": 1},
+                    })],
+                    Some("message") => vec![
+                        json!({"janus": "ack", "transaction": transaction}),
+                        json!({"janus": "event", "transaction": transaction, "sender": 2}),
+                    ],
+                    Some("claim") => {
+                        let mut response = claim_response.clone();
+                        response["transaction"] = transaction;
+                        vec![response]
+                    }
+                    _ => vec![],
+                };
+
+                for response in responses {
+                    socket
+                        .send(Message::Text(response.to_string()))
+                        .await
+                        .expect("Failed to send response");
+                }
+            }
+        });
+
+        format!("ws://{addr}").parse().expect("Invalid url")
+    }
+
+    fn session_id() -> SessionId {
+        serde_json::from_value(json!(1)).expect("Invalid session id")
+    }
+
+    #[tokio::test]
+    async fn request_and_poll_events() {
+        let url = run_janus(json!({"janus": "success"})).await;
+        let ws = WsTransport::new(url);
+
+        let response = ws
+            .send_request(json!({"janus": "create", "transaction": "create"}))
+            .await
+            .expect("Failed to create session");
+
+        assert_eq!(response["transaction"], "create");
+        assert_eq!(response["data"]["id"], 1);
+
+        let response = ws
+            .send_request(json!({"janus": "message", "transaction": "message"}))
+            .await
+            .expect("Failed to send message");
+
+        assert_eq!(response["janus"], "ack");
+
+        match ws.poll(session_id()).await.expect("Failed to poll") {
+            PollResult::Events(events) => {
+                assert_eq!(events.len(), 1);
+                assert_eq!(events[0]["janus"], "event");
+                assert_eq!(events[0]["transaction"], "message");
+            }
+            PollResult::SessionNotFound => panic!("Expected events"),
+        }
+    }
+
+    #[tokio::test]
+    async fn poll_missing_session() {
+        let url = run_janus(json!({
+            "janus": "error",
+            "error": {"code": SESSION_NOT_FOUND, "reason": "No such session"},
+        }))
+        .await;
+
+        let ws = WsTransport::new(url);
+
+        assert!(matches!(
+            ws.poll(session_id()).await.expect("Failed to poll"),
+            PollResult::SessionNotFound
+        ));
+    }
+}
@@ -1,5 +1,6 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use chrono::Utc;
+use serde::Serialize;
 use sqlx::Connection;
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -10,20 +11,26 @@ use std::{
     },
     time::Duration,
 };
-use svc_agent::mqtt::Agent;
+use svc_agent::mqtt::{
+    Agent, IntoPublishableMessage, OutgoingEvent, OutgoingEventProperties,
+    ShortTermTimingProperties,
+};
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 
 use crate::{
     app::{
         endpoint::{rtc_signal::CreateResponseData, rtc_stream},
         error::Error,
     },
-    db::{agent_connection, janus_backend, janus_rtc_stream},
+    db::{self, agent_connection, janus_backend, janus_rtc_stream},
 };
 
 use super::{
-    client::{IncomingEvent, JanusClient, PollResult, SessionId},
+    balancer::Balancer,
+    client::{
+        create_handle::CreateHandleRequest, IncomingEvent, JanusClient, PollResult, SessionId,
+    },
     waitlist::WaitList,
 };
 
@@ -36,6 +43,7 @@ pub struct Clients {
     stream_waitlist: WaitList<Result<CreateResponseData, Error>>,
     ip_addr: IpAddr,
     mqtt_agent: Option<Agent>,
+    balancer: Balancer,
 }
 
 impl Clients {
@@ -46,6 +54,7 @@ impl Clients {
         waitlist_epoch_duration: std::time::Duration,
         ip_addr: IpAddr,
         mqtt_agent: Option<Agent>,
+        balancer: Balancer,
     ) -> Self {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
@@ -55,6 +64,7 @@ impl Clients {
             stream_waitlist: WaitList::new(waitlist_epoch_duration),
             ip_addr,
             mqtt_agent,
+            balancer,
         }
     }
 
@@ -73,9 +83,31 @@ impl Clients {
                 backend.group()
             ));
         }
-        self.get_client(backend)
-            .map(Ok)
-            .unwrap_or_else(|| self.put_client(backend.clone()))
+        self.get_client(backend).map(Ok).unwrap_or_else(|| {
+            let client = JanusClient::new(backend.janus_url(), backend.transport())?;
+            Ok(self.put_client(backend.clone(), client))
+        })
+    }
+
+    /// The same as `get_or_insert` but takes the client the backend's session has been created with.
+    ///
+    /// Janus binds a session to the WebSocket connection it was created over, so the connection
+    /// must be kept.
+    pub fn get_or_insert_with(
+        &self,
+        backend: &janus_backend::Object,
+        client: JanusClient,
+    ) -> anyhow::Result<JanusClient> {
+        if backend.group() != self.group.as_deref() {
+            return Err(anyhow!(
+                "Wrong backend_group. Expected: {:?}. Got: {:?}",
+                self.group,
+                backend.group()
+            ));
+        }
+        Ok(self
+            .get_client(backend)
+            .unwrap_or_else(|| self.put_client(backend.clone(), client)))
     }
 
     fn get_client(&self, backend: &janus_backend::Object) -> Option<JanusClient> {
@@ -83,14 +115,13 @@ impl Clients {
         Some(guard.get(backend)?.client.clone())
     }
 
-    fn put_client(&self, backend: janus_backend::Object) -> anyhow::Result<JanusClient> {
+    fn put_client(&self, backend: janus_backend::Object, client: JanusClient) -> JanusClient {
         let mut guard = self.clients.write().expect("Must not panic");
         match guard.entry(backend.clone()) {
-            Entry::Occupied(o) => Ok(o.get().client.clone()),
+            Entry::Occupied(o) => o.get().client.clone(),
             Entry::Vacant(v) => {
                 let this = self.clone();
                 let mqtt_agent = self.mqtt_agent.clone();
-                let client = JanusClient::new(backend.janus_url())?;
                 let session_id = backend.session_id();
                 let is_cancelled = Arc::new(AtomicBool::new(false));
                 v.insert(ClientHandle {
@@ -103,23 +134,33 @@ impl Clients {
                     let db = self.db.clone();
                     async move {
                         let sink = this.events_sink.clone();
-                        let _guard = PollerGuard {
-                            clients: &this,
-                            backend: &backend,
+                        let recreated_backend = {
+                            let _guard = PollerGuard {
+                                clients: &this,
+                                backend: &backend,
+                            };
+                            start_polling(
+                                client.clone(),
+                                session_id,
+                                sink,
+                                db,
+                                &is_cancelled,
+                                &backend,
+                                mqtt_agent,
+                                &this.balancer,
+                            )
+                            .await
                         };
-                        start_polling(
-                            client,
-                            session_id,
-                            sink,
-                            db,
-                            &is_cancelled,
-                            &backend,
-                            mqtt_agent,
-                        )
-                        .await;
+
+                        // Polling of the new session starts over.
+                        if let Some(backend) = recreated_backend {
+                            if let Err(err) = this.get_or_insert_with(&backend, client) {
+                                error!(?backend, ?err, "Error polling recreated session");
+                            }
+                        }
                     }
                 });
-                Ok(client)
+                client
             }
         }
     }
@@ -145,6 +186,10 @@ impl Clients {
     pub fn own_ip_addr(&self) -> IpAddr {
         self.ip_addr
     }
+
+    pub fn balancer(&self) -> &Balancer {
+        &self.balancer
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -167,6 +212,7 @@ impl<'a> Drop for PollerGuard<'a> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_polling(
     janus_client: JanusClient,
     session_id: SessionId,
@@ -175,11 +221,12 @@ async fn start_polling(
     is_cancelled: &AtomicBool,
     janus_backend: &janus_backend::Object,
     mqtt_agent: Option<Agent>,
-) {
+    balancer: &Balancer,
+) -> Option<janus_backend::Object> {
     let mut fail_retries_count = 5;
     loop {
         if fail_retries_count == 0 {
-            if let Err(err) = remove_backend(janus_backend, db, mqtt_agent).await {
+            if let Err(err) = remove_backend(janus_backend, db, mqtt_agent, balancer).await {
                 error!(backend = ?janus_backend, ?err, "Error removing backend");
             }
             break;
@@ -191,7 +238,23 @@ async fn start_polling(
         match poll_result {
             Ok(PollResult::SessionNotFound) => {
                 warn!(?janus_backend, "Session not found");
-                if let Err(err) = remove_backend(janus_backend, db, mqtt_agent).await {
+                // Janus may have just restarted so try to register with it again first.
+                match recreate_session(&janus_client, janus_backend, &db, mqtt_agent.clone()).await
+                {
+                    Ok(Some(backend)) => {
+                        info!(?backend, "Session recreated");
+                        return Some(backend);
+                    }
+                    // The backend has registered again and its new session is polled already.
+                    Ok(None) => {
+                        info!(?janus_backend, "Session has already been replaced");
+                        break;
+                    }
+                    Err(err) => {
+                        error!(backend = ?janus_backend, ?err, "Error recreating session");
+                    }
+                }
+                if let Err(err) = remove_backend(janus_backend, db, mqtt_agent, balancer).await {
                     error!(backend = ?janus_backend, ?err, "Error removing backend");
                 }
                 break;
@@ -222,63 +285,521 @@ async fn start_polling(
             }
         }
     }
+
+    None
 }
 
-async fn remove_backend(
+#[derive(Debug, Serialize)]
+struct RoomBackendRestartedEvent {
+    id: db::room::Id,
+}
+
+/// Creates a new session and handle for the backend and stores them in place of the lost ones.
+///
+/// Agents are disconnected and streams are stopped since their handles have gone with
+/// the previous session. Open rooms on the backend get `room.backend_restarted` event
+/// so clients could signal again.
+///
+/// Returns `None` without touching anything if the lost session has already been replaced.
+async fn recreate_session(
+    janus_client: &JanusClient,
     backend: &janus_backend::Object,
-    db: sqlx::PgPool,
+    db: &sqlx::PgPool,
     agent: Option<Agent>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<janus_backend::Object>> {
+    let session = janus_client
+        .create_session()
+        .await
+        .context("CreateSession")?;
+    let handle = janus_client
+        .create_handle(CreateHandleRequest {
+            session_id: session.id,
+            opaque_id: None,
+        })
+        .await
+        .context("Create first handle")?;
+
     let mut conn = db.acquire().await?;
-    let result = conn
+    let replaced = conn
         .transaction(|conn| {
             let id = backend.id().clone();
-            let session_id = backend.session_id();
-            let handle_id = backend.handle_id();
+            let old_session_id = backend.session_id();
             Box::pin(async move {
-                janus_backend::DeleteQuery::new(&id, session_id, handle_id)
-                    .execute(conn)
-                    .await?;
+                let new_backend = janus_backend::ReplaceSessionQuery::new(
+                    &id,
+                    old_session_id,
+                    session.id,
+                    handle.id,
+                )
+                .execute(conn)
+                .await?;
+
+                let new_backend = match new_backend {
+                    Some(new_backend) => new_backend,
+                    None => return Ok::<_, sqlx::Error>(None),
+                };
 
-                // since backend can be up again we should disconnect everyone and stop
-                // all running streams regardless of whether backend was deleted or not
                 agent_connection::BulkDisconnectByBackendQuery::new(&id)
                     .execute(conn)
                     .await?;
                 let stopped_streams =
                     janus_rtc_stream::stop_running_streams_by_backend(&id, conn).await?;
+                let rooms = db::room::list_open_by_backend(&id, conn).await?;
 
-                Ok::<_, sqlx::Error>(stopped_streams)
+                Ok(Some((new_backend, stopped_streams, rooms)))
             })
         })
         .await?;
 
-    match (result, agent) {
-        (stopped_rtcs_streams, Some(mut agent)) => {
-            let now = Utc::now();
-            for stream in stopped_rtcs_streams {
-                let end_time = match stream
-                    .time
-                    .as_ref()
-                    .map(|t| crate::db::room::Time::from(t.clone()))
-                {
-                    Some((_start, end)) => match end {
-                        std::ops::Bound::Included(t) | std::ops::Bound::Excluded(t) => t,
-                        std::ops::Bound::Unbounded => continue,
-                    },
-                    None => now,
-                };
-                let update_evt =
-                    rtc_stream::update_event(stream.room_id, stream.janus_rtc_stream(), end_time);
-                if let Err(err) = agent.publish(update_evt) {
-                    error!(backend = ?backend, ?err, "Failed to publish rtc_stream.update evt");
+    let (new_backend, stopped_streams, rooms) = match replaced {
+        Some(replaced) => replaced,
+        None => return Ok(None),
+    };
+
+    if let Some(mut agent) = agent {
+        for update_evt in stopped_streams_events(stopped_streams) {
+            if let Err(err) = agent.publish(update_evt) {
+                error!(?backend, ?err, "Failed to publish rtc_stream.update evt");
+            }
+        }
+
+        let start_timestamp = Utc::now();
+
+        for room in rooms {
+            let payload = RoomBackendRestartedEvent { id: room.id() };
+            let timing = ShortTermTimingProperties::until_now(start_timestamp);
+            let props = OutgoingEventProperties::new("room.backend_restarted", timing);
+            let path = format!("rooms/{}/events", room.id());
+            let event = OutgoingEvent::broadcast(payload, props, &path);
+
+            if let Err(err) = agent.publish(event) {
+                error!(
+                    ?backend,
+                    ?err,
+                    "Failed to publish room.backend_restarted evt"
+                );
+            }
+        }
+    }
+
+    Ok(Some(new_backend))
+}
+
+async fn remove_backend(
+    backend: &janus_backend::Object,
+    db: sqlx::PgPool,
+    agent: Option<Agent>,
+    balancer: &Balancer,
+) -> anyhow::Result<()> {
+    let mut conn = db.acquire().await?;
+    let teardown = teardown_backend(backend, balancer, &mut conn).await?;
+
+    match agent {
+        Some(mut agent) => {
+            for evt in teardown.into_events() {
+                if let Err(err) = agent.publish_publishable(evt) {
+                    error!(backend = ?backend, ?err, "Failed to publish teardown evt");
                 }
             }
         }
-        (_streams, None) => {
+        None => {
             // not sending events since no agent provided
         }
     }
 
     Ok(())
 }
+
+/// The outcome of `teardown_backend` to be announced.
+pub struct Teardown {
+    stopped_streams: Vec<janus_rtc_stream::StreamWithRoomId>,
+    moved_rooms: Vec<db::room::Object>,
+}
+
+impl Teardown {
+    /// Builds `rtc_stream.update` events for the stopped streams and `room.backend_changed`
+    /// events for the moved rooms.
+    pub fn into_events(self) -> Vec<Box<dyn IntoPublishableMessage + Send + Sync + 'static>> {
+        let start_timestamp = Utc::now();
+
+        let streams_events = stopped_streams_events(self.stopped_streams)
+            .into_iter()
+            .map(|evt| Box::new(evt) as Box<dyn IntoPublishableMessage + Send + Sync + 'static>);
+
+        let rooms_events = self.moved_rooms.into_iter().map(|room| {
+            let payload = RoomBackendChangedEvent { id: room.id() };
+            let timing = ShortTermTimingProperties::until_now(start_timestamp);
+            let props = OutgoingEventProperties::new("room.backend_changed", timing);
+            let path = format!("rooms/{}/events", room.id());
+            let evt = OutgoingEvent::broadcast(payload, props, &path);
+            Box::new(evt) as Box<dyn IntoPublishableMessage + Send + Sync + 'static>
+        });
+
+        streams_events.chain(rooms_events).collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RoomBackendChangedEvent {
+    id: db::room::Id,
+}
+
+/// Deletes the backend, disconnects its agents and stops its running streams.
+/// Open rooms bound to the backend are moved to other ones.
+pub async fn teardown_backend(
+    backend: &janus_backend::Object,
+    balancer: &Balancer,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Teardown> {
+    conn.transaction(|conn| {
+        let backend = backend.clone();
+        let balancer = balancer.clone();
+        Box::pin(async move {
+            let id = backend.id();
+            // since backend can be up again we should disconnect everyone and stop
+            // all running streams regardless of whether backend was deleted or not.
+            // Streams go first as they're removed along with the backend.
+            agent_connection::BulkDisconnectByBackendQuery::new(id)
+                .execute(conn)
+                .await?;
+            let stopped_streams =
+                janus_rtc_stream::stop_running_streams_by_backend(id, conn).await?;
+            // Nobody is going to upload the recording parts stored on the backend so vacuum
+            // completes the recordings without them.
+            db::recording::MarkPartsMissingQuery::new(id)
+                .execute(conn)
+                .await?;
+
+            janus_backend::DeleteQuery::new(id, backend.session_id(), backend.handle_id())
+                .execute(conn)
+                .await?;
+
+            let moved_rooms = failover_rooms(&backend, &balancer, conn).await?;
+
+            Ok::<_, sqlx::Error>(Teardown {
+                stopped_streams,
+                moved_rooms,
+            })
+        })
+    })
+    .await
+}
+
+/// Binds open rooms of the deleted backend to other backends chosen by the balancer.
+/// The room's preferred region is kept if it has one, otherwise the backend's region is preferred.
+///
+/// A room is left without a backend if there's none available so the backend would be
+/// chosen on the next writer's connect.
+async fn failover_rooms(
+    backend: &janus_backend::Object,
+    balancer: &Balancer,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Vec<db::room::Object>> {
+    let rooms = db::room::list_open_by_backend(backend.id(), conn).await?;
+    let mut moved_rooms = Vec::with_capacity(rooms.len());
+
+    for room in rooms {
+        let region = room.preferred_region().or_else(|| backend.region());
+        let new_backend = balancer.select(&room, None, region, conn).await?;
+        let new_backend_id = new_backend.as_ref().map(|b| b.id());
+
+        match new_backend_id {
+            Some(new_backend_id) => {
+                info!(room_id = %room.id(), %new_backend_id, "Moving room to another backend")
+            }
+            None => warn!(room_id = %room.id(), "No backend available to move room to"),
+        }
+
+        let room = db::room::set_backend_id(room.id(), new_backend_id, conn).await?;
+        moved_rooms.push(room);
+    }
+
+    Ok(moved_rooms)
+}
+
+/// Builds `rtc_stream.update` events for the stopped streams.
+fn stopped_streams_events(
+    streams: Vec<janus_rtc_stream::StreamWithRoomId>,
+) -> Vec<rtc_stream::ObjectUpdateEvent> {
+    let now = Utc::now();
+    let mut events = Vec::with_capacity(streams.len());
+
+    for stream in streams {
+        let end_time = match stream
+            .time
+            .as_ref()
+            .map(|t| crate::db::room::Time::from(t.clone()))
+        {
+            Some((_start, end)) => match end {
+                std::ops::Bound::Included(t) | std::ops::Bound::Excluded(t) => t,
+                std::ops::Bound::Unbounded => continue,
+            },
+            None => now,
+        };
+
+        events.push(rtc_stream::update_event(
+            stream.room_id,
+            stream.janus_rtc_stream(),
+            end_time,
+        ));
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::{Method::POST, MockServer};
+    use serde_json::json;
+
+    use crate::{
+        backend::janus::client::{HandleId, Transport},
+        db::room::FindQueryable,
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn recreate_session_after_janus_restart(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let janus = MockServer::start();
+
+        let _create_session_mock = janus.mock(|when, then| {
+            when.method(POST).body_contains(r#""janus":"create""#);
+            then.status(200)
+                .json_body(json!({"janus": "success", "data": {"id": 123}}));
+        });
+
+        let _create_handle_mock = janus.mock(|when, then| {
+            when.method(POST).body_contains(r#""janus":"attach""#);
+            then.status(200)
+                .json_body(json!({"janus": "success", "data": {"id": 456}}));
+        });
+
+        let (backend, rtc_stream) = {
+            let mut conn = db.get_conn().await;
+
+            let backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                &janus.base_url(),
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+
+            let room = shared_helpers::insert_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+
+            let rtc_stream = factory::JanusRtcStream::new(USR_AUDIENCE)
+                .backend(&backend)
+                .rtc(&rtc)
+                .insert(&mut conn)
+                .await;
+
+            janus_rtc_stream::start(rtc_stream.id(), &mut conn)
+                .await
+                .expect("Failed to start rtc stream");
+
+            (backend, rtc_stream)
+        };
+
+        let janus_client =
+            JanusClient::new(&janus.base_url(), Transport::Http).expect("Failed to create client");
+
+        let new_backend = recreate_session(&janus_client, &backend, &db.pool, None)
+            .await
+            .expect("Failed to recreate session")
+            .expect("Session not replaced");
+
+        assert_eq!(new_backend.id(), backend.id());
+        assert_eq!(new_backend.session_id().to_string(), "123");
+        assert_eq!(new_backend.handle_id().to_string(), "456");
+
+        // The backend is kept along with its streams, which are stopped.
+        let mut conn = db.get_conn().await;
+
+        let rtc_stream = janus_rtc_stream::get_rtc_stream(&mut conn, rtc_stream.id())
+            .await
+            .expect("Failed to get rtc stream")
+            .expect("Rtc stream not found");
+
+        let (_start, end) = rtc_stream.time().expect("Missing time");
+        assert!(matches!(end, std::ops::Bound::Excluded(_)));
+    }
+
+    #[sqlx::test]
+    async fn recreate_session_keeps_replaced_session(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let janus = MockServer::start();
+
+        let _create_session_mock = janus.mock(|when, then| {
+            when.method(POST).body_contains(r#""janus":"create""#);
+            then.status(200)
+                .json_body(json!({"janus": "success", "data": {"id": 123}}));
+        });
+
+        let _create_handle_mock = janus.mock(|when, then| {
+            when.method(POST).body_contains(r#""janus":"attach""#);
+            then.status(200)
+                .json_body(json!({"janus": "success", "data": {"id": 456}}));
+        });
+
+        let (backend, registered_backend, rtc_stream) = {
+            let mut conn = db.get_conn().await;
+
+            let backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                &janus.base_url(),
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+
+            let room = shared_helpers::insert_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+
+            let rtc_stream = factory::JanusRtcStream::new(USR_AUDIENCE)
+                .backend(&backend)
+                .rtc(&rtc)
+                .insert(&mut conn)
+                .await;
+
+            janus_rtc_stream::start(rtc_stream.id(), &mut conn)
+                .await
+                .expect("Failed to start rtc stream");
+
+            // The restarted Janus has registered again before the old session was found lost.
+            let registered_backend = janus_backend::UpsertQuery::new(
+                backend.id(),
+                HandleId::random(),
+                SessionId::random(),
+                backend.janus_url(),
+            )
+            .execute(&mut conn)
+            .await
+            .expect("Failed to register backend");
+
+            (backend, registered_backend, rtc_stream)
+        };
+
+        let janus_client =
+            JanusClient::new(&janus.base_url(), Transport::Http).expect("Failed to create client");
+
+        let new_backend = recreate_session(&janus_client, &backend, &db.pool, None)
+            .await
+            .expect("Failed to recreate session");
+
+        assert!(new_backend.is_none());
+
+        let mut conn = db.get_conn().await;
+
+        let stored_backend = janus_backend::FindQuery::new(backend.id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to find backend")
+            .expect("Backend not found");
+
+        assert_eq!(stored_backend.session_id(), registered_backend.session_id());
+
+        let rtc_stream = janus_rtc_stream::get_rtc_stream(&mut conn, rtc_stream.id())
+            .await
+            .expect("Failed to get rtc stream")
+            .expect("Rtc stream not found");
+
+        let (_start, end) = rtc_stream.time().expect("Missing time");
+        assert_eq!(end, std::ops::Bound::Unbounded);
+    }
+
+    #[sqlx::test]
+    async fn teardown_moves_rooms_to_another_backend(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let mut conn = db.get_conn().await;
+
+        let failed_backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let room =
+            shared_helpers::insert_room_with_backend_id(&mut conn, failed_backend.id()).await;
+        let closed_room =
+            shared_helpers::insert_closed_room_with_backend_id(&mut conn, failed_backend.id())
+                .await;
+
+        let teardown = teardown_backend(
+            &failed_backend,
+            &Balancer::new(&Default::default(), None),
+            &mut conn,
+        )
+        .await
+        .expect("Failed to tear down backend");
+
+        assert_eq!(teardown.moved_rooms.len(), 1);
+        assert_eq!(teardown.moved_rooms[0].id(), room.id());
+        assert_eq!(teardown.moved_rooms[0].backend_id(), Some(backend.id()));
+        assert_eq!(teardown.into_events().len(), 1);
+
+        // Finished rooms aren't moved.
+        let closed_room = db::room::FindQuery::new(closed_room.id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to find room")
+            .expect("Room not found");
+
+        assert_eq!(closed_room.backend_id(), Some(failed_backend.id()));
+    }
+
+    #[sqlx::test]
+    async fn teardown_unbinds_rooms_without_available_backends(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let mut conn = db.get_conn().await;
+
+        let failed_backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let draining_backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        janus_backend::SetDrainingQuery::new(draining_backend.id(), true)
+            .execute(&mut conn)
+            .await
+            .expect("Failed to set draining");
+
+        let room =
+            shared_helpers::insert_room_with_backend_id(&mut conn, failed_backend.id()).await;
+
+        let teardown = teardown_backend(
+            &failed_backend,
+            &Balancer::new(&Default::default(), None),
+            &mut conn,
+        )
+        .await
+        .expect("Failed to tear down backend");
+
+        assert_eq!(teardown.moved_rooms.len(), 1);
+        assert_eq!(teardown.moved_rooms[0].id(), room.id());
+        assert_eq!(teardown.moved_rooms[0].backend_id(), None);
+    }
+}
@@ -1,10 +1,13 @@
-use std::time::Duration;
+use std::{collections::VecDeque, time::Duration};
 
 use anyhow::Context;
-use prometheus::{IntGauge, IntGaugeVec, Opts, Registry};
+use parking_lot::Mutex;
+use prometheus::{IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
 use tracing::error;
 
-use crate::db::agent_connection;
+use svc_agent::AgentId;
+
+use crate::db::{self, agent_connection};
 
 use super::client_pool::Clients;
 
@@ -13,6 +16,7 @@ pub struct Metrics {
     total: IntGauge,
     connected_agents: IntGauge,
     load: IntGaugeVec,
+    draining: IntGaugeVec,
     polling_janusses: IntGauge,
 }
 
@@ -32,13 +36,19 @@ impl Metrics {
             Opts::new("janus_load", "Janus load metrics"),
             &["kind", "agent"],
         )?;
+        let draining = IntGaugeVec::new(
+            Opts::new("janus_draining", "Load left on draining Janus backends"),
+            &["kind", "agent"],
+        )?;
         registry.register(Box::new(janus_basic_metrics))?;
         registry.register(Box::new(load.clone()))?;
+        registry.register(Box::new(draining.clone()))?;
         Ok(Self {
             online,
             total,
             connected_agents,
             load,
+            draining,
             polling_janusses,
         })
     }
@@ -90,8 +100,132 @@ impl Metrics {
             agent_load.set(backend_load.taken);
         }
 
+        let draining_load = crate::db::janus_backend::draining_load(&mut conn)
+            .await
+            .context("Failed to get draining janus backends load")?;
+        // Backends that stopped draining or went away must not be reported anymore.
+        self.draining.reset();
+        for backend_load in draining_load {
+            self.draining
+                .get_metric_with_label_values(&["rooms", backend_load.backend_id.label()])?
+                .set(backend_load.rooms);
+            self.draining
+                .get_metric_with_label_values(&["connections", backend_load.backend_id.label()])?
+                .set(backend_load.connections);
+        }
+
         self.polling_janusses.set(clients.clients_count() as i64);
 
         Ok(())
     }
 }
+
+/// Maximum number of rooms having their own slow link counters.
+const SLOW_LINK_ROOMS_LIMIT: usize = 1000;
+
+/// Counters of the events Janus reports about the handles.
+pub struct EventMetrics {
+    slow_link: IntCounterVec,
+    room_slow_link: IntCounterVec,
+    /// Rooms having slow link counters, the least recently observed one goes first.
+    slow_link_rooms: Mutex<VecDeque<db::room::Id>>,
+}
+
+impl EventMetrics {
+    pub fn new(registry: &Registry) -> anyhow::Result<Self> {
+        let slow_link = IntCounterVec::new(
+            Opts::new("janus_slow_link", "Janus slow link events"),
+            &["backend", "direction"],
+        )?;
+        let room_slow_link = IntCounterVec::new(
+            Opts::new("janus_room_slow_link", "Janus slow link events by room"),
+            &["room", "direction"],
+        )?;
+        registry.register(Box::new(slow_link.clone()))?;
+        registry.register(Box::new(room_slow_link.clone()))?;
+        Ok(Self {
+            slow_link,
+            room_slow_link,
+            slow_link_rooms: Mutex::new(VecDeque::with_capacity(SLOW_LINK_ROOMS_LIMIT)),
+        })
+    }
+
+    /// `uplink` is from Janus' perspective: media sent by the agent is being lost.
+    ///
+    /// Only the most recently observed rooms are counted by room to keep the cardinality bounded.
+    pub fn observe_slow_link(
+        &self,
+        backend: Option<&AgentId>,
+        room_id: db::room::Id,
+        uplink: bool,
+    ) {
+        let backend = backend.map(|b| b.label()).unwrap_or("unknown");
+        let direction = if uplink { "uplink" } else { "downlink" };
+        self.slow_link
+            .with_label_values(&[backend, direction])
+            .inc();
+
+        let mut rooms = self.slow_link_rooms.lock();
+
+        match rooms.iter().position(|id| *id == room_id) {
+            Some(idx) => {
+                rooms.remove(idx);
+            }
+            None if rooms.len() >= SLOW_LINK_ROOMS_LIMIT => {
+                if let Some(evicted) = rooms.pop_front() {
+                    let evicted = evicted.to_string();
+                    for direction in ["uplink", "downlink"] {
+                        // The room may have no counter for this direction.
+                        let _ = self
+                            .room_slow_link
+                            .remove_label_values(&[&evicted, direction]);
+                    }
+                }
+            }
+            None => (),
+        }
+
+        rooms.push_back(room_id);
+
+        self.room_slow_link
+            .with_label_values(&[&room_id.to_string(), direction])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn room_slow_link_counters_are_capped() {
+        let metrics = EventMetrics::new(&Registry::new()).expect("Failed to create metrics");
+
+        let first_room = db::room::Id::random();
+        metrics.observe_slow_link(None, first_room, true);
+
+        for _ in 1..SLOW_LINK_ROOMS_LIMIT {
+            metrics.observe_slow_link(None, db::room::Id::random(), true);
+        }
+
+        // Observing the first room again makes it the most recent one.
+        metrics.observe_slow_link(None, first_room, false);
+        let second_room = metrics.slow_link_rooms.lock()[0];
+
+        metrics.observe_slow_link(None, db::room::Id::random(), true);
+
+        let counter = |room_id: db::room::Id, direction: &str| {
+            metrics
+                .room_slow_link
+                .get_metric_with_label_values(&[&room_id.to_string(), direction])
+                .expect("Failed to get counter")
+                .get()
+        };
+
+        assert_eq!(metrics.slow_link_rooms.lock().len(), SLOW_LINK_ROOMS_LIMIT);
+        assert_eq!(counter(first_room, "uplink"), 1);
+        assert_eq!(counter(first_room, "downlink"), 1);
+        // The evicted room's counter starts over.
+        assert_eq!(counter(second_room, "uplink"), 0);
+    }
+}
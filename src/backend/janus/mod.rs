@@ -9,10 +9,13 @@ use svc_agent::{
     Addressable, AgentId,
 };
 use svc_error::Error as SvcError;
-use tracing::{error, info, Span};
+use tracing::{error, warn, Span};
 
 use self::client::{
-    create_handle::OpaqueId, transactions::TransactionKind, HandleId, IncomingEvent,
+    create_handle::OpaqueId,
+    events::{MediaEvent, SlowLinkEvent, TimeoutEvent},
+    transactions::TransactionKind,
+    HandleId, IncomingEvent,
 };
 use crate::{
     app::{
@@ -24,7 +27,7 @@ use crate::{
         API_VERSION,
     },
     client::conference::ConferenceClient,
-    db::{self, agent_connection, janus_rtc_stream, recording, rtc},
+    db::{self, agent_connection, janus_backend, janus_rtc_stream, recording, room::FindQueryable},
 };
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -103,10 +106,9 @@ async fn handle_event_impl<C: Context>(
         IncomingEvent::Detached(inev) => {
             handle_hangup_detach(context, inev.opaque_id, inev.sender).await
         }
-        IncomingEvent::Media(_) | IncomingEvent::Timeout(_) | IncomingEvent::SlowLink(_) => {
-            // Ignore these kinds of events.
-            Ok(Box::new(stream::empty()))
-        }
+        IncomingEvent::SlowLink(inev) => handle_slow_link(context, inev).await,
+        IncomingEvent::Media(inev) => handle_media(context, inev).await,
+        IncomingEvent::Timeout(inev) => handle_timeout(context, inev).await,
         IncomingEvent::Event(resp) => {
             match resp.transaction.kind {
                 Some(TransactionKind::AgentLeave) => Ok(Box::new(stream::empty())),
@@ -293,104 +295,92 @@ async fn handle_event_impl<C: Context>(
                             .get("status")
                             .context("Missing 'status' in the response")
                             .error(AppErrorKind::MessageParsingFailed)?;
-                        match status {
-                            val if val == "200" => Ok(()),
+                        let recording = match status {
+                            val if val == "200" => {
+                                let rtc_id = plugin_data
+                                    .get("id")
+                                    .context("Missing 'id' in response")
+                                    .error(AppErrorKind::MessageParsingFailed)
+                                    .and_then(|val| {
+                                        serde_json::from_value::<db::rtc::Id>(val.clone())
+                                            .context("Invalid value for 'id'")
+                                            .error(AppErrorKind::MessageParsingFailed)
+                                    })?;
+
+                                // if vacuuming was already started by previous request - just do nothing
+                                let maybe_already_running =
+                                    plugin_data.get("state").and_then(|v| v.as_str())
+                                        == Some(ALREADY_RUNNING_STATE);
+                                if maybe_already_running {
+                                    return Ok(Box::new(stream::empty()) as MessageStream);
+                                }
+
+                                let mjr_dumps_uris = plugin_data
+                                    .get("mjr_dumps_uris")
+                                    .context("Missing 'mjr_dumps_uris' in response")
+                                    .error(AppErrorKind::MessageParsingFailed)
+                                    .and_then(|dumps| {
+                                        serde_json::from_value::<Vec<String>>(dumps.clone())
+                                            .context("Invalid value for 'dumps_uris'")
+                                            .error(AppErrorKind::MessageParsingFailed)
+                                    })?;
+
+                                let mut conn = context.get_conn().await?;
+                                update_recording(
+                                    rtc_id,
+                                    tn.backend_id.as_ref(),
+                                    recording::Status::Ready,
+                                    Some(mjr_dumps_uris),
+                                    &mut conn,
+                                )
+                                .await?
+                            }
                             val if val == "404" => {
                                 let mut conn = context.get_conn().await?;
-                                recording::UpdateQuery::new(tn.rtc_id)
-                                    .status(recording::Status::Missing)
-                                    .execute(&mut conn)
-                                    .await?;
+                                update_recording(
+                                    tn.rtc_id,
+                                    tn.backend_id.as_ref(),
+                                    recording::Status::Missing,
+                                    None,
+                                    &mut conn,
+                                )
+                                .await?
+                            }
+                            _ => {
+                                return Err(anyhow!("Received {} status", status))
+                                    .error(AppErrorKind::BackendRequestFailed)
+                            }
+                        };
 
-                                Err(anyhow!("Janus is missing recording"))
+                        let rtc_id = match recording {
+                            Some(recording) if recording.status() == recording::Status::Ready => {
+                                recording.rtc_id()
+                            }
+                            Some(_) => {
+                                return Err(anyhow!("Janus is missing recording"))
                                     .error(AppErrorKind::BackendRecordingMissing)
                             }
-                            _ => Err(anyhow!("Received {} status", status))
-                                .error(AppErrorKind::BackendRequestFailed),
-                        }?;
-                        let rtc_id = plugin_data
-                            .get("id")
-                            .context("Missing 'id' in response")
-                            .error(AppErrorKind::MessageParsingFailed)
-                            .and_then(|val| {
-                                serde_json::from_value::<db::rtc::Id>(val.clone())
-                                    .context("Invalid value for 'id'")
-                                    .error(AppErrorKind::MessageParsingFailed)
-                            })?;
-
-                        // if vacuuming was already started by previous request - just do nothing
-                        let maybe_already_running =
-                            plugin_data.get("state").and_then(|v| v.as_str())
-                                == Some(ALREADY_RUNNING_STATE);
-                        if maybe_already_running {
-                            return Ok(Box::new(stream::empty()) as MessageStream);
-                        }
-
-                        let mjr_dumps_uris = plugin_data
-                            .get("mjr_dumps_uris")
-                            .context("Missing 'mjr_dumps_uris' in response")
-                            .error(AppErrorKind::MessageParsingFailed)
-                            .and_then(|dumps| {
-                                serde_json::from_value::<Vec<String>>(dumps.clone())
-                                    .context("Invalid value for 'dumps_uris'")
-                                    .error(AppErrorKind::MessageParsingFailed)
-                            })?;
+                            // Other parts of the recording are still being uploaded.
+                            None => return Ok(Box::new(stream::empty()) as MessageStream),
+                        };
 
+                        // The RTC may have been deleted with its recording left to be uploaded.
                         let mut conn = context.get_conn().await?;
-                        let rtc = rtc::FindQuery::new(rtc_id)
+                        let room = db::room::FindByRtcIdQuery::new(rtc_id)
+                            .include_deleted()
                             .execute(&mut conn)
                             .await?
-                            .context("RTC not found")
-                            .error(AppErrorKind::RtcNotFound)?;
-
-                        let room = endpoint::helpers::find_room_by_rtc_id(
-                            rtc.id(),
-                            endpoint::helpers::RoomTimeRequirement::Any,
-                            &mut conn,
-                        )
-                        .await?;
-
-                        recording::UpdateQuery::new(rtc_id)
-                            .status(recording::Status::Ready)
-                            .mjr_dumps_uris(mjr_dumps_uris)
-                            .execute(&mut conn)
-                            .await?;
-
-                        let mut conn = context.get_conn().await?;
-                        let rtcs_with_recs = rtc::ListWithRecordingQuery::new(room.id())
-                            .execute(&mut conn)
-                            .await?;
-
-                        // Ensure that all rtcs with a recording have ready recording.
-                        let room_done = rtcs_with_recs.iter().all(|(_rtc, maybe_recording)| {
-                            match maybe_recording {
-                                None => true,
-                                Some(recording) => {
-                                    recording.status() == db::recording::Status::Ready
-                                }
-                            }
-                        });
-
-                        if !room_done {
-                            return Ok(Box::new(stream::empty()) as MessageStream);
-                        }
+                            .context("Room not found")
+                            .error(AppErrorKind::RoomNotFound)?;
 
-                        let recs_with_rtcs =
-                            rtcs_with_recs
-                                .into_iter()
-                                .filter_map(|(rtc, maybe_recording)| {
-                                    let recording = maybe_recording?;
-                                    matches!(recording.status(), db::recording::Status::Ready)
-                                        .then(|| (recording, rtc))
-                                });
-
-                        info!(
-                            class_id = %room.classroom_id(),
-                            room_id = %room.id(),
-                            "sending room.upload event"
-                        );
-                        // Send room.upload event.
-                        let event = endpoint::system::upload_event(context, &room, recs_with_rtcs)?;
+                        // Send room.upload event once every recording of the room is ready.
+                        let event =
+                            match endpoint::system::room_upload_event(context, &room, &mut conn)
+                                .await?
+                            {
+                                Some(event) => event,
+                                None => return Ok(Box::new(stream::empty()) as MessageStream),
+                            };
 
                         let event_box = Box::new(event)
                             as Box<dyn IntoPublishableMessage + Send + Sync + 'static>;
@@ -438,6 +428,145 @@ struct SpeakingNotification {
     agent_id: AgentId,
 }
 
+async fn handle_media<C: Context>(
+    context: &mut C,
+    inev: MediaEvent,
+) -> Result<MessageStream, AppError> {
+    let kind = match inev.kind.as_str() {
+        "audio" => janus_rtc_stream::MediaKind::Audio,
+        "video" => janus_rtc_stream::MediaKind::Video,
+        _ => return Ok(Box::new(stream::empty())),
+    };
+
+    // Only publishers' handles have their streams, so the events of readers' handles are skipped.
+    let mut conn = context.get_conn().await?;
+
+    let maybe_rtc_stream =
+        janus_rtc_stream::update_media(inev.opaque_id.stream_id, kind, inev.receiving, &mut conn)
+            .await?;
+
+    match maybe_rtc_stream {
+        Some(rtc_stream) => {
+            let uri = format!("rooms/{}/events", inev.opaque_id.room_id);
+            let timing = ShortTermTimingProperties::until_now(context.start_timestamp());
+            let props = OutgoingEventProperties::new("rtc_stream.media_update", timing);
+            let event = OutgoingEvent::broadcast(rtc_stream, props, &uri);
+
+            Ok(Box::new(stream::once(std::future::ready(
+                Box::new(event) as Box<dyn IntoPublishableMessage + Send + Sync + 'static>
+            ))) as MessageStream)
+        }
+        None => Ok(Box::new(stream::empty()) as MessageStream),
+    }
+}
+
+/// Stores the result of the recording upload. Returns `None` if the recording is split
+/// and some of its parts are still being uploaded by other backends.
+async fn update_recording(
+    rtc_id: db::rtc::Id,
+    backend_id: Option<&AgentId>,
+    status: recording::Status,
+    mjr_dumps_uris: Option<Vec<String>>,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Option<recording::Object>> {
+    match backend_id {
+        Some(backend_id) => {
+            let mut q = recording::UpdatePartQuery::new(rtc_id, backend_id).status(status);
+            if let Some(mjr_dumps_uris) = mjr_dumps_uris {
+                q = q.mjr_dumps_uris(mjr_dumps_uris);
+            }
+            q.execute(conn).await?;
+
+            recording::complete_from_parts(rtc_id, conn).await
+        }
+        None => {
+            let mut q = recording::UpdateQuery::new(rtc_id).status(status);
+            if let Some(mjr_dumps_uris) = mjr_dumps_uris {
+                q = q.mjr_dumps_uris(mjr_dumps_uris);
+            }
+            q.execute(conn).await.map(Some)
+        }
+    }
+}
+
+async fn handle_timeout<C: Context>(
+    context: &mut C,
+    inev: TimeoutEvent,
+) -> Result<MessageStream, AppError> {
+    let mut conn = context.get_conn().await?;
+
+    // Janus has torn down the session so the backend registered with it is dead.
+    let backend = match janus_backend::FindBySessionQuery::new(inev.session_id)
+        .execute(&mut conn)
+        .await?
+    {
+        Some(backend) => backend,
+        None => return Ok(Box::new(stream::empty())),
+    };
+
+    warn!(?backend, "Session timed out");
+
+    let janus_clients = context.janus_clients();
+    let teardown =
+        client_pool::teardown_backend(&backend, janus_clients.balancer(), &mut conn).await?;
+    janus_clients.remove_client(&backend);
+
+    Ok(Box::new(stream::iter(teardown.into_events())))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SlowLinkNotification {
+    agent_id: AgentId,
+    rtc_id: db::rtc::Id,
+    uplink: bool,
+}
+
+async fn handle_slow_link<C: Context>(
+    context: &mut C,
+    inev: SlowLinkEvent,
+) -> Result<MessageStream, AppError> {
+    let mut conn = context.get_conn().await?;
+
+    let backend = janus_backend::FindBySessionQuery::new(inev.session_id)
+        .execute(&mut conn)
+        .await?;
+
+    context.metrics().janus_events.observe_slow_link(
+        backend.as_ref().map(|b| b.id()),
+        inev.opaque_id.room_id,
+        inev.uplink,
+    );
+
+    // Notify the agent at most once per throttling interval
+    // since Janus keeps sending these events while the link is degraded.
+    let throttle = context.config().slow_link_throttle;
+
+    let maybe_target =
+        agent_connection::SlowLinkQuery::new(inev.sender, context.start_timestamp() - throttle)
+            .execute(&mut conn)
+            .await?;
+
+    match maybe_target {
+        Some(target) => {
+            let notification = SlowLinkNotification {
+                agent_id: target.agent_id,
+                rtc_id: target.rtc_id,
+                uplink: inev.uplink,
+            };
+
+            let uri = format!("rooms/{}/events", target.room_id);
+            let timing = ShortTermTimingProperties::until_now(context.start_timestamp());
+            let props = OutgoingEventProperties::new("rtc_stream.slow_link", timing);
+            let event = OutgoingEvent::broadcast(notification, props, &uri);
+
+            Ok(Box::new(stream::once(std::future::ready(
+                Box::new(event) as Box<dyn IntoPublishableMessage + Send + Sync + 'static>
+            ))) as MessageStream)
+        }
+        None => Ok(Box::new(stream::empty()) as MessageStream),
+    }
+}
+
 async fn handle_hangup_detach<C: Context>(
     context: &mut C,
     opaque_id: OpaqueId,
@@ -488,7 +617,202 @@ async fn handle_hangup_detach<C: Context>(
     Ok(Box::new(stream))
 }
 
+#[cfg(test)]
+mod tests {
+    use serde_json::Value as JsonValue;
+
+    use crate::{
+        backend::janus::client::SessionId,
+        test_helpers::{db::TestDb, parse_messages, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn slow_link_notification_is_throttled(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let session_id = SessionId::random();
+        let handle_id = HandleId::random();
+
+        let (room, rtc) = {
+            let mut conn = db.get_conn().await;
+
+            let backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "http://localhost",
+                session_id,
+                HandleId::random(),
+            )
+            .await;
+
+            let room = shared_helpers::insert_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+
+            shared_helpers::insert_connected_to_handle_agent(
+                &mut conn,
+                agent.agent_id(),
+                room.id(),
+                rtc.id(),
+                handle_id,
+            )
+            .await;
+
+            (room, rtc)
+        };
+
+        let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+        let slow_link = || {
+            IncomingEvent::SlowLink(SlowLinkEvent {
+                session_id,
+                sender: handle_id,
+                opaque_id: OpaqueId {
+                    stream_id: db::janus_rtc_stream::Id::random(),
+                    room_id: room.id(),
+                },
+                uplink: true,
+            })
+        };
+
+        let messages = parse_messages(super::handle_event(&mut context, slow_link()).await).await;
+        let (payload, evp, topic) = find_event::<JsonValue>(messages.as_slice());
+        assert_eq!(evp.label(), "rtc_stream.slow_link");
+        assert!(topic.ends_with(&format!("/rooms/{}/events", room.id())));
+        assert_eq!(payload["agent_id"], agent.agent_id().to_string());
+        assert_eq!(payload["rtc_id"], rtc.id().to_string());
+        assert_eq!(payload["uplink"], true);
+
+        // Janus keeps reporting the slow link but the agent is notified only once.
+        let messages = parse_messages(super::handle_event(&mut context, slow_link()).await).await;
+        assert!(messages.is_empty());
+    }
+
+    #[sqlx::test]
+    async fn timeout_tears_down_backend(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let session_id = SessionId::random();
+
+        let (backend, room, rtc_stream) = {
+            let mut conn = db.get_conn().await;
+
+            let backend = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "http://localhost",
+                session_id,
+                HandleId::random(),
+            )
+            .await;
+
+            let room = shared_helpers::insert_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+
+            let rtc_stream = factory::JanusRtcStream::new(USR_AUDIENCE)
+                .backend(&backend)
+                .rtc(&rtc)
+                .insert(&mut conn)
+                .await;
+
+            janus_rtc_stream::start(rtc_stream.id(), &mut conn)
+                .await
+                .expect("Failed to start rtc stream");
+
+            shared_helpers::insert_connected_agent(
+                &mut conn,
+                agent.agent_id(),
+                room.id(),
+                rtc.id(),
+            )
+            .await;
+
+            (backend, room, rtc_stream)
+        };
+
+        let mut context = TestContext::new(db.clone(), TestAuthz::new()).await;
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        context.with_janus(tx);
+
+        let timeout = IncomingEvent::Timeout(TimeoutEvent { session_id });
+
+        let messages = parse_messages(super::handle_event(&mut context, timeout).await).await;
+        let (payload, evp, topic) = find_event::<JsonValue>(messages.as_slice());
+        assert_eq!(evp.label(), "rtc_stream.update");
+        assert!(topic.ends_with(&format!("/rooms/{}/events", room.id())));
+        assert_eq!(payload["id"], rtc_stream.id().to_string());
+
+        let mut conn = db.get_conn().await;
+
+        let maybe_backend = janus_backend::FindQuery::new(backend.id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to find backend");
+
+        assert!(maybe_backend.is_none());
+
+        let connections_count = agent_connection::CountQuery::new()
+            .execute(&mut conn)
+            .await
+            .expect("Failed to count connections");
+
+        assert_eq!(connections_count.count, 0);
+    }
+
+    #[sqlx::test]
+    async fn media_update_on_stalled_video(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+
+        let (rtc, rtc_stream) = {
+            let mut conn = db.get_conn().await;
+            let rtc = shared_helpers::insert_rtc(&mut conn).await;
+
+            let rtc_stream = factory::JanusRtcStream::new(USR_AUDIENCE)
+                .rtc(&rtc)
+                .insert(&mut conn)
+                .await;
+
+            (rtc, rtc_stream)
+        };
+
+        let mut context = TestContext::new(db, TestAuthz::new()).await;
+
+        let media = |kind: &str, receiving: bool| {
+            IncomingEvent::Media(MediaEvent {
+                session_id: SessionId::random(),
+                sender: rtc_stream.handle_id(),
+                opaque_id: OpaqueId {
+                    stream_id: rtc_stream.id(),
+                    room_id: rtc.room_id(),
+                },
+                kind: kind.to_owned(),
+                receiving,
+            })
+        };
+
+        let messages =
+            parse_messages(super::handle_event(&mut context, media("video", false)).await).await;
+        let (payload, evp, topic) = find_event::<JsonValue>(messages.as_slice());
+        assert_eq!(evp.label(), "rtc_stream.media_update");
+        assert!(topic.ends_with(&format!("/rooms/{}/events", rtc.room_id())));
+        assert_eq!(payload["id"], rtc_stream.id().to_string());
+        assert_eq!(payload["video_receiving"], false);
+        assert!(payload.get("audio_receiving").is_none());
+
+        // Nothing has changed so there's no notification.
+        let messages =
+            parse_messages(super::handle_event(&mut context, media("video", false)).await).await;
+        assert!(messages.is_empty());
+
+        // The video resumes.
+        let messages =
+            parse_messages(super::handle_event(&mut context, media("video", true)).await).await;
+        let (payload, _, _) = find_event::<JsonValue>(messages.as_slice());
+        assert_eq!(payload["video_receiving"], true);
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
+pub mod balancer;
 pub mod client;
 pub mod client_pool;
 pub mod metrics;
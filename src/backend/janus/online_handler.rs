@@ -5,13 +5,13 @@ use crate::{
     backend::janus::client::{
         create_handle::CreateHandleRequest,
         service_ping::{ServicePingRequest, ServicePingRequestBody},
-        JanusClient,
+        JanusClient, Transport,
     },
     config::JanusRegistry,
     db,
 };
 use anyhow::{Context, Result};
-use http::Response;
+use http::{Request, Response};
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Server,
@@ -19,7 +19,7 @@ use hyper::{
 use serde::{Deserialize, Serialize};
 use svc_agent::{AccountId, AgentId};
 use svc_authn::{jose::ConfigMap, token::jws_compact::extract::decode_jws_compact_with_config};
-use tracing::error;
+use tracing::{error, info};
 
 use super::client_pool::Clients;
 
@@ -30,6 +30,15 @@ struct Online {
     group: Option<String>,
     janus_url: String,
     agent_id: AgentId,
+    #[serde(default)]
+    transport: Transport,
+    region: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Draining {
+    agent_id: AgentId,
+    draining: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,16 +102,8 @@ pub async fn start_internal_api(
                 match req.uri().path() {
                     "/" => {
                         let handle = async {
-                            if req
-                                .headers()
-                                .get("Authorization")
-                                .and_then(|x| x.to_str().ok())
-                                .map_or(true, |h| h != token)
-                            {
-                                error!("Invalid token, path = {}", req.uri().path());
-                                return Ok::<_, anyhow::Error>(
-                                    Response::builder().status(401).body(Body::empty())?,
-                                );
+                            if let Some(response) = check_token(&req, &token)? {
+                                return Ok::<_, anyhow::Error>(response);
                             }
                             let online: Online = serde_json::from_slice(
                                 &hyper::body::to_bytes(req.into_body()).await?,
@@ -118,6 +119,31 @@ pub async fn start_internal_api(
                                 .expect("Must be ok")
                         }))
                     }
+                    "/draining" => {
+                        let handle = async {
+                            if let Some(response) = check_token(&req, &token)? {
+                                return Ok::<_, anyhow::Error>(response);
+                            }
+                            let draining: Draining = serde_json::from_slice(
+                                &hyper::body::to_bytes(req.into_body()).await?,
+                            )?;
+                            let status = if handle_draining(draining, db).await? {
+                                200
+                            } else {
+                                404
+                            };
+                            Ok::<_, anyhow::Error>(
+                                Response::builder().status(status).body(Body::empty())?,
+                            )
+                        };
+                        Ok::<_, String>(handle.await.unwrap_or_else(|err| {
+                            error!(?err, "Set janus draining failed");
+                            Response::builder()
+                                .status(500)
+                                .body(Body::empty())
+                                .expect("Must be ok")
+                        }))
+                    }
                     "/callbacks/stream" => {
                         let handle = async {
                             let token = req
@@ -180,6 +206,21 @@ pub async fn start_internal_api(
     Ok(())
 }
 
+/// Returns the `401` response if the request doesn't carry the registry token.
+fn check_token(req: &Request<Body>, token: &str) -> Result<Option<Response<Body>>> {
+    let header = req
+        .headers()
+        .get("Authorization")
+        .and_then(|x| x.to_str().ok());
+
+    if header == Some(token) {
+        return Ok(None);
+    }
+
+    error!("Invalid token, path = {}", req.uri().path());
+    Ok(Some(Response::builder().status(401).body(Body::empty())?))
+}
+
 async fn handle_online(event: Online, clients: Clients, db: sqlx::PgPool) -> Result<()> {
     let backend_id = event.agent_id.clone();
     let mut conn = db.acquire().await?;
@@ -187,8 +228,9 @@ async fn handle_online(event: Online, clients: Clients, db: sqlx::PgPool) -> Res
         .execute(&mut conn)
         .await?;
 
-    let janus_client = JanusClient::new(&event.janus_url)?;
-    if let Some(backend) = existing_backend {
+    let janus_client = JanusClient::new(&event.janus_url, event.transport)?;
+    // Switching the transport requires a new session.
+    if let Some(backend) = existing_backend.filter(|b| b.transport() == event.transport) {
         let ping_response = janus_client
             .service_ping(ServicePingRequest {
                 session_id: backend.session_id(),
@@ -197,7 +239,7 @@ async fn handle_online(event: Online, clients: Clients, db: sqlx::PgPool) -> Res
             })
             .await;
         if ping_response.is_ok() {
-            clients.get_or_insert(&backend)?;
+            clients.get_or_insert_with(&backend, janus_client)?;
             return Ok(());
         }
     }
@@ -227,7 +269,8 @@ async fn handle_online(event: Online, clients: Clients, db: sqlx::PgPool) -> Res
         handle.id,
         session.id,
         &event.janus_url,
-    );
+    )
+    .transport(event.transport);
 
     if let Some(capacity) = event.capacity {
         q = q.capacity(capacity);
@@ -241,12 +284,30 @@ async fn handle_online(event: Online, clients: Clients, db: sqlx::PgPool) -> Res
         q = q.group(group);
     }
 
+    if let Some(region) = event.region.as_deref() {
+        q = q.region(region);
+    }
+
     let backend = q.execute(&mut conn).await?;
 
-    clients.get_or_insert(&backend)?;
+    clients.get_or_insert_with(&backend, janus_client)?;
     Ok(())
 }
 
+/// Returns `false` if there is no such backend.
+async fn handle_draining(event: Draining, db: sqlx::PgPool) -> Result<bool> {
+    let mut conn = db.acquire().await?;
+    let updated = db::janus_backend::SetDrainingQuery::new(&event.agent_id, event.draining)
+        .execute(&mut conn)
+        .await?;
+
+    if updated > 0 {
+        info!(backend = %event.agent_id, draining = event.draining, "Janus draining changed");
+    }
+
+    Ok(updated > 0)
+}
+
 #[cfg(test)]
 mod test {
     use std::time::Duration;
@@ -255,7 +316,10 @@ mod test {
 
     use crate::{
         backend::janus::{
-            client::service_ping::{ServicePingRequest, ServicePingRequestBody},
+            client::{
+                service_ping::{ServicePingRequest, ServicePingRequestBody},
+                Transport,
+            },
             online_handler::{handle_online, Online},
         },
         db,
@@ -294,6 +358,8 @@ mod test {
             balancer_capacity: Some(2),
             group: None,
             janus_url: janus.url.clone(),
+            transport: Transport::Http,
+            region: None,
         };
 
         handle_online(event, context.janus_clients(), context.db().clone()).await?;
@@ -341,6 +407,8 @@ mod test {
             balancer_capacity: Some(2),
             group: None,
             janus_url: janus.url.clone(),
+            transport: Transport::Http,
+            region: None,
         };
 
         handle_online(event, context.janus_clients(), context.db().clone()).await?;
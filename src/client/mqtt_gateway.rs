@@ -28,6 +28,7 @@ impl From<reqwest::Error> for Error {
 #[async_trait]
 pub trait MqttGatewayClient: Sync + Send {
     async fn create_subscription(&self, subject: AgentId, object: &[&str]) -> Result<(), Error>;
+    async fn delete_subscription(&self, subject: AgentId, object: &[&str]) -> Result<(), Error>;
 }
 
 #[derive(Clone)]
@@ -70,4 +71,26 @@ impl MqttGatewayClient for MqttGatewayHttpClient {
             otherwise => Err(Error::UnexpectedResponse(otherwise)),
         }
     }
+
+    async fn delete_subscription(&self, subject: AgentId, object: &[&str]) -> Result<(), Error> {
+        let mut uri = self.mqtt_api_host_uri.clone();
+        uri.set_path("/api/v1/subscriptions");
+
+        let r = self
+            .http
+            .delete(uri)
+            .json(&serde_json::json!({
+                "object": object,
+                "subject": subject,
+                "version": API_VERSION
+            }))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await?;
+
+        match r.status() {
+            StatusCode::OK => Ok(()),
+            otherwise => Err(Error::UnexpectedResponse(otherwise)),
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, net::SocketAddr, time::Duration};
+use std::{collections::HashMap, convert::TryFrom, net::SocketAddr, time::Duration};
 
 use reqwest::Url;
 use serde::Deserialize;
@@ -23,16 +23,32 @@ pub struct Config {
     pub metrics: MetricsConfig,
     pub max_room_duration: Option<i64>,
     pub janus_group: Option<String>,
+    #[serde(default)]
+    pub balancer: BalancerConfigs,
     #[serde(with = "humantime_serde")]
     pub orphaned_room_timeout: Duration,
+    #[serde(
+        deserialize_with = "deserialize_chrono_duration",
+        default = "default_lobby_timeout"
+    )]
+    pub lobby_timeout: chrono::Duration,
     pub janus_registry: JanusRegistry,
     pub authn: svc_authn::jose::ConfigMap,
     #[serde(with = "humantime_serde", default = "default_waitlist_epoch_duration")]
     pub waitlist_epoch_duration: Duration,
     #[serde(with = "humantime_serde", default = "default_waitlist_timeout")]
     pub waitlist_timeout: Duration,
+    /// Minimal interval between `rtc_stream.slow_link` notifications for the same handle.
+    #[serde(
+        deserialize_with = "deserialize_chrono_duration",
+        default = "default_slow_link_throttle"
+    )]
+    pub slow_link_throttle: chrono::Duration,
     pub outbox: crate::outbox::config::Config,
     pub nats: Option<svc_nats_client::Config>,
+    pub closing_soon: Option<ClosingSoonConfig>,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
 }
 
 fn default_waitlist_epoch_duration() -> Duration {
@@ -43,6 +59,61 @@ fn default_waitlist_timeout() -> Duration {
     Duration::from_secs(25)
 }
 
+fn default_slow_link_throttle() -> chrono::Duration {
+    chrono::Duration::seconds(30)
+}
+
+fn default_lobby_timeout() -> chrono::Duration {
+    chrono::Duration::minutes(10)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ClosingSoonConfig {
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+    /// How long before the room closure `room.closing_soon` events are sent, in seconds.
+    #[serde(deserialize_with = "deserialize_thresholds")]
+    pub thresholds: Vec<i32>,
+}
+
+fn deserialize_chrono_duration<'de, D>(deserializer: D) -> Result<chrono::Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let duration: Duration = humantime_serde::deserialize(deserializer)?;
+    chrono::Duration::from_std(duration).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_thresholds<'de, D>(deserializer: D) -> Result<Vec<i32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let durations = Vec::<humantime_serde::Serde<Duration>>::deserialize(deserializer)?;
+
+    durations
+        .into_iter()
+        .map(|d| i32::try_from(d.into_inner().as_secs()).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Intervals of the system jobs run by the built-in scheduler.
+/// Jobs without an interval are not scheduled.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SchedulerConfig {
+    #[serde(default, with = "humantime_serde")]
+    pub vacuum_interval: Option<Duration>,
+    #[serde(default, with = "humantime_serde")]
+    pub agent_cleanup_interval: Option<Duration>,
+    #[serde(default, with = "humantime_serde")]
+    pub agent_connection_cleanup_interval: Option<Duration>,
+    #[serde(default, with = "humantime_serde")]
+    pub lobby_cleanup_interval: Option<Duration>,
+    #[serde(default, with = "humantime_serde")]
+    pub close_orphaned_rooms_interval: Option<Duration>,
+    #[serde(default, with = "humantime_serde")]
+    pub closing_warning_cleanup_interval: Option<Duration>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct JanusRegistry {
     pub bind_addr: SocketAddr,
@@ -86,6 +157,46 @@ pub struct UploadConfig {
     pub bucket: String,
 }
 
+/// Strategies choosing a backend for the room's first writer.
+/// The audience's strategy takes precedence over the RTC sharing policy's one.
+/// Without any the strategy depends on `janus_group`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct BalancerConfigs {
+    pub shared: Option<BalancerStrategyConfig>,
+    pub owned: Option<BalancerStrategyConfig>,
+    #[serde(default)]
+    pub audiences: HashMap<String, BalancerStrategyConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum BalancerStrategyConfig {
+    MostLoaded,
+    LeastLoaded,
+    WeightedByCapacity,
+    RoundRobin,
+    /// `threshold` is the share of the backend's capacity, from 0 to 1.
+    PackUntilThreshold {
+        #[serde(deserialize_with = "deserialize_threshold")]
+        threshold: f64,
+    },
+}
+
+fn deserialize_threshold<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let threshold = f64::deserialize(deserializer)?;
+
+    if (0.0..=1.0).contains(&threshold) {
+        Ok(threshold)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "threshold must be from 0 to 1, got {threshold}"
+        )))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct MetricsConfig {
     pub http: MetricsHttpConfig,
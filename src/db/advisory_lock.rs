@@ -0,0 +1,20 @@
+/// Tries to take a session level advisory lock identified by the key.
+///
+/// The lock is held until it gets released explicitly or the connection is closed.
+pub async fn try_lock(key: &str, conn: &mut sqlx::PgConnection) -> sqlx::Result<bool> {
+    sqlx::query_scalar!(
+        r#"SELECT pg_try_advisory_lock(hashtext($1)) AS "locked!""#,
+        key
+    )
+    .fetch_one(conn)
+    .await
+}
+
+pub async fn unlock(key: &str, conn: &mut sqlx::PgConnection) -> sqlx::Result<bool> {
+    sqlx::query_scalar!(
+        r#"SELECT pg_advisory_unlock(hashtext($1)) AS "unlocked!""#,
+        key
+    )
+    .fetch_one(conn)
+    .await
+}
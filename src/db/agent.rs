@@ -1,5 +1,6 @@
 use chrono::{serde::ts_seconds, DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use svc_agent::AgentId;
 
 use crate::db;
@@ -16,6 +17,23 @@ pub enum Status {
     InProgress,
     #[sqlx(rename = "ready")]
     Ready,
+    /// Waiting in the lobby for the host approval.
+    #[sqlx(rename = "pending")]
+    Pending,
+}
+
+/// Display information provided by the client on `room.enter`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,12 +44,22 @@ pub struct Object {
     #[serde(with = "ts_seconds")]
     created_at: DateTime<Utc>,
     status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<Json<Metadata>>,
 }
 
 impl Object {
     pub fn id(&self) -> Id {
         self.id
     }
+
+    pub fn agent_id(&self) -> &AgentId {
+        &self.agent_id
+    }
+
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref().map(|m| &m.0)
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -99,13 +127,14 @@ impl<'a> ListQuery<'a> {
                 agent_id as "agent_id: AgentId",
                 room_id as "room_id: Id",
                 created_at,
-                status as "status: Status"
+                status as "status: Status",
+                metadata as "metadata: Json<Metadata>"
             FROM agent
             WHERE
-                status = 'ready' AND
                 ($1::agent_id IS NULL     OR agent_id = $1::agent_id) AND
                 ($2::uuid IS NULL         OR room_id  = $2::uuid) AND
-                ($3::agent_status IS NULL OR status = $3::agent_status)
+                -- only ready agents are listed unless another status is requested
+                status = COALESCE($3::agent_status, 'ready')
             ORDER BY created_at DESC
             OFFSET $4
             LIMIT $5
@@ -129,6 +158,7 @@ pub struct InsertQuery<'a> {
     room_id: db::room::Id,
     status: Status,
     created_at: Option<DateTime<Utc>>,
+    metadata: Option<&'a Metadata>,
 }
 
 impl<'a> InsertQuery<'a> {
@@ -138,14 +168,22 @@ impl<'a> InsertQuery<'a> {
             room_id,
             status: Status::InProgress,
             created_at: None,
+            metadata: None,
         }
     }
 
-    #[cfg(test)]
     pub fn status(self, status: Status) -> Self {
         Self { status, ..self }
     }
 
+    /// Replaces the stored metadata. The metadata is kept as is on re-entering without one.
+    pub fn metadata(self, metadata: &'a Metadata) -> Self {
+        Self {
+            metadata: Some(metadata),
+            ..self
+        }
+    }
+
     #[cfg(test)]
     pub fn created_at(self, created_at: DateTime<Utc>) -> Self {
         Self {
@@ -158,22 +196,25 @@ impl<'a> InsertQuery<'a> {
         sqlx::query_as!(
             Object,
             r#"
-            INSERT INTO agent (agent_id, room_id, status, created_at)
-            VALUES ($1, $2, $3, COALESCE($4, now()))
+            INSERT INTO agent (agent_id, room_id, status, created_at, metadata)
+            VALUES ($1, $2, $3, COALESCE($4, now()), $5)
             ON CONFLICT (agent_id, room_id) DO UPDATE
             SET
-                status = 'in_progress'
+                status = EXCLUDED.status,
+                metadata = COALESCE(EXCLUDED.metadata, agent.metadata)
             RETURNING
                 id as "id: Id",
                 agent_id as "agent_id: AgentId",
                 room_id as "room_id: Id",
                 created_at,
-                status as "status: Status"
+                status as "status: Status",
+                metadata as "metadata: Json<Metadata>"
             "#,
             self.agent_id as &AgentId,
             self.room_id as Id,
             self.status as Status,
-            self.created_at
+            self.created_at,
+            self.metadata.map(Json) as Option<Json<&Metadata>>,
         )
         .fetch_one(conn)
         .await
@@ -220,7 +261,8 @@ impl<'a> UpdateQuery<'a> {
                 agent_id as "agent_id: AgentId",
                 room_id as "room_id: Id",
                 created_at,
-                status as "status: Status"
+                status as "status: Status",
+                metadata as "metadata: Json<Metadata>"
             "#,
             self.agent_id as &AgentId,
             self.room_id as Id,
@@ -282,11 +324,22 @@ impl<'a> DeleteQuery<'a> {
 
 pub struct CleanupQuery {
     created_at: DateTime<Utc>,
+    status: Option<Status>,
 }
 
 impl CleanupQuery {
     pub fn new(created_at: DateTime<Utc>) -> Self {
-        Self { created_at }
+        Self {
+            created_at,
+            status: None,
+        }
+    }
+
+    pub fn status(self, status: Status) -> Self {
+        Self {
+            status: Some(status),
+            ..self
+        }
     }
 
     pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
@@ -294,9 +347,11 @@ impl CleanupQuery {
             r#"
             DELETE FROM agent
             WHERE
-                created_at < $1
+                created_at < $1 AND
+                ($2::agent_status IS NULL OR status = $2)
             "#,
             self.created_at,
+            self.status as Option<Status>,
         )
         .execute(conn)
         .await
@@ -76,6 +76,43 @@ impl<'a> FindQuery<'a> {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+pub struct ListQuery<'a> {
+    agent_id: &'a AgentId,
+    room_id: db::room::Id,
+}
+
+impl<'a> ListQuery<'a> {
+    pub fn new(agent_id: &'a AgentId, room_id: db::room::Id) -> Self {
+        Self { agent_id, room_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                ac.agent_id as "agent_id: db::id::Id",
+                ac.handle_id as "handle_id: HandleId",
+                ac.created_at,
+                ac.rtc_id as "rtc_id: db::id::Id",
+                ac.status as "status: Status"
+            FROM agent_connection as ac
+            INNER JOIN agent as a
+            ON a.id = ac.agent_id
+            WHERE
+                a.agent_id = $1 AND
+                a.room_id = $2
+            "#,
+            self.agent_id as &AgentId,
+            self.room_id as db::id::Id
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
 pub struct CountResult {
     pub count: i64,
 }
@@ -247,6 +284,92 @@ impl DisconnectSingleAgentQuery {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// The agent whose handle has reported a slow link.
+#[derive(Debug)]
+pub struct SlowLinkTarget {
+    pub agent_id: AgentId,
+    pub room_id: db::room::Id,
+    pub rtc_id: db::rtc::Id,
+}
+
+/// Marks the handle as having a slow link.
+///
+/// Returns nothing if the handle is unknown or has already reported a slow link after `since`
+/// so notifications are sent at most once per throttling interval.
+#[derive(Debug)]
+pub struct SlowLinkQuery {
+    handle_id: HandleId,
+    since: DateTime<Utc>,
+}
+
+impl SlowLinkQuery {
+    pub fn new(handle_id: HandleId, since: DateTime<Utc>) -> Self {
+        Self { handle_id, since }
+    }
+
+    pub async fn execute(
+        &self,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Option<SlowLinkTarget>> {
+        sqlx::query_as!(
+            SlowLinkTarget,
+            r#"
+            UPDATE agent_connection AS ac
+            SET
+                slow_link_at = NOW()
+            FROM agent AS a
+            WHERE
+                a.id = ac.agent_id AND
+                ac.handle_id = $1 AND
+                (ac.slow_link_at IS NULL OR ac.slow_link_at < $2)
+            RETURNING
+                a.agent_id as "agent_id: AgentId",
+                a.room_id as "room_id: db::room::Id",
+                ac.rtc_id as "rtc_id: db::rtc::Id"
+            "#,
+            self.handle_id as HandleId,
+            self.since,
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct ListByRtcQuery {
+    rtc_id: db::rtc::Id,
+}
+
+impl ListByRtcQuery {
+    pub fn new(rtc_id: db::rtc::Id) -> Self {
+        Self { rtc_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                agent_id as "agent_id: db::id::Id",
+                handle_id as "handle_id: HandleId",
+                created_at,
+                rtc_id as "rtc_id: db::id::Id",
+                status as "status: Status"
+            FROM agent_connection
+            WHERE
+                rtc_id = $1
+            "#,
+            self.rtc_id as db::rtc::Id,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 #[derive(Debug)]
 pub struct BulkDisconnectByRtcQuery {
     rtc_id: db::rtc::Id,
@@ -0,0 +1,198 @@
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use svc_agent::AgentId;
+
+use crate::db;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "agent_role_kind", rename_all = "snake_case")]
+pub enum Role {
+    Host,
+    CoHost,
+    Speaker,
+    Viewer,
+}
+
+impl Role {
+    /// Moderators are allowed to manage the room without asking the external authz.
+    pub fn is_moderator(self) -> bool {
+        matches!(self, Self::Host | Self::CoHost)
+    }
+
+    /// Resolves the role of the agent in the room.
+    ///
+    /// The host and co-host roles always follow the room's `host` and `co_hosts` so a stored
+    /// role can't outlive them. Other agents keep the stored role assigned by a moderator
+    /// and are speakers by default.
+    pub fn resolve(room: &db::room::Object, agent_id: &AgentId, stored: Option<Self>) -> Self {
+        if room.host() == Some(agent_id) {
+            Self::Host
+        } else if room.co_hosts().contains(agent_id) {
+            Self::CoHost
+        } else {
+            match stored {
+                Some(role @ Self::Viewer) | Some(role @ Self::Speaker) => role,
+                _ => Self::Speaker,
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Object {
+    room_id: db::room::Id,
+    agent_id: AgentId,
+    role: Role,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated_by: Option<AgentId>,
+    #[serde(with = "ts_seconds")]
+    updated_at: DateTime<Utc>,
+}
+
+impl Object {
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// The moderator who has assigned the role. Empty for the roles assigned on enter.
+    pub fn updated_by(&self) -> Option<&AgentId> {
+        self.updated_by.as_ref()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct FindQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+}
+
+impl<'a> FindQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId) -> Self {
+        Self { room_id, agent_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Option<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                room_id as "room_id: db::room::Id",
+                agent_id as "agent_id: AgentId",
+                role as "role: Role",
+                updated_by as "updated_by: AgentId",
+                updated_at
+            FROM agent_role
+            WHERE
+                room_id = $1 AND
+                agent_id = $2
+            "#,
+            self.room_id as db::room::Id,
+            self.agent_id as &AgentId,
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct UpsertQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+    role: Role,
+    updated_by: Option<&'a AgentId>,
+}
+
+impl<'a> UpsertQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId, role: Role) -> Self {
+        Self {
+            room_id,
+            agent_id,
+            role,
+            updated_by: None,
+        }
+    }
+
+    pub fn updated_by(self, updated_by: &'a AgentId) -> Self {
+        Self {
+            updated_by: Some(updated_by),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            INSERT INTO agent_role (room_id, agent_id, role, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (room_id, agent_id) DO UPDATE
+            SET
+                role = EXCLUDED.role,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING
+                room_id as "room_id: db::room::Id",
+                agent_id as "agent_id: AgentId",
+                role as "role: Role",
+                updated_by as "updated_by: AgentId",
+                updated_at
+            "#,
+            self.room_id as db::room::Id,
+            self.agent_id as &AgentId,
+            self.role as Role,
+            self.updated_by as Option<&AgentId>,
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{db::TestDb, prelude::*};
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn resolve_role(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let mut conn = db.get_conn().await;
+
+        let host = TestAgent::new("web", "host", USR_AUDIENCE);
+        let co_host = TestAgent::new("web", "co-host", USR_AUDIENCE);
+        let agent = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let co_hosts = vec![co_host.agent_id().to_owned()];
+
+        let room = shared_helpers::insert_room(&mut conn).await;
+
+        let room = db::room::UpdateQuery::new(room.id())
+            .host(Some(host.agent_id()))
+            .co_hosts(Some(&co_hosts))
+            .execute(&mut conn)
+            .await
+            .expect("Failed to update room");
+
+        assert_eq!(Role::resolve(&room, host.agent_id(), None), Role::Host);
+        assert_eq!(
+            Role::resolve(&room, co_host.agent_id(), Some(Role::Viewer)),
+            Role::CoHost
+        );
+        assert_eq!(Role::resolve(&room, agent.agent_id(), None), Role::Speaker);
+        assert_eq!(
+            Role::resolve(&room, agent.agent_id(), Some(Role::Viewer)),
+            Role::Viewer
+        );
+        assert_eq!(
+            Role::resolve(&room, agent.agent_id(), Some(Role::CoHost)),
+            Role::Speaker
+        );
+    }
+}
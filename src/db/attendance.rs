@@ -0,0 +1,210 @@
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use svc_agent::AgentId;
+
+use crate::db;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "attendance_leave_reason", rename_all = "snake_case")]
+pub enum LeaveReason {
+    /// The agent has left the room with `room.leave`.
+    Leave,
+    /// The agent's subscription has been dropped by the broker.
+    Disconnect,
+    /// The agent has been kicked out of the room.
+    Kick,
+    /// The room has been closed while the agent was present.
+    RoomClose,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Presence of a single agent in the room aggregated over all of its entries.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Summary {
+    agent_id: AgentId,
+    /// Total presence time in seconds. Open entries are counted until now.
+    presence: i64,
+    entries: i64,
+    online: bool,
+    #[serde(with = "ts_seconds")]
+    first_entered_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_leave_reason: Option<LeaveReason>,
+}
+
+#[cfg(test)]
+impl Summary {
+    pub fn agent_id(&self) -> &AgentId {
+        &self.agent_id
+    }
+
+    pub fn presence(&self) -> i64 {
+        self.presence
+    }
+
+    pub fn entries(&self) -> i64 {
+        self.entries
+    }
+
+    pub fn online(&self) -> bool {
+        self.online
+    }
+
+    pub fn last_leave_reason(&self) -> Option<LeaveReason> {
+        self.last_leave_reason
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Opens an attendance entry. Does nothing if the agent already has an open entry in the room.
+#[derive(Debug)]
+pub struct InsertQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+}
+
+impl<'a> InsertQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId) -> Self {
+        Self { room_id, agent_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO attendance (room_id, agent_id)
+            VALUES ($1, $2)
+            ON CONFLICT (room_id, agent_id) WHERE left_at IS NULL DO NOTHING
+            "#,
+            self.room_id as db::room::Id,
+            self.agent_id as &AgentId,
+        )
+        .execute(conn)
+        .await
+        .map(|_| ())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Closes open attendance entries filtered by the room and/or the agent.
+#[derive(Debug)]
+pub struct CloseQuery<'a> {
+    reason: LeaveReason,
+    room_id: Option<db::room::Id>,
+    agent_id: Option<&'a AgentId>,
+}
+
+impl<'a> CloseQuery<'a> {
+    pub fn new(reason: LeaveReason) -> Self {
+        Self {
+            reason,
+            room_id: None,
+            agent_id: None,
+        }
+    }
+
+    pub fn room_id(self, room_id: db::room::Id) -> Self {
+        Self {
+            room_id: Some(room_id),
+            ..self
+        }
+    }
+
+    pub fn agent_id(self, agent_id: &'a AgentId) -> Self {
+        Self {
+            agent_id: Some(agent_id),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            UPDATE attendance
+            SET
+                left_at = NOW(),
+                leave_reason = $1
+            WHERE
+                left_at IS NULL AND
+                ($2::uuid IS NULL OR room_id = $2) AND
+                ($3::agent_id IS NULL OR agent_id = $3)
+            "#,
+            self.reason as LeaveReason,
+            self.room_id as Option<db::room::Id>,
+            self.agent_id as Option<&AgentId>,
+        )
+        .execute(conn)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Closes open entries of agents which are not present in the room anymore,
+/// e.g. removed by the agent cleanup.
+pub async fn close_absent(reason: LeaveReason, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+    sqlx::query!(
+        r#"
+        UPDATE attendance AS at
+        SET
+            left_at = NOW(),
+            leave_reason = $1
+        WHERE
+            at.left_at IS NULL AND
+            NOT EXISTS (
+                SELECT 1
+                FROM agent AS a
+                WHERE
+                    a.room_id = at.room_id AND
+                    a.agent_id = at.agent_id
+            )
+        "#,
+        reason as LeaveReason,
+    )
+    .execute(conn)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct SummaryQuery {
+    room_id: db::room::Id,
+}
+
+impl SummaryQuery {
+    pub fn new(room_id: db::room::Id) -> Self {
+        Self { room_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Summary>> {
+        sqlx::query_as!(
+            Summary,
+            r#"
+            SELECT
+                agent_id as "agent_id!: AgentId",
+                EXTRACT(EPOCH FROM SUM(COALESCE(left_at, NOW()) - entered_at))::bigint as "presence!",
+                COUNT(*) as "entries!",
+                BOOL_OR(left_at IS NULL) as "online!",
+                MIN(entered_at) as "first_entered_at!",
+                (ARRAY_AGG(leave_reason ORDER BY entered_at DESC))[1]
+                    as "last_leave_reason?: LeaveReason"
+            FROM attendance
+            WHERE
+                room_id = $1
+            GROUP BY agent_id
+            ORDER BY MIN(entered_at)
+            "#,
+            self.room_id as db::room::Id,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
@@ -1,5 +1,5 @@
 use crate::backend::janus::{
-    client::{HandleId, SessionId},
+    client::{HandleId, SessionId, Transport},
     JANUS_API_VERSION,
 };
 use crate::db;
@@ -19,6 +19,8 @@ pub struct Object {
     pub api_version: String,
     pub group: Option<String>,
     pub janus_url: String,
+    pub transport: Transport,
+    pub region: Option<String>,
 }
 
 impl Object {
@@ -38,10 +40,18 @@ impl Object {
         &self.janus_url
     }
 
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+
     /// Get a reference to the object's group.
     pub fn group(&self) -> Option<&str> {
         self.group.as_deref()
     }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -68,7 +78,9 @@ impl<'a> FindQuery<'a> {
                 balancer_capacity,
                 api_version,
                 "group",
-                janus_url
+                janus_url,
+                transport as "transport: Transport",
+                region
             FROM janus_backend
             WHERE
                 id = $1
@@ -83,6 +95,45 @@ impl<'a> FindQuery<'a> {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+pub struct FindBySessionQuery {
+    session_id: SessionId,
+}
+
+impl FindBySessionQuery {
+    pub fn new(session_id: SessionId) -> Self {
+        Self { session_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Option<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                id as "id: AgentId",
+                handle_id as "handle_id: HandleId",
+                session_id as "session_id: SessionId",
+                created_at,
+                capacity,
+                balancer_capacity,
+                api_version,
+                "group",
+                janus_url,
+                transport as "transport: Transport",
+                region
+            FROM janus_backend
+            WHERE
+                session_id = $1
+            LIMIT 1
+            "#,
+            self.session_id as SessionId
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 #[derive(Debug)]
 pub struct UpsertQuery<'a> {
     id: &'a AgentId,
@@ -93,6 +144,8 @@ pub struct UpsertQuery<'a> {
     api_version: String,
     group: Option<&'a str>,
     janus_url: &'a str,
+    transport: Option<Transport>,
+    region: Option<&'a str>,
 }
 
 impl<'a> UpsertQuery<'a> {
@@ -111,6 +164,8 @@ impl<'a> UpsertQuery<'a> {
             api_version: JANUS_API_VERSION.to_string(),
             group: None,
             janus_url,
+            transport: None,
+            region: None,
         }
     }
 
@@ -135,13 +190,27 @@ impl<'a> UpsertQuery<'a> {
         }
     }
 
+    pub fn transport(self, transport: Transport) -> Self {
+        Self {
+            transport: Some(transport),
+            ..self
+        }
+    }
+
+    pub fn region(self, region: &'a str) -> Self {
+        Self {
+            region: Some(region),
+            ..self
+        }
+    }
+
     pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
         sqlx::query_as!(
             Object,
             r#"
             INSERT INTO janus_backend
-                (id, handle_id, session_id, capacity, balancer_capacity, api_version, "group", janus_url)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                (id, handle_id, session_id, capacity, balancer_capacity, api_version, "group", janus_url, transport, region)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, 'http'::janus_transport), $10)
             ON CONFLICT (id) DO UPDATE
             SET
                 handle_id         = $2,
@@ -150,7 +219,9 @@ impl<'a> UpsertQuery<'a> {
                 balancer_capacity = COALESCE($5, janus_backend.balancer_capacity),
                 api_version       = $6,
                 "group"           = COALESCE($7, janus_backend."group"),
-                janus_url         = $8
+                janus_url         = $8,
+                transport         = COALESCE($9, janus_backend.transport),
+                region            = COALESCE($10, janus_backend.region)
             RETURNING
                 id as "id: AgentId",
                 handle_id as "handle_id: HandleId",
@@ -160,7 +231,9 @@ impl<'a> UpsertQuery<'a> {
                 balancer_capacity,
                 api_version,
                 "group",
-                janus_url
+                janus_url,
+                transport as "transport: Transport",
+                region
             "#,
             self.id as &AgentId,
             self.handle_id as HandleId,
@@ -169,7 +242,9 @@ impl<'a> UpsertQuery<'a> {
             self.balancer_capacity,
             self.api_version,
             self.group,
-            self.janus_url
+            self.janus_url,
+            self.transport as Option<Transport>,
+            self.region,
         )
         .fetch_one(conn)
         .await
@@ -178,6 +253,66 @@ impl<'a> UpsertQuery<'a> {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Stores a new session and handle for the backend unless the session has already been
+/// replaced, e.g. by the backend registering again.
+pub struct ReplaceSessionQuery<'a> {
+    id: &'a AgentId,
+    old_session_id: SessionId,
+    session_id: SessionId,
+    handle_id: HandleId,
+}
+
+impl<'a> ReplaceSessionQuery<'a> {
+    pub fn new(
+        id: &'a AgentId,
+        old_session_id: SessionId,
+        session_id: SessionId,
+        handle_id: HandleId,
+    ) -> Self {
+        Self {
+            id,
+            old_session_id,
+            session_id,
+            handle_id,
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Option<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            UPDATE janus_backend
+            SET
+                session_id = $3,
+                handle_id  = $4
+            WHERE
+                id = $1 AND
+                session_id = $2
+            RETURNING
+                id as "id: AgentId",
+                handle_id as "handle_id: HandleId",
+                session_id as "session_id: SessionId",
+                created_at,
+                capacity,
+                balancer_capacity,
+                api_version,
+                "group",
+                janus_url,
+                transport as "transport: Transport",
+                region
+            "#,
+            self.id as &AgentId,
+            self.old_session_id as SessionId,
+            self.session_id as SessionId,
+            self.handle_id as HandleId,
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 pub struct DeleteQuery<'a> {
     id: &'a AgentId,
     session_id: SessionId,
@@ -214,6 +349,36 @@ impl<'a> DeleteQuery<'a> {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Draining backends aren't selected for new rooms while the rooms already bound to them
+/// keep working, so the backend could be taken down for maintenance once they are over.
+pub struct SetDrainingQuery<'a> {
+    id: &'a AgentId,
+    draining: bool,
+}
+
+impl<'a> SetDrainingQuery<'a> {
+    pub fn new(id: &'a AgentId, draining: bool) -> Self {
+        Self { id, draining }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            UPDATE janus_backend
+            SET draining = $2
+            WHERE id = $1
+            "#,
+            self.id as &AgentId,
+            self.draining
+        )
+        .execute(conn)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 // Returns the most loaded backend capable to host the room with its reserve considering:
 // - room opening period;
 // - actual number of online agents;
@@ -224,6 +389,7 @@ impl<'a> DeleteQuery<'a> {
 pub async fn most_loaded(
     room_id: db::room::Id,
     group: Option<&str>,
+    region: Option<&str>,
     conn: &mut sqlx::PgConnection,
 ) -> sqlx::Result<Option<Object>> {
     sqlx::query_as!(
@@ -272,7 +438,9 @@ pub async fn most_loaded(
             jb.balancer_capacity,
             jb.api_version,
             jb."group",
-            jb.janus_url
+            jb.janus_url,
+            jb.transport as "transport: Transport",
+            jb.region
         FROM janus_backend AS jb
         LEFT JOIN janus_backend_load AS jbl
         ON jbl.backend_id = jb.id
@@ -282,19 +450,24 @@ pub async fn most_loaded(
         AND   COALESCE(jb.balancer_capacity, jb.capacity, 2147483647) - COALESCE(jbl.load, 0) >= COALESCE(r2.reserve, 1)
         AND   jb.api_version = $2
         AND   ($3::text IS NULL OR jb."group" = $3::text)
+        AND   ($4::text IS NULL OR jb.region = $4::text)
+        AND   NOT jb.draining
         ORDER BY COALESCE(jbl.load, 0) DESC, RANDOM()
         LIMIT 1
         "#,
         room_id as db::room::Id,
         JANUS_API_VERSION,
         group,
+        region,
     ).fetch_optional(conn).await
 }
 
 // The same as above but finds the least loaded backend instead without considering the reserve.
+// Draining backends are skipped by both and both are limited to the `region` when it's given.
 pub async fn least_loaded(
     room_id: db::room::Id,
     group: Option<&str>,
+    region: Option<&str>,
     conn: &mut sqlx::PgConnection,
 ) -> sqlx::Result<Option<Object>> {
     sqlx::query_as!(
@@ -343,6 +516,8 @@ pub async fn least_loaded(
                 WHERE r2.id = $1
                 AND   jb.api_version = $2
                 AND   ($3::text IS NULL OR jb."group" = $3::text)
+                AND   ($4::text IS NULL OR jb.region = $4::text)
+                AND   NOT jb.draining
                 ORDER BY
                     COALESCE(jb.balancer_capacity, jb.capacity, 2147483647) - COALESCE(jbl.load, 0) DESC
                 LIMIT 3
@@ -356,7 +531,9 @@ pub async fn least_loaded(
             balancer_capacity,
             api_version,
             "group",
-            janus_url
+            janus_url,
+            transport as "transport: Transport",
+            region
         FROM least_loaded
         ORDER BY RANDOM()
         LIMIT 1
@@ -364,6 +541,7 @@ pub async fn least_loaded(
         room_id as db::room::Id,
         JANUS_API_VERSION,
         group,
+        region,
     )
     .fetch_optional(conn)
     .await
@@ -371,6 +549,137 @@ pub async fn least_loaded(
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// A backend along with its load counted the same way as in `most_loaded`.
+#[derive(Debug)]
+pub struct LoadedBackend {
+    pub backend: Object,
+    pub load: f64,
+    /// Balancer capacity or capacity if the former is not set. `i32::MAX` if neither is set.
+    pub capacity: i32,
+}
+
+impl LoadedBackend {
+    pub fn free_capacity(&self) -> f64 {
+        f64::from(self.capacity) - self.load
+    }
+}
+
+struct LoadedBackendRow {
+    id: AgentId,
+    handle_id: HandleId,
+    session_id: SessionId,
+    created_at: DateTime<Utc>,
+    capacity: Option<i32>,
+    balancer_capacity: Option<i32>,
+    api_version: String,
+    group: Option<String>,
+    janus_url: String,
+    transport: Transport,
+    region: Option<String>,
+    load: f64,
+    effective_capacity: i32,
+}
+
+impl From<LoadedBackendRow> for LoadedBackend {
+    fn from(row: LoadedBackendRow) -> Self {
+        Self {
+            backend: Object {
+                id: row.id,
+                handle_id: row.handle_id,
+                session_id: row.session_id,
+                created_at: row.created_at,
+                capacity: row.capacity,
+                balancer_capacity: row.balancer_capacity,
+                api_version: row.api_version,
+                group: row.group,
+                janus_url: row.janus_url,
+                transport: row.transport,
+                region: row.region,
+            },
+            load: row.load,
+            capacity: row.effective_capacity,
+        }
+    }
+}
+
+// Returns every backend that could be chosen for a room with its load, ordered by id.
+// Draining backends are skipped. Backends are limited to the `region` when it's given.
+pub async fn list_with_load(
+    group: Option<&str>,
+    region: Option<&str>,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Vec<LoadedBackend>> {
+    sqlx::query_as!(
+        LoadedBackendRow,
+        r#"
+        WITH
+            room_load AS (
+                SELECT
+                    a.room_id,
+                    SUM(COALESCE(rwc.video_remb, 1000000) / 1000000.0) AS taken
+                FROM agent AS a
+                INNER JOIN agent_connection AS ac
+                ON ac.agent_id = a.id
+                LEFT JOIN rtc_writer_config AS rwc
+                ON rwc.rtc_id = ac.rtc_id
+                GROUP BY a.room_id
+            ),
+            active_room AS (
+                SELECT *
+                FROM room
+                WHERE backend_id IS NOT NULL
+                AND   time @> NOW()
+            ),
+            janus_backend_load AS (
+                SELECT
+                    backend_id,
+                    SUM(GREATEST(taken, reserve)) AS load
+                FROM (
+                    SELECT DISTINCT ON(backend_id, room_id)
+                        ar.backend_id,
+                        ar.id                   AS room_id,
+                        COALESCE(rl.taken, 0)   AS taken,
+                        COALESCE(ar.reserve, 0) AS reserve
+                    FROM active_room AS ar
+                    LEFT JOIN room_load AS rl
+                    ON rl.room_id = ar.id
+                ) AS sub
+                GROUP BY backend_id
+            )
+        SELECT
+            jb.id as "id: AgentId",
+            jb.handle_id as "handle_id: HandleId",
+            jb.session_id as "session_id: SessionId",
+            jb.created_at,
+            jb.capacity,
+            jb.balancer_capacity,
+            jb.api_version,
+            jb."group",
+            jb.janus_url,
+            jb.transport as "transport: Transport",
+            jb.region,
+            COALESCE(jbl.load, 0)::float8 AS "load!: f64",
+            COALESCE(jb.balancer_capacity, jb.capacity, 2147483647) AS "effective_capacity!: i32"
+        FROM janus_backend AS jb
+        LEFT JOIN janus_backend_load AS jbl
+        ON jbl.backend_id = jb.id
+        WHERE jb.api_version = $1
+        AND   ($2::text IS NULL OR jb."group" = $2::text)
+        AND   ($3::text IS NULL OR jb.region = $3::text)
+        AND   NOT jb.draining
+        ORDER BY jb.id
+        "#,
+        JANUS_API_VERSION,
+        group,
+        region,
+    )
+    .fetch_all(conn)
+    .await
+    .map(|rows| rows.into_iter().map(LoadedBackend::from).collect())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 struct FreeCapacityQueryRow {
     free_capacity: i32,
 }
@@ -560,6 +869,38 @@ pub async fn reserve_load_for_each_backend(
     .await
 }
 
+#[derive(Debug)]
+pub struct DrainingLoad {
+    pub backend_id: AgentId,
+    pub rooms: i64,
+    pub connections: i64,
+}
+
+/// Returns the number of open rooms and agents' connections left on each draining backend.
+pub async fn draining_load(conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<DrainingLoad>> {
+    sqlx::query_as!(
+        DrainingLoad,
+        r#"
+        SELECT
+            jb.id AS "backend_id: AgentId",
+            COUNT(DISTINCT r.id) AS "rooms!: i64",
+            COUNT(ac.agent_id) AS "connections!: i64"
+        FROM janus_backend AS jb
+        LEFT JOIN room AS r
+        ON  r.backend_id = jb.id
+        AND r.time @> NOW()
+        LEFT JOIN agent AS a
+        ON a.room_id = r.id
+        LEFT JOIN agent_connection AS ac
+        ON ac.agent_id = a.id
+        WHERE jb.draining
+        GROUP BY jb.id
+        "#
+    )
+    .fetch_all(conn)
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{Duration, Utc};
@@ -658,4 +999,97 @@ mod tests {
                 assert_eq!(b.load, *expected_load as i64);
             });
     }
+
+    #[sqlx::test]
+    async fn draining_backend_is_not_selected(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let room = shared_helpers::insert_room(&mut conn).await;
+
+        super::SetDrainingQuery::new(backend.id(), true)
+            .execute(&mut conn)
+            .await
+            .expect("Failed to set draining");
+
+        let most_loaded = super::most_loaded(room.id(), None, None, &mut conn)
+            .await
+            .expect("Db query failed");
+        assert!(most_loaded.is_none());
+
+        let least_loaded = super::least_loaded(room.id(), None, None, &mut conn)
+            .await
+            .expect("Db query failed");
+        assert!(least_loaded.is_none());
+
+        super::SetDrainingQuery::new(backend.id(), false)
+            .execute(&mut conn)
+            .await
+            .expect("Failed to unset draining");
+
+        let most_loaded = super::most_loaded(room.id(), None, None, &mut conn)
+            .await
+            .expect("Db query failed")
+            .expect("No backend selected");
+        assert_eq!(most_loaded.id(), backend.id());
+    }
+
+    #[sqlx::test]
+    async fn draining_load(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let draining_backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let backend = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        for backend in [&draining_backend, &backend] {
+            let room = shared_helpers::insert_room_with_backend_id(&mut conn, backend.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+
+            for label in ["user1", "user2"] {
+                let agent = TestAgent::new("web", label, USR_AUDIENCE);
+
+                shared_helpers::insert_connected_agent(
+                    &mut conn,
+                    agent.agent_id(),
+                    room.id(),
+                    rtc.id(),
+                )
+                .await;
+            }
+        }
+
+        super::SetDrainingQuery::new(draining_backend.id(), true)
+            .execute(&mut conn)
+            .await
+            .expect("Failed to set draining");
+
+        let loads = super::draining_load(&mut conn)
+            .await
+            .expect("Db query failed");
+
+        assert_eq!(loads.len(), 1);
+        assert_eq!(&loads[0].backend_id, draining_backend.id());
+        assert_eq!(loads[0].rooms, 1);
+        assert_eq!(loads[0].connections, 2);
+    }
 }
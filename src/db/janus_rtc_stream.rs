@@ -27,10 +27,15 @@ pub struct Object {
     time: Option<TimePg>,
     #[serde(with = "ts_seconds")]
     created_at: DateTime<Utc>,
+    /// Whether Janus is receiving the publisher's audio. Unknown until Janus reports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio_receiving: Option<bool>,
+    /// Whether Janus is receiving the publisher's video. Unknown until Janus reports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    video_receiving: Option<bool>,
 }
 
 impl Object {
-    #[cfg(test)]
     pub fn id(&self) -> Id {
         self.id
     }
@@ -107,6 +112,13 @@ impl ListQuery {
         }
     }
 
+    pub fn active(self, active: bool) -> Self {
+        Self {
+            active: Some(active),
+            ..self
+        }
+    }
+
     pub fn offset(self, offset: i64) -> Self {
         Self {
             offset: Some(offset),
@@ -133,7 +145,9 @@ impl ListQuery {
                 janus_rtc_stream.created_at,
                 janus_rtc_stream.label,
                 janus_rtc_stream.sent_by as "sent_by: AgentId",
-                janus_rtc_stream.time as "time: TimePg"
+                janus_rtc_stream.time as "time: TimePg",
+                janus_rtc_stream.audio_receiving,
+                janus_rtc_stream.video_receiving
             FROM janus_rtc_stream
             INNER JOIN rtc
             ON rtc.id = janus_rtc_stream.rtc_id
@@ -213,7 +227,9 @@ impl<'a> InsertQuery<'a> {
                 created_at,
                 label,
                 sent_by as "sent_by: AgentId",
-                time as "time: TimePg"
+                time as "time: TimePg",
+                audio_receiving,
+                video_receiving
             "#,
             self.id as Id,
             self.handle_id as HandleId,
@@ -249,7 +265,9 @@ pub async fn start(
             created_at,
             label,
             sent_by as "sent_by: AgentId",
-            time as "time: TimePg"
+            time as "time: TimePg",
+            audio_receiving,
+            video_receiving
         "#,
         id as Id,
     )
@@ -288,9 +306,59 @@ pub async fn stop(
             created_at,
             label,
             sent_by as "sent_by: AgentId",
-            time as "time: TimePg"
+            time as "time: TimePg",
+            audio_receiving,
+            video_receiving
+        "#,
+        id as Id,
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Audio,
+    Video,
+}
+
+/// Sets whether Janus is receiving the stream's audio or video.
+///
+/// Returns the stream only if the state has actually changed.
+pub async fn update_media(
+    id: db::janus_rtc_stream::Id,
+    kind: MediaKind,
+    receiving: bool,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Option<Object>> {
+    let is_audio = kind == MediaKind::Audio;
+
+    sqlx::query_as!(
+        Object,
+        r#"
+        UPDATE janus_rtc_stream
+        SET
+            audio_receiving = (CASE WHEN $2 THEN $3 ELSE audio_receiving END),
+            video_receiving = (CASE WHEN $2 THEN video_receiving ELSE $3 END)
+        WHERE
+            id = $1 AND
+            (CASE WHEN $2 THEN audio_receiving ELSE video_receiving END) IS DISTINCT FROM $3
+        RETURNING
+            id as "id: db::id::Id",
+            handle_id as "handle_id: HandleId",
+            rtc_id as "rtc_id: Id",
+            backend_id as "backend_id: AgentId",
+            created_at,
+            label,
+            sent_by as "sent_by: AgentId",
+            time as "time: TimePg",
+            audio_receiving,
+            video_receiving
         "#,
         id as Id,
+        is_audio,
+        receiving,
     )
     .fetch_optional(conn)
     .await
@@ -306,6 +374,8 @@ pub struct StreamWithRoomId {
     pub sent_by: AgentId,
     pub time: Option<TimePg>,
     pub created_at: DateTime<Utc>,
+    pub audio_receiving: Option<bool>,
+    pub video_receiving: Option<bool>,
     pub room_id: db::room::Id,
 }
 
@@ -320,6 +390,8 @@ impl StreamWithRoomId {
             sent_by: self.sent_by.clone(),
             time: self.time.clone(),
             created_at: self.created_at,
+            audio_receiving: self.audio_receiving,
+            video_receiving: self.video_receiving,
         }
     }
 }
@@ -357,6 +429,8 @@ pub async fn stop_running_streams_by_backend(
             "janus_rtc_stream"."label",
             "janus_rtc_stream"."sent_by" as "sent_by: AgentId",
             "janus_rtc_stream"."time" as "time: TimePg",
+            "janus_rtc_stream"."audio_receiving",
+            "janus_rtc_stream"."video_receiving",
             "rtc"."room_id" as "room_id: Id"
         "#,
         backend_id as &AgentId
@@ -381,7 +455,9 @@ pub async fn get_rtc_stream(
             "janus_rtc_stream"."created_at",
             "janus_rtc_stream"."label",
             "janus_rtc_stream"."sent_by" as "sent_by: AgentId",
-            "janus_rtc_stream"."time" as "time: TimePg"
+            "janus_rtc_stream"."time" as "time: TimePg",
+            "janus_rtc_stream"."audio_receiving",
+            "janus_rtc_stream"."video_receiving"
         FROM janus_rtc_stream
         WHERE
             id = $1
@@ -17,15 +17,23 @@ pub async fn create_pool(
         .expect("Failed to create sqlx database pool")
 }
 
+pub mod advisory_lock;
 pub mod agent;
 pub mod agent_connection;
+pub mod agent_role;
+pub mod attendance;
 pub mod group_agent;
 pub mod id;
 pub mod janus_backend;
 pub mod janus_rtc_stream;
 pub mod orphaned_room;
+pub mod raised_hand;
 pub mod recording;
 pub mod room;
+pub mod room_audit;
+pub mod room_ban;
+pub mod room_closing_warning;
+pub mod room_template;
 pub mod rtc;
 pub mod rtc_reader_config;
 pub mod rtc_writer_config;
@@ -49,6 +49,12 @@ struct TimedOutRow {
     rtc_sharing_policy: super::rtc::SharingPolicy,
     infinite: bool,
     closed_by: Option<AgentId>,
+    template_id: Option<super::room::Id>,
+    parent_id: Option<super::room::Id>,
+    co_hosts: Vec<AgentId>,
+    locked: bool,
+    lobby: bool,
+    preferred_region: Option<String>,
 }
 
 impl TimedOutRow {
@@ -72,7 +78,13 @@ impl TimedOutRow {
                 host: self.host,
                 timed_out: self.timed_out,
                 closed_by: self.closed_by,
+                template_id: self.template_id,
+                parent_id: self.parent_id,
+                co_hosts: self.co_hosts,
+                locked: self.locked,
+                lobby: self.lobby,
                 infinite: self.infinite,
+                preferred_region: self.preferred_region,
             }),
         )
     }
@@ -100,7 +112,13 @@ pub async fn get_timed_out(
             r.backend as "backend: super::room::RoomBackend",
             r.rtc_sharing_policy as "rtc_sharing_policy: super::rtc::SharingPolicy",
             r.infinite,
-            r.closed_by as "closed_by: AgentId"
+            r.closed_by as "closed_by: AgentId",
+            r.template_id as "template_id: super::room::Id",
+            r.parent_id as "parent_id: super::room::Id",
+            r.co_hosts as "co_hosts: Vec<AgentId>",
+            r.locked,
+            r.lobby,
+            r.preferred_region
         FROM orphaned_room as orph
         LEFT JOIN room as r
         ON r.id = orph.id
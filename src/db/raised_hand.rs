@@ -0,0 +1,116 @@
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use svc_agent::AgentId;
+
+use crate::db;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Object {
+    agent_id: AgentId,
+    #[serde(with = "ts_seconds")]
+    created_at: DateTime<Utc>,
+}
+
+impl Object {
+    pub fn agent_id(&self) -> &AgentId {
+        &self.agent_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Puts the agent to the end of the room's queue. Keeps the position if the hand is already raised.
+#[derive(Debug)]
+pub struct InsertQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+}
+
+impl<'a> InsertQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId) -> Self {
+        Self { room_id, agent_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO raised_hand (room_id, agent_id)
+            VALUES ($1, $2)
+            ON CONFLICT (room_id, agent_id) DO NOTHING
+            "#,
+            self.room_id as db::room::Id,
+            self.agent_id as &AgentId,
+        )
+        .execute(conn)
+        .await
+        .map(|_| ())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct DeleteQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+}
+
+impl<'a> DeleteQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId) -> Self {
+        Self { room_id, agent_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            DELETE FROM raised_hand
+            WHERE
+                room_id = $1 AND
+                agent_id = $2
+            "#,
+            self.room_id as db::room::Id,
+            self.agent_id as &AgentId,
+        )
+        .execute(conn)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Lists the room's queue in the order the hands have been raised.
+#[derive(Debug)]
+pub struct ListQuery {
+    room_id: db::room::Id,
+}
+
+impl ListQuery {
+    pub fn new(room_id: db::room::Id) -> Self {
+        Self { room_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                agent_id as "agent_id: AgentId",
+                created_at
+            FROM raised_hand
+            WHERE
+                room_id = $1
+            ORDER BY created_at
+            "#,
+            self.room_id as db::room::Id,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
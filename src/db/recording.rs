@@ -2,6 +2,7 @@ use std::{fmt, ops::Bound};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use svc_agent::AgentId;
 
 use crate::db;
 
@@ -208,3 +209,327 @@ impl UpdateQuery {
         .await
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A part of the recording stored on a single backend.
+///
+/// The recording gets split when the room moves to another backend in the middle of it
+/// so every part is uploaded by its own backend.
+#[derive(Debug)]
+pub struct Part {
+    status: Status,
+    mjr_dumps_uris: Option<Vec<String>>,
+}
+
+impl Part {
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct InsertPartQuery<'a> {
+    rtc_id: db::rtc::Id,
+    backend_id: &'a AgentId,
+}
+
+impl<'a> InsertPartQuery<'a> {
+    pub fn new(rtc_id: db::rtc::Id, backend_id: &'a AgentId) -> Self {
+        Self { rtc_id, backend_id }
+    }
+
+    /// Does nothing if the backend already holds a part of the recording.
+    pub async fn execute(self, conn: &mut sqlx::PgConnection) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO recording_part (rtc_id, backend_id)
+            VALUES ($1, $2)
+            ON CONFLICT (rtc_id, backend_id) DO NOTHING
+            "#,
+            self.rtc_id as db::rtc::Id,
+            self.backend_id as &AgentId,
+        )
+        .execute(conn)
+        .await
+        .map(|_| ())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct ListPartsQuery {
+    rtc_id: db::rtc::Id,
+}
+
+impl ListPartsQuery {
+    pub fn new(rtc_id: db::rtc::Id) -> Self {
+        Self { rtc_id }
+    }
+
+    pub async fn execute(self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Part>> {
+        sqlx::query_as!(
+            Part,
+            r#"
+            SELECT
+                status as "status: Status",
+                mjr_dumps_uris
+            FROM recording_part
+            WHERE
+                rtc_id = $1
+            ORDER BY created_at
+            "#,
+            self.rtc_id as db::rtc::Id,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct UpdatePartQuery<'a> {
+    rtc_id: db::rtc::Id,
+    backend_id: &'a AgentId,
+    status: Option<Status>,
+    mjr_dumps_uris: Option<Vec<String>>,
+}
+
+impl<'a> UpdatePartQuery<'a> {
+    pub fn new(rtc_id: db::rtc::Id, backend_id: &'a AgentId) -> Self {
+        Self {
+            rtc_id,
+            backend_id,
+            status: None,
+            mjr_dumps_uris: None,
+        }
+    }
+
+    pub fn status(self, status: Status) -> Self {
+        Self {
+            status: Some(status),
+            ..self
+        }
+    }
+
+    pub fn mjr_dumps_uris(self, mjr_dumps_uris: Vec<String>) -> Self {
+        Self {
+            mjr_dumps_uris: Some(mjr_dumps_uris),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Part> {
+        sqlx::query_as!(
+            Part,
+            r#"
+            UPDATE recording_part
+            SET
+                status = $1,
+                mjr_dumps_uris = $2
+            WHERE
+                rtc_id = $3 AND
+                backend_id = $4 AND
+                -- do not overwrite existing `ready` status with `missing`
+                (
+                    $1 <> 'missing'::recording_status OR
+                    status = 'in_progress'
+                )
+            RETURNING
+                status as "status: Status",
+                mjr_dumps_uris
+            "#,
+            self.status as Option<Status>,
+            self.mjr_dumps_uris.as_ref().map(|m| m.as_slice()),
+            self.rtc_id as db::rtc::Id,
+            self.backend_id as &AgentId,
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Marks in-progress parts of the recordings stored on the backend as missing.
+///
+/// Used when the backend has gone so there's nobody to upload these parts.
+#[derive(Debug)]
+pub struct MarkPartsMissingQuery<'a> {
+    backend_id: &'a AgentId,
+}
+
+impl<'a> MarkPartsMissingQuery<'a> {
+    pub fn new(backend_id: &'a AgentId) -> Self {
+        Self { backend_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            UPDATE recording_part
+            SET status = 'missing'::recording_status
+            WHERE
+                backend_id = $1 AND
+                status = 'in_progress'
+            "#,
+            self.backend_id as &AgentId,
+        )
+        .execute(conn)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Returns in-progress recordings of the finished rooms which parts are neither uploading
+/// nor awaiting upload anymore.
+///
+/// That's the case when the backend holding the last pending part has gone, so vacuum
+/// has nothing to upload and has to complete such recordings by itself.
+pub async fn finished_with_settled_parts(
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Vec<db::rtc::Id>> {
+    sqlx::query!(
+        r#"
+        SELECT recording.rtc_id as "rtc_id: db::rtc::Id"
+        FROM recording
+        INNER JOIN rtc
+        ON rtc.id = recording.rtc_id
+        INNER JOIN room
+        ON room.id = rtc.room_id
+        WHERE
+            room.rtc_sharing_policy = ANY(ARRAY ['shared'::rtc_sharing_policy, 'owned']) AND
+            upper(room.time) < now() AND
+            recording.status = 'in_progress' AND
+            EXISTS (
+                SELECT 1 FROM recording_part
+                WHERE recording_part.rtc_id = recording.rtc_id
+            ) AND
+            NOT EXISTS (
+                SELECT 1 FROM recording_part
+                WHERE
+                    recording_part.rtc_id = recording.rtc_id AND
+                    recording_part.status = 'in_progress'
+            )
+        "#,
+    )
+    .fetch_all(conn)
+    .await
+    .map(|rows| rows.into_iter().map(|r| r.rtc_id).collect())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Completes the recording once every its part has been uploaded or turned out missing.
+///
+/// The recording is ready if at least one part is ready. Dumps of the ready parts go
+/// in the order the parts were recorded. Returns `None` while some parts are still
+/// in progress.
+pub async fn complete_from_parts(
+    rtc_id: db::rtc::Id,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Option<Object>> {
+    let parts = ListPartsQuery::new(rtc_id).execute(conn).await?;
+
+    if parts.iter().any(|p| p.status() == Status::InProgress) {
+        return Ok(None);
+    }
+
+    let ready_parts = parts
+        .into_iter()
+        .filter(|p| p.status() == Status::Ready)
+        .collect::<Vec<_>>();
+
+    let query = if ready_parts.is_empty() {
+        UpdateQuery::new(rtc_id).status(Status::Missing)
+    } else {
+        let mjr_dumps_uris = ready_parts
+            .into_iter()
+            .flat_map(|p| p.mjr_dumps_uris.unwrap_or_default())
+            .collect();
+
+        UpdateQuery::new(rtc_id)
+            .status(Status::Ready)
+            .mjr_dumps_uris(mjr_dumps_uris)
+    };
+
+    query.execute(conn).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backend::janus::client::{HandleId, SessionId},
+        test_helpers::{db::TestDb, prelude::*},
+    };
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn complete_from_parts(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+
+        let backend1 = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let backend2 = shared_helpers::insert_janus_backend(
+            &mut conn,
+            "test",
+            SessionId::random(),
+            HandleId::random(),
+        )
+        .await;
+
+        let room = shared_helpers::insert_room_with_backend_id(&mut conn, backend1.id()).await;
+        let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+        shared_helpers::insert_recording(&mut conn, &rtc).await;
+
+        // The room has moved to another backend in the middle of the recording.
+        InsertPartQuery::new(rtc.id(), backend2.id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to insert recording part");
+
+        UpdatePartQuery::new(rtc.id(), backend2.id())
+            .status(Status::Ready)
+            .mjr_dumps_uris(vec!["dump2".to_owned()])
+            .execute(&mut conn)
+            .await
+            .expect("Failed to update recording part");
+
+        let recording = super::complete_from_parts(rtc.id(), &mut conn)
+            .await
+            .expect("Failed to complete recording");
+
+        assert!(recording.is_none());
+
+        UpdatePartQuery::new(rtc.id(), backend1.id())
+            .status(Status::Ready)
+            .mjr_dumps_uris(vec!["dump1".to_owned()])
+            .execute(&mut conn)
+            .await
+            .expect("Failed to update recording part");
+
+        let recording = super::complete_from_parts(rtc.id(), &mut conn)
+            .await
+            .expect("Failed to complete recording")
+            .expect("Recording must be completed");
+
+        assert_eq!(recording.status(), Status::Ready);
+        assert_eq!(
+            recording.mjr_dumps_uris(),
+            Some(&vec!["dump1".to_owned(), "dump2".to_owned()])
+        );
+    }
+}
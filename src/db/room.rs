@@ -8,7 +8,7 @@ use uuid::Uuid;
 
 use crate::{
     backend::janus::{
-        client::{HandleId, SessionId},
+        client::{HandleId, SessionId, Transport},
         JANUS_API_VERSION,
     },
     db::{
@@ -100,10 +100,19 @@ pub struct Object {
     pub rtc_sharing_policy: RtcSharingPolicy,
     pub classroom_id: Uuid,
     pub host: Option<AgentId>,
+    pub co_hosts: Vec<AgentId>,
     pub timed_out: bool,
     pub closed_by: Option<AgentId>,
     #[serde(skip)]
     pub infinite: bool,
+    pub lobby: bool,
+    pub locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Id>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<db::room_template::Id>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_region: Option<String>,
 }
 
 impl Object {
@@ -131,7 +140,6 @@ impl Object {
         }
     }
 
-    #[cfg(test)]
     pub fn tags(&self) -> &JsonValue {
         &self.tags
     }
@@ -152,6 +160,10 @@ impl Object {
         self.host.as_ref()
     }
 
+    pub fn co_hosts(&self) -> &[AgentId] {
+        &self.co_hosts
+    }
+
     #[cfg(test)]
     pub fn timed_out(&self) -> bool {
         self.timed_out
@@ -160,6 +172,27 @@ impl Object {
     pub fn infinite(&self) -> bool {
         self.infinite
     }
+
+    pub fn lobby(&self) -> bool {
+        self.lobby
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn preferred_region(&self) -> Option<&str> {
+        self.preferred_region.as_deref()
+    }
+
+    #[cfg(test)]
+    pub fn parent_id(&self) -> Option<Id> {
+        self.parent_id
+    }
+
+    pub fn template_id(&self) -> Option<db::room_template::Id> {
+        self.template_id
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -200,7 +233,13 @@ impl FindQueryable for FindQuery {
                 backend as "backend: RoomBackend",
                 rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
                 infinite,
-                closed_by as "closed_by: AgentId"
+                closed_by as "closed_by: AgentId",
+                template_id as "template_id: Id",
+                parent_id as "parent_id: Id",
+                co_hosts as "co_hosts: Vec<AgentId>",
+                locked,
+                lobby,
+                preferred_region
             FROM room
             WHERE
                 id = $1
@@ -212,14 +251,78 @@ impl FindQueryable for FindQuery {
     }
 }
 
+/// Finds the room and locks it until the end of the transaction
+/// so concurrent updates of its properties don't overwrite each other.
+#[derive(Debug)]
+pub struct FindForUpdateQuery {
+    id: Id,
+}
+
+impl FindForUpdateQuery {
+    pub fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[async_trait::async_trait]
+impl FindQueryable for FindForUpdateQuery {
+    async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Option<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                id as "id: Id",
+                backend_id as "backend_id: AgentId",
+                time as "time: TimePg",
+                reserve,
+                tags,
+                classroom_id,
+                host as "host: AgentId",
+                timed_out,
+                audience,
+                created_at,
+                backend as "backend: RoomBackend",
+                rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+                infinite,
+                closed_by as "closed_by: AgentId",
+                template_id as "template_id: Id",
+                parent_id as "parent_id: Id",
+                co_hosts as "co_hosts: Vec<AgentId>",
+                locked,
+                lobby,
+                preferred_region
+            FROM room
+            WHERE
+                id = $1
+            FOR UPDATE
+            "#,
+            self.id as Id,
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
 #[derive(Debug)]
 pub struct FindByRtcIdQuery {
     rtc_id: db::rtc::Id,
+    include_deleted: bool,
 }
 
 impl FindByRtcIdQuery {
     pub fn new(rtc_id: db::rtc::Id) -> Self {
-        Self { rtc_id }
+        Self {
+            rtc_id,
+            include_deleted: false,
+        }
+    }
+
+    /// Also finds the room of a deleted RTC, e.g. to upload its recording.
+    pub fn include_deleted(self) -> Self {
+        Self {
+            include_deleted: true,
+            ..self
+        }
     }
 }
 
@@ -243,14 +346,22 @@ impl FindQueryable for FindByRtcIdQuery {
                 r.backend as "backend: RoomBackend",
                 r.rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
                 r.infinite,
-                r.closed_by as "closed_by: AgentId"
+                r.closed_by as "closed_by: AgentId",
+                r.template_id as "template_id: Id",
+                r.parent_id as "parent_id: Id",
+                r.co_hosts as "co_hosts: Vec<AgentId>",
+                r.locked,
+                r.lobby,
+                r.preferred_region
             FROM room as r
             INNER JOIN rtc
             ON r.id = rtc.room_id
             WHERE
-                rtc.id = $1
+                rtc.id = $1 AND
+                ($2 OR rtc.deleted_at IS NULL)
             "#,
             self.rtc_id as db::rtc::Id,
+            self.include_deleted,
         )
         .fetch_optional(conn)
         .await
@@ -259,6 +370,225 @@ impl FindQueryable for FindByRtcIdQuery {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Debug, Default)]
+pub struct ListQuery<'a> {
+    audience: Option<&'a str>,
+    classroom_id: Option<Uuid>,
+    time: Option<Time>,
+    closed: Option<bool>,
+    backend_id: Option<&'a AgentId>,
+    tags: Option<&'a JsonValue>,
+    parent_id: Option<Id>,
+    after: Option<Id>,
+    limit: Option<i64>,
+}
+
+impl<'a> ListQuery<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn audience(self, audience: &'a str) -> Self {
+        Self {
+            audience: Some(audience),
+            ..self
+        }
+    }
+
+    pub fn classroom_id(self, classroom_id: Uuid) -> Self {
+        Self {
+            classroom_id: Some(classroom_id),
+            ..self
+        }
+    }
+
+    pub fn time(self, time: Time) -> Self {
+        Self {
+            time: Some(time),
+            ..self
+        }
+    }
+
+    pub fn closed(self, closed: bool) -> Self {
+        Self {
+            closed: Some(closed),
+            ..self
+        }
+    }
+
+    pub fn backend_id(self, backend_id: &'a AgentId) -> Self {
+        Self {
+            backend_id: Some(backend_id),
+            ..self
+        }
+    }
+
+    pub fn tags(self, tags: &'a JsonValue) -> Self {
+        Self {
+            tags: Some(tags),
+            ..self
+        }
+    }
+
+    pub fn parent_id(self, parent_id: Id) -> Self {
+        Self {
+            parent_id: Some(parent_id),
+            ..self
+        }
+    }
+
+    /// Keyset pagination cursor: returns rooms created after the room with the given id.
+    pub fn after(self, after: Id) -> Self {
+        Self {
+            after: Some(after),
+            ..self
+        }
+    }
+
+    pub fn limit(self, limit: i64) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Object>> {
+        // The time filter is only put into the query when it's given so the planner
+        // doesn't fall back to a sequential scan on the `$3 IS NULL` branch.
+        match self.time {
+            Some(time) => self.execute_with_time(time, conn).await,
+            None => self.execute_without_time(conn).await,
+        }
+    }
+
+    async fn execute_with_time(
+        &self,
+        time: Time,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                id as "id: Id",
+                backend_id as "backend_id: AgentId",
+                time as "time: TimePg",
+                reserve,
+                tags,
+                classroom_id,
+                host as "host: AgentId",
+                timed_out,
+                audience,
+                created_at,
+                backend as "backend: RoomBackend",
+                rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+                infinite,
+                closed_by as "closed_by: AgentId",
+                template_id as "template_id: Id",
+                parent_id as "parent_id: Id",
+                co_hosts as "co_hosts: Vec<AgentId>",
+                locked,
+                lobby,
+                preferred_region
+            FROM room
+            WHERE
+                ($1::text IS NULL      OR audience = $1) AND
+                ($2::uuid IS NULL      OR classroom_id = $2) AND
+                time && $3 AND
+                (
+                    $4::boolean IS NULL OR
+                    -- closed rooms have an upper bound in the past,
+                    -- open rooms are either unbounded or close in the future
+                    $4 = COALESCE(upper(time) <= now(), false)
+                ) AND
+                ($5::agent_id IS NULL  OR backend_id = $5) AND
+                ($6::jsonb IS NULL     OR tags::jsonb @> $6) AND
+                ($7::uuid IS NULL      OR parent_id = $7) AND
+                (
+                    $8::uuid IS NULL OR
+                    (created_at, id) > (SELECT created_at, id FROM room WHERE id = $8)
+                )
+            ORDER BY created_at, id
+            LIMIT $9
+            "#,
+            self.audience,
+            self.classroom_id,
+            TimePg::from(time) as TimePg,
+            self.closed,
+            self.backend_id as Option<&AgentId>,
+            self.tags,
+            self.parent_id as Option<Id>,
+            self.after as Option<Id>,
+            self.limit,
+        )
+        .fetch_all(conn)
+        .await
+    }
+
+    async fn execute_without_time(
+        &self,
+        conn: &mut sqlx::PgConnection,
+    ) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                id as "id: Id",
+                backend_id as "backend_id: AgentId",
+                time as "time: TimePg",
+                reserve,
+                tags,
+                classroom_id,
+                host as "host: AgentId",
+                timed_out,
+                audience,
+                created_at,
+                backend as "backend: RoomBackend",
+                rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+                infinite,
+                closed_by as "closed_by: AgentId",
+                template_id as "template_id: Id",
+                parent_id as "parent_id: Id",
+                co_hosts as "co_hosts: Vec<AgentId>",
+                locked,
+                lobby,
+                preferred_region
+            FROM room
+            WHERE
+                ($1::text IS NULL      OR audience = $1) AND
+                ($2::uuid IS NULL      OR classroom_id = $2) AND
+                (
+                    $3::boolean IS NULL OR
+                    -- closed rooms have an upper bound in the past,
+                    -- open rooms are either unbounded or close in the future
+                    $3 = COALESCE(upper(time) <= now(), false)
+                ) AND
+                ($4::agent_id IS NULL  OR backend_id = $4) AND
+                ($5::jsonb IS NULL     OR tags::jsonb @> $5) AND
+                ($6::uuid IS NULL      OR parent_id = $6) AND
+                (
+                    $7::uuid IS NULL OR
+                    (created_at, id) > (SELECT created_at, id FROM room WHERE id = $7)
+                )
+            ORDER BY created_at, id
+            LIMIT $8
+            "#,
+            self.audience,
+            self.classroom_id,
+            self.closed,
+            self.backend_id as Option<&AgentId>,
+            self.tags,
+            self.parent_id as Option<Id>,
+            self.after as Option<Id>,
+            self.limit,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 struct FinishedInProgressRecordingsRow {
     room_id: Id,
     time: TimePg,
@@ -267,18 +597,25 @@ struct FinishedInProgressRecordingsRow {
     backend: RoomBackend,
     reserve: Option<i32>,
     tags: JsonValue,
-    backend_id: AgentId,
+    room_backend_id: Option<AgentId>,
     rtc_sharing_policy: RtcSharingPolicy,
     classroom_id: sqlx::types::Uuid,
     host: Option<AgentId>,
     timed_out: bool,
     closed_by: Option<AgentId>,
+    template_id: Option<db::room_template::Id>,
+    parent_id: Option<Id>,
+    co_hosts: Vec<AgentId>,
+    locked: bool,
+    lobby: bool,
     infinite: bool,
+    preferred_region: Option<String>,
     rtc_id: db::rtc::Id,
     started_at: Option<DateTime<Utc>>,
     segments: Option<Vec<SegmentPg>>,
     status: RecordingStatus,
     mjr_dumps_uris: Option<Vec<String>>,
+    backend_id: AgentId,
     handle_id: HandleId,
     session_id: SessionId,
     janus_backend_created_at: DateTime<Utc>,
@@ -287,6 +624,8 @@ struct FinishedInProgressRecordingsRow {
     api_version: String,
     group: Option<String>,
     janus_url: String,
+    transport: Transport,
+    region: Option<String>,
 }
 
 impl FinishedInProgressRecordingsRow {
@@ -300,13 +639,19 @@ impl FinishedInProgressRecordingsRow {
                 backend: self.backend,
                 reserve: self.reserve,
                 tags: self.tags,
-                backend_id: Some(self.backend_id.clone()),
+                backend_id: self.room_backend_id,
                 rtc_sharing_policy: self.rtc_sharing_policy,
                 classroom_id: self.classroom_id,
                 host: self.host,
                 timed_out: self.timed_out,
                 closed_by: self.closed_by,
+                template_id: self.template_id,
+                parent_id: self.parent_id,
+                co_hosts: self.co_hosts,
+                locked: self.locked,
+                lobby: self.lobby,
                 infinite: self.infinite,
+                preferred_region: self.preferred_region,
             },
             Recording {
                 rtc_id: self.rtc_id,
@@ -325,6 +670,8 @@ impl FinishedInProgressRecordingsRow {
                 api_version: self.api_version,
                 group: self.group,
                 janus_url: self.janus_url,
+                transport: self.transport,
+                region: self.region,
             },
         )
     }
@@ -338,6 +685,9 @@ impl FinishedInProgressRecordingsRow {
 // room1 | rtc2 | recording1  -> room1 | rtc2 | recording1
 // room2 | rtc3 | recording2     room2 | rtc3 | recording2
 // room3 | rtc4 | null           room3 | null | null
+//
+// A recording split across several backends yields a row for each part left to upload
+// along with the backend holding it.
 pub async fn finished_with_in_progress_recordings(
     conn: &mut sqlx::PgConnection,
     maybe_group: Option<&str>,
@@ -353,18 +703,25 @@ pub async fn finished_with_in_progress_recordings(
             room.backend as "backend: RoomBackend",
             room.reserve,
             room.tags,
-            room.backend_id as "backend_id!: AgentId",
+            room.backend_id as "room_backend_id: AgentId",
             room.rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
             room.classroom_id,
             room.host as "host: AgentId",
             room.timed_out,
             room.closed_by as "closed_by: AgentId",
+            room.template_id as "template_id: Id",
+            room.parent_id as "parent_id: Id",
+            room.co_hosts as "co_hosts: Vec<AgentId>",
+            room.locked,
+            room.lobby,
             room.infinite,
+            room.preferred_region,
             recording.rtc_id as "rtc_id: db::rtc::Id",
             recording.started_at,
             recording.segments as "segments: Vec<SegmentPg>",
             recording.status as "status: RecordingStatus",
             recording.mjr_dumps_uris,
+            janus_backend.id as "backend_id: AgentId",
             janus_backend.handle_id as "handle_id: HandleId",
             janus_backend.session_id as "session_id: SessionId",
             janus_backend.created_at as "janus_backend_created_at: _",
@@ -372,14 +729,19 @@ pub async fn finished_with_in_progress_recordings(
             janus_backend.balancer_capacity,
             janus_backend.api_version,
             janus_backend.group,
-            janus_backend.janus_url
+            janus_backend.janus_url,
+            janus_backend.transport as "transport: Transport",
+            janus_backend.region
         FROM room
         INNER JOIN rtc
         ON room.id = rtc.room_id
         INNER JOIN recording
         ON recording.rtc_id = rtc.id
+        INNER JOIN recording_part
+        ON  recording_part.rtc_id = recording.rtc_id
+        AND recording_part.status = 'in_progress'
         INNER JOIN janus_backend
-        ON janus_backend.id = room.backend_id
+        ON janus_backend.id = recording_part.backend_id
         WHERE
             room.rtc_sharing_policy = ANY(ARRAY ['shared'::rtc_sharing_policy, 'owned']) AND
             janus_backend.api_version = $1 AND
@@ -397,6 +759,90 @@ pub async fn finished_with_in_progress_recordings(
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Returns open rooms bound to the backend.
+pub async fn list_open_by_backend(
+    backend_id: &AgentId,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Vec<Object>> {
+    sqlx::query_as!(
+        Object,
+        r#"
+        SELECT
+            id as "id: Id",
+            backend_id as "backend_id: AgentId",
+            time as "time: TimePg",
+            reserve,
+            tags,
+            classroom_id,
+            host as "host: AgentId",
+            timed_out,
+            audience,
+            created_at,
+            backend as "backend: RoomBackend",
+            rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+            infinite,
+            closed_by as "closed_by: AgentId",
+            template_id as "template_id: Id",
+            parent_id as "parent_id: Id",
+            co_hosts as "co_hosts: Vec<AgentId>",
+            locked,
+            lobby,
+            preferred_region
+        FROM room
+        WHERE
+            backend_id = $1 AND
+            time @> now()
+        "#,
+        backend_id as &AgentId,
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Binds the room to another backend or unbinds it when `backend_id` is `None`
+/// so the backend would be chosen on the next writer's connect.
+pub async fn set_backend_id(
+    id: Id,
+    backend_id: Option<&AgentId>,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Object> {
+    sqlx::query_as!(
+        Object,
+        r#"
+        UPDATE room
+        SET backend_id = $2
+        WHERE id = $1
+        RETURNING
+            id as "id: Id",
+            backend_id as "backend_id: AgentId",
+            time as "time: TimePg",
+            reserve,
+            tags,
+            classroom_id,
+            host as "host: AgentId",
+            timed_out,
+            audience,
+            created_at,
+            backend as "backend: RoomBackend",
+            rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+            infinite,
+            closed_by as "closed_by: AgentId",
+            template_id as "template_id: Id",
+            parent_id as "parent_id: Id",
+            co_hosts as "co_hosts: Vec<AgentId>",
+            locked,
+            lobby,
+            preferred_region
+        "#,
+        id as Id,
+        backend_id as Option<&AgentId>,
+    )
+    .fetch_one(conn)
+    .await
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 #[derive(Debug)]
 pub struct InsertQuery<'a> {
     time: Time,
@@ -408,6 +854,11 @@ pub struct InsertQuery<'a> {
     rtc_sharing_policy: RtcSharingPolicy,
     classroom_id: Uuid,
     infinite: bool,
+    lobby: bool,
+    co_hosts: &'a [AgentId],
+    parent_id: Option<Id>,
+    template_id: Option<db::room_template::Id>,
+    preferred_region: Option<&'a str>,
 }
 
 impl<'a> InsertQuery<'a> {
@@ -427,6 +878,11 @@ impl<'a> InsertQuery<'a> {
             rtc_sharing_policy,
             classroom_id,
             infinite: false,
+            lobby: false,
+            co_hosts: &[],
+            parent_id: None,
+            template_id: None,
+            preferred_region: None,
         }
     }
 
@@ -444,7 +900,28 @@ impl<'a> InsertQuery<'a> {
         }
     }
 
-    #[cfg(test)]
+    pub fn lobby(self, lobby: bool) -> Self {
+        Self { lobby, ..self }
+    }
+
+    pub fn co_hosts(self, co_hosts: &'a [AgentId]) -> Self {
+        Self { co_hosts, ..self }
+    }
+
+    pub fn parent_id(self, parent_id: Id) -> Self {
+        Self {
+            parent_id: Some(parent_id),
+            ..self
+        }
+    }
+
+    pub fn template_id(self, template_id: db::room_template::Id) -> Self {
+        Self {
+            template_id: Some(template_id),
+            ..self
+        }
+    }
+
     pub fn backend_id(self, backend_id: &'a AgentId) -> Self {
         Self {
             backend_id: Some(backend_id),
@@ -452,6 +929,13 @@ impl<'a> InsertQuery<'a> {
         }
     }
 
+    pub fn preferred_region(self, preferred_region: &'a str) -> Self {
+        Self {
+            preferred_region: Some(preferred_region),
+            ..self
+        }
+    }
+
     #[cfg(test)]
     pub fn infinite(self, infinite: bool) -> Self {
         Self { infinite, ..self }
@@ -463,9 +947,10 @@ impl<'a> InsertQuery<'a> {
             r#"
             INSERT INTO room (
                 time, audience, backend, reserve, tags,
-                backend_id, rtc_sharing_policy, classroom_id, infinite
+                backend_id, rtc_sharing_policy, classroom_id, infinite, lobby,
+                co_hosts, parent_id, template_id, preferred_region
             )
-            VALUES ($1, $2, $3, $4, COALESCE($5, '{}'::jsonb), $6, $7, $8, $9)
+            VALUES ($1, $2, $3, $4, COALESCE($5, '{}'::jsonb), $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING
                 id as "id: Id",
                 backend_id as "backend_id: AgentId",
@@ -480,7 +965,13 @@ impl<'a> InsertQuery<'a> {
                 backend as "backend: RoomBackend",
                 rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
                 infinite,
-                closed_by as "closed_by: AgentId"
+                closed_by as "closed_by: AgentId",
+                template_id as "template_id: Id",
+                parent_id as "parent_id: Id",
+                co_hosts as "co_hosts: Vec<AgentId>",
+                locked,
+                lobby,
+                preferred_region
             "#,
             TimePg::from(self.time) as TimePg,
             self.audience,
@@ -491,6 +982,11 @@ impl<'a> InsertQuery<'a> {
             self.rtc_sharing_policy as RtcSharingPolicy,
             self.classroom_id,
             self.infinite,
+            self.lobby,
+            self.co_hosts as &[AgentId],
+            self.parent_id as Option<Id>,
+            self.template_id as Option<db::room_template::Id>,
+            self.preferred_region,
         )
         .fetch_one(conn)
         .await
@@ -509,6 +1005,9 @@ pub struct UpdateQuery<'a> {
     classroom_id: Option<Uuid>,
     host: Option<&'a AgentId>,
     timed_out: Option<bool>,
+    lobby: Option<bool>,
+    locked: Option<bool>,
+    co_hosts: Option<&'a [AgentId]>,
 }
 
 impl<'a> UpdateQuery<'a> {
@@ -522,6 +1021,9 @@ impl<'a> UpdateQuery<'a> {
             classroom_id: Default::default(),
             host: Default::default(),
             timed_out: Default::default(),
+            lobby: Default::default(),
+            locked: Default::default(),
+            co_hosts: Default::default(),
         }
     }
 
@@ -559,6 +1061,18 @@ impl<'a> UpdateQuery<'a> {
         Self { host, ..self }
     }
 
+    pub fn lobby(self, lobby: Option<bool>) -> Self {
+        Self { lobby, ..self }
+    }
+
+    pub fn locked(self, locked: Option<bool>) -> Self {
+        Self { locked, ..self }
+    }
+
+    pub fn co_hosts(self, co_hosts: Option<&'a [AgentId]>) -> Self {
+        Self { co_hosts, ..self }
+    }
+
     pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
         sqlx::query_as!(
             Object,
@@ -571,7 +1085,10 @@ impl<'a> UpdateQuery<'a> {
                 tags         = COALESCE($5, tags::jsonb),
                 classroom_id = COALESCE($6, classroom_id),
                 host         = COALESCE($7, host),
-                timed_out    = COALESCE($8, timed_out)
+                timed_out    = COALESCE($8, timed_out),
+                lobby        = COALESCE($9, lobby),
+                locked       = COALESCE($10, locked),
+                co_hosts     = COALESCE($11, co_hosts)
             WHERE
                 id = $1
             RETURNING
@@ -588,7 +1105,13 @@ impl<'a> UpdateQuery<'a> {
                 backend as "backend: RoomBackend",
                 rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
                 infinite,
-                closed_by as "closed_by: AgentId"
+                closed_by as "closed_by: AgentId",
+                template_id as "template_id: Id",
+                parent_id as "parent_id: Id",
+                co_hosts as "co_hosts: Vec<AgentId>",
+                locked,
+                lobby,
+                preferred_region
             "#,
             self.id as db::room::Id,
             self.backend_id as Option<&AgentId>,
@@ -597,7 +1120,10 @@ impl<'a> UpdateQuery<'a> {
             self.tags,
             self.classroom_id,
             self.host as Option<&AgentId>,
-            self.timed_out
+            self.timed_out,
+            self.lobby,
+            self.locked,
+            self.co_hosts as Option<&[AgentId]>,
         )
         .fetch_one(conn)
         .await
@@ -634,7 +1160,13 @@ pub async fn set_closed_by(
             backend as "backend: RoomBackend",
             rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
             infinite,
-            closed_by as "closed_by: AgentId"
+            closed_by as "closed_by: AgentId",
+            template_id as "template_id: Id",
+            parent_id as "parent_id: Id",
+            co_hosts as "co_hosts: Vec<AgentId>",
+            locked,
+            lobby,
+            preferred_region
         "#,
         room_id as Id,
         agent as &AgentId,
@@ -645,6 +1177,69 @@ pub async fn set_closed_by(
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Passes the host role to the first co-host in the list who is currently present in the room.
+///
+/// Does nothing and returns `None` when the room host is not `host_left` anymore
+/// or there's no co-host in the room.
+pub async fn promote_co_host(
+    room_id: Id,
+    host_left: &AgentId,
+    conn: &mut sqlx::PgConnection,
+) -> sqlx::Result<Option<Object>> {
+    sqlx::query_as!(
+        Object,
+        r#"
+        UPDATE room
+        SET
+            host = next_host.agent_id
+        FROM (
+            SELECT r.co_hosts[position] AS agent_id
+            FROM room AS r,
+                GENERATE_SUBSCRIPTS(r.co_hosts, 1) AS position
+            INNER JOIN agent AS a
+            ON a.room_id = $1
+            WHERE
+                r.id = $1 AND
+                a.agent_id = r.co_hosts[position] AND
+                a.status = 'ready' AND
+                r.co_hosts[position] <> $2
+            ORDER BY position
+            LIMIT 1
+        ) AS next_host
+        WHERE
+            room.id = $1 AND
+            room.host = $2
+        RETURNING
+            room.id as "id: Id",
+            room.backend_id as "backend_id: AgentId",
+            room.time as "time: TimePg",
+            room.reserve,
+            room.tags,
+            room.classroom_id,
+            room.host as "host: AgentId",
+            room.timed_out,
+            room.audience,
+            room.created_at,
+            room.backend as "backend: RoomBackend",
+            room.rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+            room.infinite,
+            room.closed_by as "closed_by: AgentId",
+            room.template_id as "template_id: Id",
+            room.parent_id as "parent_id: Id",
+            room.co_hosts as "co_hosts: Vec<AgentId>",
+            room.locked,
+            room.lobby,
+            room.preferred_region
+        "#,
+        room_id as Id,
+        host_left as &AgentId,
+    )
+    .fetch_optional(conn)
+    .await
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 #[cfg(test)]
 mod tests {
     mod finished_with_in_progress_recordings {
@@ -718,6 +1313,59 @@ mod tests {
             }
         }
 
+        #[sqlx::test]
+        async fn selects_backend_for_each_recording_part(pool: sqlx::PgPool) {
+            let db = db::TestDb::new(pool);
+            let mut conn = db.get_conn().await;
+
+            let backend1 = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "test",
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+            let backend2 = shared_helpers::insert_janus_backend(
+                &mut conn,
+                "test",
+                SessionId::random(),
+                HandleId::random(),
+            )
+            .await;
+
+            let room =
+                shared_helpers::insert_closed_room_with_backend_id(&mut conn, backend1.id()).await;
+            let rtc = shared_helpers::insert_rtc_with_room(&mut conn, &room).await;
+            shared_helpers::insert_recording(&mut conn, &rtc).await;
+
+            crate::db::recording::InsertPartQuery::new(rtc.id(), backend2.id())
+                .execute(&mut conn)
+                .await
+                .expect("Failed to insert recording part");
+
+            let mut rooms = finished_with_in_progress_recordings(&mut conn, None)
+                .await
+                .expect("finished_with_in_progress_recordings call failed");
+
+            assert_eq!(rooms.len(), 2);
+
+            // Parts that have already been uploaded are skipped.
+            crate::db::recording::UpdatePartQuery::new(rtc.id(), backend1.id())
+                .status(RecordingStatus::Ready)
+                .execute(&mut conn)
+                .await
+                .expect("Failed to update recording part");
+
+            rooms = finished_with_in_progress_recordings(&mut conn, None)
+                .await
+                .expect("finished_with_in_progress_recordings call failed");
+
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].0.id(), room.id());
+            assert_eq!(rooms[0].0.backend_id(), Some(backend1.id()));
+            assert_eq!(rooms[0].2.id(), backend2.id());
+        }
+
         #[sqlx::test]
         async fn selects_appropriate_backend_by_group(pool: sqlx::PgPool) {
             let db = db::TestDb::new(pool);
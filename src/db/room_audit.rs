@@ -0,0 +1,219 @@
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use svc_agent::AgentId;
+
+use crate::db;
+
+pub type Id = db::id::Id;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Object {
+    id: Id,
+    room_id: db::room::Id,
+    action: String,
+    created_by: AgentId,
+    before: Option<JsonValue>,
+    after: Option<JsonValue>,
+    #[serde(with = "ts_seconds")]
+    created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+impl Object {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn created_by(&self) -> &AgentId {
+        &self.created_by
+    }
+
+    pub fn before(&self) -> Option<&JsonValue> {
+        self.before.as_ref()
+    }
+
+    pub fn after(&self) -> Option<&JsonValue> {
+        self.after.as_ref()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Leaves only the top-level properties which differ between the two objects.
+///
+/// Non-object values are returned as is.
+pub fn diff(before: JsonValue, after: JsonValue) -> (JsonValue, JsonValue) {
+    match (before, after) {
+        (JsonValue::Object(before), JsonValue::Object(mut after)) => {
+            let mut changed_before = JsonMap::new();
+
+            for (key, value) in before {
+                match after.get(&key) {
+                    Some(new_value) if *new_value == value => {
+                        after.remove(&key);
+                    }
+                    _ => {
+                        changed_before.insert(key, value);
+                    }
+                }
+            }
+
+            (JsonValue::Object(changed_before), JsonValue::Object(after))
+        }
+        (before, after) => (before, after),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct InsertQuery<'a> {
+    room_id: db::room::Id,
+    action: &'a str,
+    created_by: &'a AgentId,
+    before: Option<JsonValue>,
+    after: Option<JsonValue>,
+}
+
+impl<'a> InsertQuery<'a> {
+    pub fn new(room_id: db::room::Id, action: &'a str, created_by: &'a AgentId) -> Self {
+        Self {
+            room_id,
+            action,
+            created_by,
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn before(self, before: JsonValue) -> Self {
+        Self {
+            before: Some(before),
+            ..self
+        }
+    }
+
+    pub fn after(self, after: JsonValue) -> Self {
+        Self {
+            after: Some(after),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            INSERT INTO room_audit (room_id, action, created_by, before, after)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id as "id: Id",
+                room_id as "room_id: db::room::Id",
+                action,
+                created_by as "created_by: AgentId",
+                before,
+                after,
+                created_at
+            "#,
+            self.room_id as db::room::Id,
+            self.action,
+            self.created_by as &AgentId,
+            self.before,
+            self.after,
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct ListQuery {
+    room_id: db::room::Id,
+    after: Option<Id>,
+    limit: Option<i64>,
+}
+
+impl ListQuery {
+    pub fn new(room_id: db::room::Id) -> Self {
+        Self {
+            room_id,
+            after: None,
+            limit: None,
+        }
+    }
+
+    /// Keyset pagination cursor: returns entries created after the entry with the given id.
+    pub fn after(self, after: Id) -> Self {
+        Self {
+            after: Some(after),
+            ..self
+        }
+    }
+
+    pub fn limit(self, limit: i64) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                id as "id: Id",
+                room_id as "room_id: db::room::Id",
+                action,
+                created_by as "created_by: AgentId",
+                before,
+                after,
+                created_at
+            FROM room_audit
+            WHERE
+                room_id = $1 AND
+                (
+                    $2::uuid IS NULL OR
+                    (created_at, id) > (SELECT created_at, id FROM room_audit WHERE id = $2)
+                )
+            ORDER BY created_at, id
+            LIMIT $3
+            "#,
+            self.room_id as db::room::Id,
+            self.after as Option<Id>,
+            self.limit,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::diff;
+
+    #[test]
+    fn diff_objects() {
+        let (before, after) = diff(
+            json!({ "time": [1, 2], "locked": false, "tags": {} }),
+            json!({ "time": [1, 3], "locked": false, "tags": {}, "host": "web.user.usr.example.net" }),
+        );
+
+        assert_eq!(before, json!({ "time": [1, 2] }));
+        assert_eq!(
+            after,
+            json!({ "time": [1, 3], "host": "web.user.usr.example.net" })
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use svc_agent::AgentId;
+
+use crate::db;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Object {
+    room_id: db::room::Id,
+    agent_id: AgentId,
+    created_by: AgentId,
+    #[serde(with = "ts_seconds")]
+    created_at: DateTime<Utc>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Finds a ban for the account of the given agent.
+///
+/// Bans are matched by account rather than by agent so that entering the room
+/// with another agent label doesn't bypass them.
+pub struct FindQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+}
+
+impl<'a> FindQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId) -> Self {
+        Self { room_id, agent_id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Option<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                room_id as "room_id: db::id::Id",
+                agent_id as "agent_id: AgentId",
+                created_by as "created_by: AgentId",
+                created_at
+            FROM room_ban
+            WHERE
+                room_id = $1 AND
+                (agent_id).account_id = ($2::agent_id).account_id
+            LIMIT 1
+            "#,
+            self.room_id as db::id::Id,
+            self.agent_id as &AgentId,
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct InsertQuery<'a> {
+    room_id: db::room::Id,
+    agent_id: &'a AgentId,
+    created_by: &'a AgentId,
+}
+
+impl<'a> InsertQuery<'a> {
+    pub fn new(room_id: db::room::Id, agent_id: &'a AgentId, created_by: &'a AgentId) -> Self {
+        Self {
+            room_id,
+            agent_id,
+            created_by,
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            INSERT INTO room_ban (room_id, agent_id, created_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (room_id, agent_id) DO UPDATE
+            SET
+                created_by = EXCLUDED.created_by
+            RETURNING
+                room_id as "room_id: db::id::Id",
+                agent_id as "agent_id: AgentId",
+                created_by as "created_by: AgentId",
+                created_at
+            "#,
+            self.room_id as db::id::Id,
+            self.agent_id as &AgentId,
+            self.created_by as &AgentId,
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FindQuery, InsertQuery};
+    use crate::test_helpers::{db::TestDb, prelude::*};
+
+    #[sqlx::test]
+    async fn find_ban_by_account(pool: sqlx::PgPool) {
+        let db = TestDb::new(pool);
+        let mut conn = db.get_conn().await;
+
+        let host = TestAgent::new("web", "host", USR_AUDIENCE);
+        let banned = TestAgent::new("web", "user123", USR_AUDIENCE);
+        let relabeled = TestAgent::new("mobile", "user123", USR_AUDIENCE);
+        let other = TestAgent::new("web", "user456", USR_AUDIENCE);
+
+        let room = shared_helpers::insert_room(&mut conn).await;
+
+        InsertQuery::new(room.id(), banned.agent_id(), host.agent_id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to insert ban");
+
+        let ban = FindQuery::new(room.id(), relabeled.agent_id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to find ban");
+
+        assert!(ban.is_some());
+
+        let ban = FindQuery::new(room.id(), other.agent_id())
+            .execute(&mut conn)
+            .await
+            .expect("Failed to find ban");
+
+        assert!(ban.is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+
+use crate::db;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct Object {
+    room_id: db::room::Id,
+    threshold: i32,
+    closes_at: DateTime<Utc>,
+}
+
+impl Object {
+    pub fn room_id(&self) -> db::room::Id {
+        self.room_id
+    }
+
+    pub fn threshold(&self) -> i32 {
+        self.threshold
+    }
+
+    pub fn closes_at(&self) -> DateTime<Utc> {
+        self.closes_at
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Marks warnings for open rooms closing within any of the thresholds (in seconds) as sent.
+///
+/// Only the newly marked warnings are returned so concurrent callers never get the same one.
+/// Warnings are bound to the closing time so rescheduling the room makes them due again.
+pub struct MarkDueQuery<'a> {
+    thresholds: &'a [i32],
+}
+
+impl<'a> MarkDueQuery<'a> {
+    pub fn new(thresholds: &'a [i32]) -> Self {
+        Self { thresholds }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Vec<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            INSERT INTO room_closing_warning (room_id, threshold, closes_at)
+            SELECT r.id, t.threshold, UPPER(r.time)
+            FROM room AS r, UNNEST($1::integer[]) AS t(threshold)
+            WHERE
+                r.time && TSTZRANGE(NOW(), NOW() + MAKE_INTERVAL(secs => t.threshold), '(]') AND
+                UPPER(r.time) <= NOW() + MAKE_INTERVAL(secs => t.threshold)
+            ON CONFLICT DO NOTHING
+            RETURNING
+                room_id as "room_id: db::id::Id",
+                threshold,
+                closes_at
+            "#,
+            self.thresholds,
+        )
+        .fetch_all(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Deletes warnings for closing times before the given one.
+///
+/// Rooms can't be due for them anymore since only rooms closing in the future are marked.
+pub struct DeleteExpiredQuery {
+    closes_before: DateTime<Utc>,
+}
+
+impl DeleteExpiredQuery {
+    pub fn new(closes_before: DateTime<Utc>) -> Self {
+        Self { closes_before }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            DELETE FROM room_closing_warning
+            WHERE closes_at < $1
+            "#,
+            self.closes_before,
+        )
+        .execute(conn)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::{Duration, SubsecRound};
+
+    use crate::test_helpers::{db::TestDb, prelude::*};
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn delete_expired(pool: sqlx::PgPool) {
+        let mut conn = TestDb::new(pool).get_conn().await;
+        let now = Utc::now().trunc_subsecs(0);
+
+        factory::Room::new()
+            .audience(USR_AUDIENCE)
+            .time((
+                Bound::Included(now - Duration::hours(1)),
+                Bound::Excluded(now + Duration::seconds(30)),
+            ))
+            .rtc_sharing_policy(db::rtc::SharingPolicy::Shared)
+            .insert(&mut conn)
+            .await;
+
+        let warnings = MarkDueQuery::new(&[60])
+            .execute(&mut conn)
+            .await
+            .expect("Failed to mark warnings");
+
+        assert_eq!(warnings.len(), 1);
+
+        let deleted = DeleteExpiredQuery::new(now)
+            .execute(&mut conn)
+            .await
+            .expect("Failed to delete warnings");
+
+        assert_eq!(deleted, 0);
+
+        let deleted = DeleteExpiredQuery::new(now + Duration::minutes(1))
+            .execute(&mut conn)
+            .await
+            .expect("Failed to delete warnings");
+
+        assert_eq!(deleted, 1);
+    }
+}
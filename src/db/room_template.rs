@@ -0,0 +1,211 @@
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::types::Json;
+use svc_agent::AgentId;
+
+use crate::db::{self, group_agent::Groups, rtc::SharingPolicy as RtcSharingPolicy};
+
+pub type Id = db::id::Id;
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Writer config values applied to every rtc created in a room made from the template.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct WriterConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_video: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_audio: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    video_remb: Option<i64>,
+}
+
+impl WriterConfig {
+    pub fn send_video(&self) -> Option<bool> {
+        self.send_video
+    }
+
+    pub fn send_audio(&self) -> Option<bool> {
+        self.send_audio
+    }
+
+    pub fn video_remb(&self) -> Option<i64> {
+        self.video_remb
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Object {
+    id: Id,
+    audience: String,
+    rtc_sharing_policy: RtcSharingPolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reserve: Option<i32>,
+    tags: JsonValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    groups: Option<Groups>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    writer_config: Option<Json<WriterConfig>>,
+    created_by: AgentId,
+    #[serde(with = "ts_seconds")]
+    created_at: DateTime<Utc>,
+}
+
+impl Object {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+
+    pub fn rtc_sharing_policy(&self) -> RtcSharingPolicy {
+        self.rtc_sharing_policy
+    }
+
+    pub fn reserve(&self) -> Option<i32> {
+        self.reserve
+    }
+
+    pub fn tags(&self) -> &JsonValue {
+        &self.tags
+    }
+
+    pub fn groups(&self) -> Option<&Groups> {
+        self.groups.as_ref()
+    }
+
+    pub fn writer_config(&self) -> Option<WriterConfig> {
+        self.writer_config.as_ref().map(|c| c.0)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct FindQuery {
+    id: Id,
+}
+
+impl FindQuery {
+    pub fn new(id: Id) -> Self {
+        Self { id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Option<Object>> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            SELECT
+                id as "id: Id",
+                audience,
+                rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+                reserve,
+                tags,
+                groups as "groups: Groups",
+                writer_config as "writer_config: Json<WriterConfig>",
+                created_by as "created_by: AgentId",
+                created_at
+            FROM room_template
+            WHERE
+                id = $1
+            "#,
+            self.id as Id,
+        )
+        .fetch_optional(conn)
+        .await
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub struct InsertQuery<'a> {
+    audience: &'a str,
+    rtc_sharing_policy: RtcSharingPolicy,
+    reserve: Option<i32>,
+    tags: Option<&'a JsonValue>,
+    groups: Option<&'a Groups>,
+    writer_config: Option<WriterConfig>,
+    created_by: &'a AgentId,
+}
+
+impl<'a> InsertQuery<'a> {
+    pub fn new(
+        audience: &'a str,
+        rtc_sharing_policy: RtcSharingPolicy,
+        created_by: &'a AgentId,
+    ) -> Self {
+        Self {
+            audience,
+            rtc_sharing_policy,
+            reserve: None,
+            tags: None,
+            groups: None,
+            writer_config: None,
+            created_by,
+        }
+    }
+
+    pub fn reserve(self, reserve: i32) -> Self {
+        Self {
+            reserve: Some(reserve),
+            ..self
+        }
+    }
+
+    pub fn tags(self, tags: &'a JsonValue) -> Self {
+        Self {
+            tags: Some(tags),
+            ..self
+        }
+    }
+
+    pub fn groups(self, groups: &'a Groups) -> Self {
+        Self {
+            groups: Some(groups),
+            ..self
+        }
+    }
+
+    pub fn writer_config(self, writer_config: WriterConfig) -> Self {
+        Self {
+            writer_config: Some(writer_config),
+            ..self
+        }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
+        sqlx::query_as!(
+            Object,
+            r#"
+            INSERT INTO room_template (
+                audience, rtc_sharing_policy, reserve, tags, groups, writer_config, created_by
+            )
+            VALUES ($1, $2, $3, COALESCE($4, '{}'::jsonb), $5, $6, $7)
+            RETURNING
+                id as "id: Id",
+                audience,
+                rtc_sharing_policy as "rtc_sharing_policy: RtcSharingPolicy",
+                reserve,
+                tags,
+                groups as "groups: Groups",
+                writer_config as "writer_config: Json<WriterConfig>",
+                created_by as "created_by: AgentId",
+                created_at
+            "#,
+            self.audience,
+            self.rtc_sharing_policy as RtcSharingPolicy,
+            self.reserve,
+            self.tags,
+            self.groups as Option<&Groups>,
+            self.writer_config.map(Json) as Option<Json<WriterConfig>>,
+            self.created_by as &AgentId,
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
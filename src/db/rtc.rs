@@ -38,6 +38,19 @@ impl fmt::Display for SharingPolicy {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// What the RTC is used for. In rooms with `owned` sharing policy an agent may have
+/// a single RTC of each kind, e.g. to share the screen and the camera at the same time.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, Hash, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "rtc_kind", rename_all = "lowercase")]
+pub enum Kind {
+    #[default]
+    Camera,
+    Screen,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Object {
     pub id: Id,
@@ -45,6 +58,8 @@ pub struct Object {
     #[serde(with = "ts_seconds")]
     pub created_at: DateTime<Utc>,
     pub created_by: AgentId,
+    #[serde(default)]
+    pub kind: Kind,
 }
 
 impl Object {
@@ -59,6 +74,10 @@ impl Object {
     pub fn created_by(&self) -> &AgentId {
         &self.created_by
     }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -80,10 +99,12 @@ impl FindQuery {
                 id as "id: Id",
                 room_id as "room_id: Id",
                 created_at,
-                created_by as "created_by: AgentId"
+                created_by as "created_by: AgentId",
+                kind as "kind: Kind"
             FROM rtc
             WHERE
-                id = $1
+                id = $1 AND
+                deleted_at IS NULL
             "#,
             self.id as Id
         )
@@ -98,6 +119,7 @@ impl FindQuery {
 pub struct ListQuery<'a> {
     room_id: Option<db::room::Id>,
     created_by: Option<&'a [&'a AgentId]>,
+    kind: Option<Kind>,
     offset: Option<i64>,
     limit: Option<i64>,
 }
@@ -121,6 +143,13 @@ impl<'a> ListQuery<'a> {
         }
     }
 
+    pub fn kind(self, kind: Kind) -> Self {
+        Self {
+            kind: Some(kind),
+            ..self
+        }
+    }
+
     pub fn offset(self, offset: i64) -> Self {
         Self {
             offset: Some(offset),
@@ -145,17 +174,21 @@ impl<'a> ListQuery<'a> {
                 id as "id: Id",
                 room_id as "room_id: Id",
                 created_at,
-                created_by as "created_by: AgentId"
+                created_by as "created_by: AgentId",
+                kind as "kind: Kind"
             FROM rtc
             WHERE
                 ($1::uuid IS NULL OR room_id = $1) AND
-                (array_length($2::agent_id[], 1) IS NULL OR created_by = ANY($2))
+                (array_length($2::agent_id[], 1) IS NULL OR created_by = ANY($2)) AND
+                ($3::rtc_kind IS NULL OR kind = $3) AND
+                deleted_at IS NULL
             ORDER BY created_at
-            OFFSET $3
-            LIMIT $4
+            OFFSET $4
+            LIMIT $5
             "#,
             self.room_id as Option<Id>,
             created_by as &[&AgentId],
+            self.kind as Option<Kind>,
             self.offset,
             self.limit
         )
@@ -175,6 +208,7 @@ struct ListWithRecordingRow {
     room_id: db::room::Id,
     created_at: DateTime<Utc>,
     created_by: AgentId,
+    kind: Kind,
     started_at: Option<DateTime<Utc>>,
     segments: Option<Vec<db::recording::SegmentPg>>,
     status: Option<db::recording::Status>,
@@ -189,6 +223,7 @@ impl ListWithRecordingRow {
                 room_id: self.room_id,
                 created_at: self.created_at,
                 created_by: self.created_by,
+                kind: self.kind,
             },
             match self.status {
                 Some(status) => Some(Recording {
@@ -221,6 +256,7 @@ impl ListWithRecordingQuery {
                 rtc.room_id as "room_id: db::room::Id",
                 rtc.created_at,
                 rtc.created_by as "created_by: AgentId",
+                rtc.kind as "kind: Kind",
                 recording.started_at,
                 recording.segments as "segments: Vec<db::recording::SegmentPg>",
                 recording.status as "status?: db::recording::Status",
@@ -245,6 +281,7 @@ impl ListWithRecordingQuery {
 pub struct InsertQuery<'a> {
     room_id: db::room::Id,
     created_by: &'a AgentId,
+    kind: Kind,
 }
 
 impl<'a> InsertQuery<'a> {
@@ -252,25 +289,63 @@ impl<'a> InsertQuery<'a> {
         Self {
             room_id,
             created_by,
+            kind: Kind::Camera,
         }
     }
 
+    pub fn kind(self, kind: Kind) -> Self {
+        Self { kind, ..self }
+    }
+
     pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<Object> {
         sqlx::query_as!(
             Object,
             r#"
-            INSERT INTO rtc (room_id, created_by)
-            VALUES ($1, $2)
+            INSERT INTO rtc (room_id, created_by, kind)
+            VALUES ($1, $2, $3)
             RETURNING
                 id as "id: Id",
                 room_id as "room_id: Id",
                 created_at,
-                created_by as "created_by: AgentId"
+                created_by as "created_by: AgentId",
+                kind as "kind: Kind"
             "#,
             self.room_id as Id,
-            self.created_by as &AgentId
+            self.created_by as &AgentId,
+            self.kind as Kind,
         )
         .fetch_one(conn)
         .await
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Marks the RTC as deleted. The row is kept along with its recording so it
+/// gets uploaded when the room is vacuumed.
+#[derive(Debug)]
+pub struct DeleteQuery {
+    id: Id,
+}
+
+impl DeleteQuery {
+    pub fn new(id: Id) -> Self {
+        Self { id }
+    }
+
+    pub async fn execute(&self, conn: &mut sqlx::PgConnection) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            UPDATE rtc
+            SET deleted_at = NOW()
+            WHERE
+                id = $1 AND
+                deleted_at IS NULL
+            "#,
+            self.id as Id,
+        )
+        .execute(conn)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
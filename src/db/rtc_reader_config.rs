@@ -47,6 +47,7 @@ struct ListWithRtcRow {
     receive_audio: bool,
     room_id: db::room::Id,
     created_by: AgentId,
+    kind: db::rtc::Kind,
     created_at: DateTime<Utc>,
 }
 
@@ -64,6 +65,7 @@ impl ListWithRtcRow {
                 room_id: self.room_id,
                 created_at: self.created_at,
                 created_by: self.created_by,
+                kind: self.kind,
             },
         )
     }
@@ -88,12 +90,14 @@ impl<'a> ListWithRtcQuery<'a> {
                 rrc.receive_audio,
                 rtc.room_id as "room_id: db::room::Id",
                 rtc.created_by as "created_by: AgentId",
+                rtc.kind as "kind: db::rtc::Kind",
                 rtc.created_at
             FROM rtc_reader_config as rrc
             INNER JOIN rtc
             ON rrc.rtc_id = rtc.id
             WHERE
                 rtc.room_id = $1 AND
+                rtc.deleted_at IS NULL AND
                 rrc.reader_id = ANY($2)
             "#,
             self.room_id as db::room::Id,
@@ -56,6 +56,7 @@ struct ListWithRtcRow {
     room_id: db::room::Id,
     created_at: DateTime<Utc>,
     created_by: AgentId,
+    kind: db::rtc::Kind,
 }
 
 impl ListWithRtcRow {
@@ -74,6 +75,7 @@ impl ListWithRtcRow {
                 room_id: self.room_id,
                 created_at: self.created_at,
                 created_by: self.created_by,
+                kind: self.kind,
             },
         )
     }
@@ -97,12 +99,14 @@ impl ListWithRtcQuery {
                 rwc.updated_at,
                 r.room_id as "room_id: db::room::Id",
                 r.created_at,
-                r.created_by as "created_by: AgentId"
+                r.created_by as "created_by: AgentId",
+                r.kind as "kind: db::rtc::Kind"
             FROM rtc_writer_config as rwc
             INNER JOIN rtc as r
             ON rwc.rtc_id = r.id
             WHERE
-                r.room_id = $1
+                r.room_id = $1 AND
+                r.deleted_at IS NULL
             "#,
             self.room_id as db::room::Id,
         )
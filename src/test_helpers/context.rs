@@ -22,7 +22,7 @@ use crate::{
         context::{Context, GlobalContext, MessageContext},
         metrics::Metrics,
     },
-    backend::janus::{client::IncomingEvent, client_pool::Clients},
+    backend::janus::{balancer::Balancer, client::IncomingEvent, client_pool::Clients},
     client::{
         conference::ConferenceHttpClient, mqtt::MqttClient, mqtt_gateway::MqttGatewayHttpClient,
     },
@@ -162,6 +162,11 @@ impl TestContext {
                 .method(httpmock::Method::POST);
             then.status(200);
         });
+        let _subscriptions_delete_mock = mock_server.mock(|when, then| {
+            when.path("/api/v1/subscriptions")
+                .method(httpmock::Method::DELETE);
+            then.status(200);
+        });
 
         let config = build_config(&mock_server);
         let agent_id = AgentId::new(&config.agent_label, config.id.clone());
@@ -189,6 +194,7 @@ impl TestContext {
             WAITLIST_DURATION,
             IpAddr::V4(Ipv4Addr::LOCALHOST),
             None,
+            Balancer::new(&self.config.balancer, None),
         ));
     }
 
@@ -200,6 +206,7 @@ impl TestContext {
             WAITLIST_DURATION,
             IpAddr::V4(Ipv4Addr::LOCALHOST),
             None,
+            Balancer::new(&self.config.balancer, Some(group.to_string())),
         ));
     }
 
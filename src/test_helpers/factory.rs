@@ -4,7 +4,7 @@ use svc_agent::{AccountId, AgentId};
 
 use crate::{
     backend::janus::client::{HandleId, SessionId},
-    db::{self, agent, group_agent::Groups, room::Id},
+    db::{self, agent, group_agent::Groups, room::FindQueryable, room::Id},
 };
 
 use super::{
@@ -21,6 +21,7 @@ pub struct Room<'a> {
     backend_id: Option<&'a AgentId>,
     reserve: Option<i32>,
     infinite: bool,
+    lobby: bool,
 }
 
 impl<'a> Room<'a> {
@@ -32,6 +33,7 @@ impl<'a> Room<'a> {
             backend_id: None,
             reserve: None,
             infinite: false,
+            lobby: false,
         }
     }
 
@@ -77,6 +79,13 @@ impl<'a> Room<'a> {
         }
     }
 
+    pub fn lobby(self) -> Self {
+        Self {
+            lobby: true,
+            ..self
+        }
+    }
+
     pub async fn insert(self, conn: &mut sqlx::PgConnection) -> db::room::Object {
         let audience = self.audience.expect("Audience not set");
         let time = self.time.expect("Time not set");
@@ -100,6 +109,10 @@ impl<'a> Room<'a> {
             q = q.infinite(true);
         }
 
+        if self.lobby {
+            q = q.lobby(true);
+        }
+
         q.execute(conn).await.expect("Failed to insert room")
     }
 }
@@ -220,6 +233,7 @@ impl AgentConnection {
 pub struct Rtc {
     room_id: db::room::Id,
     created_by: AgentId,
+    kind: db::rtc::Kind,
 }
 
 impl Rtc {
@@ -227,6 +241,7 @@ impl Rtc {
         Self {
             room_id,
             created_by: AgentId::new("web", AccountId::new("nevermind", "example.com")),
+            kind: db::rtc::Kind::Camera,
         }
     }
 
@@ -234,8 +249,13 @@ impl Rtc {
         Self { created_by, ..self }
     }
 
+    pub fn kind(self, kind: db::rtc::Kind) -> Self {
+        Self { kind, ..self }
+    }
+
     pub async fn insert(&self, conn: &mut sqlx::PgConnection) -> db::rtc::Object {
         db::rtc::InsertQuery::new(self.room_id, &self.created_by)
+            .kind(self.kind)
             .execute(conn)
             .await
             .expect("Failed to insert janus_backend")
@@ -251,6 +271,7 @@ pub struct JanusBackend {
     capacity: Option<i32>,
     balancer_capacity: Option<i32>,
     group: Option<String>,
+    region: Option<String>,
     janus_url: String,
 }
 
@@ -263,6 +284,7 @@ impl JanusBackend {
             capacity: None,
             balancer_capacity: None,
             group: None,
+            region: None,
             janus_url,
         }
     }
@@ -288,6 +310,13 @@ impl JanusBackend {
         }
     }
 
+    pub fn region(self, region: &str) -> Self {
+        Self {
+            region: Some(region.to_owned()),
+            ..self
+        }
+    }
+
     pub async fn insert(&self, conn: &mut sqlx::PgConnection) -> db::janus_backend::Object {
         let mut q = db::janus_backend::UpsertQuery::new(
             &self.id,
@@ -308,6 +337,10 @@ impl JanusBackend {
             q = q.group(group);
         }
 
+        if let Some(ref region) = self.region {
+            q = q.region(region);
+        }
+
         q.execute(conn)
             .await
             .expect("Failed to insert janus_backend")
@@ -333,6 +366,20 @@ impl<'a> JanusRtcStream<'a> {
         }
     }
 
+    pub fn backend(self, backend: &'a db::janus_backend::Object) -> Self {
+        Self {
+            backend: Some(backend),
+            ..self
+        }
+    }
+
+    pub fn rtc(self, rtc: &'a db::rtc::Object) -> Self {
+        Self {
+            rtc: Some(rtc),
+            ..self
+        }
+    }
+
     pub async fn insert(&self, conn: &mut sqlx::PgConnection) -> db::janus_rtc_stream::Object {
         let default_backend;
 
@@ -407,10 +454,26 @@ impl<'a> Recording<'a> {
             }
         };
 
-        db::recording::InsertQuery::new(rtc.id())
+        let recording = db::recording::InsertQuery::new(rtc.id())
+            .execute(conn)
+            .await
+            .expect("Failed to insert recording");
+
+        // The part is on the backend the room is bound to.
+        let room = db::room::FindQuery::new(rtc.room_id())
             .execute(conn)
             .await
-            .expect("Failed to insert recording")
+            .expect("Failed to find room")
+            .expect("Room not found");
+
+        if let Some(backend_id) = room.backend_id() {
+            db::recording::InsertPartQuery::new(rtc.id(), backend_id)
+                .execute(conn)
+                .await
+                .expect("Failed to insert recording part");
+        }
+
+        recording
     }
 }
 
@@ -61,7 +61,7 @@ pub async fn handle_event<H: EventHandler>(
     Ok(parse_messages(messages).await)
 }
 
-async fn parse_messages(mut messages: MessageStream) -> Vec<OutgoingEnvelope> {
+pub async fn parse_messages(mut messages: MessageStream) -> Vec<OutgoingEnvelope> {
     let mut parsed_messages = vec![];
 
     while let Some(message) = messages.next().await {
@@ -8,7 +8,7 @@ use crate::{
     backend::janus::client::{
         create_handle::CreateHandleRequest,
         service_ping::{ServicePingRequest, ServicePingRequestBody},
-        HandleId, JanusClient, SessionId,
+        HandleId, JanusClient, SessionId, Transport,
     },
     db::{
         self,
@@ -130,7 +130,7 @@ pub async fn insert_connected_agent(
 }
 
 pub async fn create_handle(janus_url: &str, session_id: SessionId) -> HandleId {
-    JanusClient::new(janus_url)
+    JanusClient::new(janus_url, Transport::Http)
         .unwrap()
         .create_handle(CreateHandleRequest {
             session_id,
@@ -142,7 +142,7 @@ pub async fn create_handle(janus_url: &str, session_id: SessionId) -> HandleId {
 }
 
 pub async fn init_janus(janus_url: &str) -> (SessionId, HandleId) {
-    let janus_client = JanusClient::new(janus_url).unwrap();
+    let janus_client = JanusClient::new(janus_url, Transport::Http).unwrap();
     let session_id = janus_client.create_session().await.unwrap().id;
     let handle_id = janus_client
         .create_handle(CreateHandleRequest {